@@ -0,0 +1,261 @@
+/**
+ * Rust-Hexo 站内搜索
+ *
+ * 读取 `search.path` 生成的索引（文章列表或倒排索引），配置来自 script 标签的 data 属性：
+ *   data-index    索引地址
+ *   data-root     站点根路径，用于拼接分片地址
+ *   data-limit    最多显示的结果数
+ *   data-highlight / data-live / data-preview-length
+ *
+ * 页面中的 `[data-search-input]`（或 `#search-input`）作为输入框，结果写入
+ * `[data-search-results]`（或 `#search-results`）。每次搜索都会在 document 上触发
+ * `rust-hexo:search` 事件，detail 为 `{ query, results }`，调用 preventDefault() 可以自行渲染结果。
+ */
+(function () {
+    'use strict';
+    
+    const script = document.currentScript;
+    const dataset = script ? script.dataset : {};
+    const config = {
+        index: dataset.index || '/search/search.json',
+        root: (dataset.root || '/').replace(/\/?$/, '/'),
+        limit: parseInt(dataset.limit || '10', 10),
+        highlight: dataset.highlight !== 'false',
+        live: dataset.live !== 'false',
+        previewLength: parseInt(dataset.previewLength || '200', 10),
+        weights: { title: 10, tags: 5, content: 1 },
+    };
+    
+    let index = null;
+    const shards = new Map();
+    
+    // 与 core::search::tokenize 相同的分词规则
+    function isCjk(code) {
+        return (code >= 0x4E00 && code <= 0x9FFF) || (code >= 0x3400 && code <= 0x4DBF) ||
+            (code >= 0x20000 && code <= 0x2A6DF) || (code >= 0xF900 && code <= 0xFAFF) ||
+            (code >= 0x3040 && code <= 0x30FF) || (code >= 0xAC00 && code <= 0xD7AF);
+    }
+    
+    function undouble(base) {
+        const n = base.length;
+        if (n >= 2 && base[n - 1] === base[n - 2] && !'aeiouylsz'.includes(base[n - 1])) {
+            return base.slice(0, -1);
+        }
+        return base;
+    }
+    
+    // 与 core::search::stem 相同的词干提取规则
+    function stem(word) {
+        if (word.length <= 3 || !/^[a-z]+$/.test(word)) return word;
+        
+        let stemmed;
+        if (word.endsWith('ies') && word.length - 3 >= 2) {
+            stemmed = word.slice(0, -3) + 'y';
+        } else if (word.endsWith('sses')) {
+            stemmed = word.slice(0, -2);
+        } else if (word.endsWith('ss') || word.endsWith('us') || word.endsWith('is')) {
+            stemmed = word;
+        } else if (word.endsWith('ing') && word.length - 3 >= 3) {
+            stemmed = undouble(word.slice(0, -3));
+        } else if (word.endsWith('ed') && word.length - 2 >= 3) {
+            stemmed = undouble(word.slice(0, -2));
+        } else if (word.endsWith('es') && ['sh', 'ch', 'x', 'z', 'o'].some(end => word.slice(0, -2).endsWith(end))) {
+            stemmed = word.slice(0, -2);
+        } else if (word.endsWith('s')) {
+            stemmed = word.slice(0, -1);
+        } else {
+            stemmed = word;
+        }
+        
+        if (stemmed.endsWith('e')) {
+            const base = stemmed.slice(0, -1);
+            if (base.length >= 3 && !base.endsWith('e')) return base;
+        }
+        return stemmed;
+    }
+    
+    function tokenize(text) {
+        const tokens = [];
+        let word = '';
+        let cjk = [];
+        const pushWord = () => {
+            if (Array.from(word).length > 1 || /\p{N}/u.test(word)) tokens.push(stem(word));
+            word = '';
+        };
+        const pushCjk = () => {
+            if (cjk.length === 1) tokens.push(cjk[0]);
+            for (let i = 0; i + 1 < cjk.length; i++) tokens.push(cjk[i] + cjk[i + 1]);
+            cjk = [];
+        };
+        
+        for (const c of text.toLowerCase()) {
+            if (isCjk(c.codePointAt(0))) {
+                pushWord();
+                cjk.push(c);
+            } else if (/[\p{L}\p{N}]/u.test(c)) {
+                pushCjk();
+                word += c;
+            } else {
+                pushWord();
+                pushCjk();
+            }
+        }
+        pushWord();
+        pushCjk();
+        return tokens;
+    }
+    
+    async function fetchJson(url) {
+        const response = await fetch(url);
+        if (!response.ok) throw new Error(`加载搜索索引失败: ${url} (${response.status})`);
+        return response.json();
+    }
+    
+    async function load() {
+        if (!index) index = await fetchJson(config.index);
+        return index;
+    }
+    
+    async function shard(key) {
+        if (!shards.has(key)) {
+            const path = index.shards[key];
+            shards.set(key, path ? fetchJson(config.root + path) : Promise.resolve({}));
+        }
+        return shards.get(key);
+    }
+    
+    // 倒排索引：只加载查询词条所在的分片，返回包含所有词条的文章
+    async function searchInverted(tokens) {
+        const scores = new Map();
+        for (const token of tokens) {
+            const postings = (await shard(token.codePointAt(0).toString(16)))[token] || [];
+            for (const [doc, score] of postings) {
+                const entry = scores.get(doc) || { count: 0, score: 0 };
+                entry.count += 1;
+                entry.score += score;
+                scores.set(doc, entry);
+            }
+        }
+        return Array.from(scores)
+            .filter(([, entry]) => entry.count === tokens.length)
+            .map(([doc, entry]) => ({ ...index.documents[doc], score: entry.score }));
+    }
+    
+    // 文章列表：逐篇匹配标题、标签和内容
+    function searchList(tokens) {
+        return index.map(item => {
+            const title = tokenize(item.title || '');
+            const tags = tokenize([...(item.tags || []), ...(item.categories || [])].join(' '));
+            const content = tokenize(item.content || '');
+            let score = 0;
+            for (const token of tokens) {
+                const found = (title.includes(token) ? config.weights.title : 0) +
+                    (tags.includes(token) ? config.weights.tags : 0) +
+                    (content.includes(token) ? config.weights.content : 0);
+                if (!found) return null;
+                score += found;
+            }
+            return {
+                title: item.title,
+                path: item.path,
+                url: config.root + item.path.replace(/index\.html$/, ''),
+                date: item.date,
+                categories: item.categories || [],
+                tags: item.tags || [],
+                excerpt: (item.content || '').replace(/<[^>]*>/g, ''),
+                score,
+            };
+        }).filter(Boolean);
+    }
+    
+    /** 搜索，返回按得分降序排列的结果 */
+    async function search(query) {
+        const tokens = Array.from(new Set(tokenize(query)));
+        if (tokens.length === 0) return [];
+        await load();
+        const results = Array.isArray(index) ? searchList(tokens) : await searchInverted(tokens);
+        return results.sort((a, b) => b.score - a.score).slice(0, config.limit);
+    }
+    
+    function escapeHtml(text) {
+        return String(text).replace(/[&<>"']/g, c => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' })[c]);
+    }
+    
+    /** 转义文本并用 <mark> 标出查询中的词 */
+    function highlight(text, query) {
+        const escaped = escapeHtml(text || '');
+        const words = query.toLowerCase().split(/[^\p{L}\p{N}]+/u).filter(Boolean)
+            .map(word => escapeHtml(word).replace(/[.*+?^${}()|[\]\\]/g, '\\$&'));
+        if (!config.highlight || words.length === 0) return escaped;
+        return escaped.replace(new RegExp(`(${words.join('|')})`, 'giu'), '<mark>$1</mark>');
+    }
+    
+    function preview(text) {
+        return text.length > config.previewLength ? text.slice(0, config.previewLength) + '...' : text;
+    }
+    
+    function render(container, results, query) {
+        if (results.length === 0) {
+            container.innerHTML = query ? '<div class="no-results">未找到相关文章</div>' : '';
+            return;
+        }
+        container.innerHTML = results.map(item => `
+            <div class="search-result">
+                <h3 class="result-title"><a href="${escapeHtml(item.url)}">${highlight(item.title, query)}</a></h3>
+                <div class="result-meta">
+                    <span class="date">${escapeHtml(item.date)}</span>
+                    ${item.categories.length ? `<span class="categories">${item.categories.map(escapeHtml).join(', ')}</span>` : ''}
+                    ${item.tags.length ? `<span class="tags">${item.tags.map(escapeHtml).join(', ')}</span>` : ''}
+                </div>
+                <div class="result-preview">${highlight(preview(item.excerpt || ''), query)}</div>
+            </div>`).join('');
+    }
+    
+    async function run(input, container) {
+        const query = input.value.trim();
+        let results = [];
+        try {
+            results = query ? await search(query) : [];
+        } catch (error) {
+            console.error(error);
+            if (container) container.innerHTML = '<div class="search-error">搜索时发生错误，请稍后重试</div>';
+            return;
+        }
+        
+        const event = new CustomEvent('rust-hexo:search', { detail: { query, results, input }, cancelable: true });
+        if (document.dispatchEvent(event) && container) {
+            render(container, results, query);
+        }
+    }
+    
+    function bind() {
+        const input = document.querySelector('[data-search-input]') || document.getElementById('search-input');
+        if (!input) return;
+        const container = document.querySelector('[data-search-results]') || document.getElementById('search-results');
+        
+        let timer = null;
+        if (config.live) {
+            input.addEventListener('input', () => {
+                clearTimeout(timer);
+                timer = setTimeout(() => run(input, container), 200);
+            });
+        }
+        input.addEventListener('keydown', event => {
+            if (event.key === 'Enter') run(input, container);
+        });
+        
+        const query = new URLSearchParams(window.location.search).get('q');
+        if (query) {
+            input.value = query;
+            run(input, container);
+        }
+    }
+    
+    window.RustHexoSearch = { config, search, tokenize, highlight };
+    
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', bind);
+    } else {
+        bind();
+    }
+})();
@@ -218,7 +218,4 @@ impl Plugin for CommentsPlugin {
     }
 }
 
-#[no_mangle]
-pub extern "C" fn create_plugin() -> Box<dyn Plugin> {
-    Box::new(CommentsPlugin::new())
-} 
\ No newline at end of file
+rust_hexo::declare_plugin!(CommentsPlugin, CommentsPlugin::new); 
\ No newline at end of file
@@ -290,8 +290,5 @@ impl Plugin for MathPlugin {
     }
 }
 
-// 创建插件实例的外部函数
-#[no_mangle]
-pub extern "C" fn create_plugin() -> *mut dyn Plugin {
-    Box::into_raw(Box::new(MathPlugin::new()))
-} 
\ No newline at end of file
+// 导出插件，供 rust-hexo 加载
+rust_hexo::declare_plugin!(MathPlugin, MathPlugin::new); 
\ No newline at end of file
@@ -243,7 +243,4 @@ impl Plugin for SyntaxHighlightPlugin {
     }
 }
 
-#[no_mangle]
-pub extern "C" fn create_plugin() -> Box<dyn Plugin> {
-    Box::new(SyntaxHighlightPlugin::new())
-} 
\ No newline at end of file
+rust_hexo::declare_plugin!(SyntaxHighlightPlugin, SyntaxHighlightPlugin::new); 
\ No newline at end of file
@@ -1,5 +1,6 @@
 use rust_hexo::plugins::{Plugin, PluginContext, PluginHook, ContentType};
-use anyhow::Result;
+use rust_hexo::theme::engine::TemplateFunction;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use semver::Version;
 use tracing::{debug, info};
@@ -66,13 +67,13 @@ impl WordCountPlugin {
     }
     
     /// 字数统计函数，提供给模板系统
-    fn word_count_function(&self, args: &HashMap<String, serde_json::Value>) -> Result<serde_json::Value, tera::Error> {
+    fn word_count_function(&self, args: &HashMap<String, serde_json::Value>) -> Result<serde_json::Value> {
         let content = match args.get("content") {
             Some(val) => match val.as_str() {
                 Some(s) => s,
-                None => return Err(tera::Error::msg("无法将content转换为字符串")),
+                None => return Err(anyhow!("无法将content转换为字符串")),
             },
-            None => return Err(tera::Error::msg("缺少必要的参数: content")),
+            None => return Err(anyhow!("缺少必要的参数: content")),
         };
 
         // 计算英文单词和中文字符
@@ -82,13 +83,13 @@ impl WordCountPlugin {
     }
     
     /// 估计阅读时间函数，提供给模板系统
-    fn reading_time_function(&self, args: &HashMap<String, serde_json::Value>) -> Result<serde_json::Value, tera::Error> {
+    fn reading_time_function(&self, args: &HashMap<String, serde_json::Value>) -> Result<serde_json::Value> {
         let content = match args.get("content") {
             Some(val) => match val.as_str() {
                 Some(s) => s,
-                None => return Err(tera::Error::msg("无法将content转换为字符串")),
+                None => return Err(anyhow!("无法将content转换为字符串")),
             },
-            None => return Err(tera::Error::msg("缺少必要的参数: content")),
+            None => return Err(anyhow!("缺少必要的参数: content")),
         };
 
         // 计算字数
@@ -168,7 +169,7 @@ impl Plugin for WordCountPlugin {
         Ok(result)
     }
     
-    fn get_template_functions(&self) -> HashMap<String, Box<dyn Fn(&HashMap<String, serde_json::Value>) -> Result<serde_json::Value, tera::Error> + Send + Sync>> {
+    fn get_template_functions(&self) -> HashMap<String, TemplateFunction> {
         let mut functions = HashMap::new();
         
         // 创建一个插件的引用用于在闭包中使用
@@ -176,17 +177,17 @@ impl Plugin for WordCountPlugin {
         
         // 添加word_count函数
         let word_count_plugin = Arc::clone(&plugin);
-        let word_count_fn = Box::new(move |args: &HashMap<String, serde_json::Value>| {
+        let word_count_fn: TemplateFunction = Arc::new(move |args: &HashMap<String, serde_json::Value>| {
             word_count_plugin.word_count_function(args)
-        }) as Box<dyn Fn(&HashMap<String, serde_json::Value>) -> Result<serde_json::Value, tera::Error> + Send + Sync>;
+        });
         
         functions.insert("word_count".to_string(), word_count_fn);
         
         // 添加reading_time函数
         let reading_time_plugin = Arc::clone(&plugin);
-        let reading_time_fn = Box::new(move |args: &HashMap<String, serde_json::Value>| {
+        let reading_time_fn: TemplateFunction = Arc::new(move |args: &HashMap<String, serde_json::Value>| {
             reading_time_plugin.reading_time_function(args)
-        }) as Box<dyn Fn(&HashMap<String, serde_json::Value>) -> Result<serde_json::Value, tera::Error> + Send + Sync>;
+        });
         
         functions.insert("reading_time".to_string(), reading_time_fn);
         
@@ -227,8 +228,5 @@ impl ChineseChar for char {
     }
 }
 
-/// 创建插件实例
-#[no_mangle]
-pub extern "C" fn create_plugin() -> Box<dyn Plugin> {
-    Box::new(WordCountPlugin::new())
-}
\ No newline at end of file
+// 导出插件，供 rust-hexo 加载
+rust_hexo::declare_plugin!(WordCountPlugin, WordCountPlugin::new);
\ No newline at end of file
@@ -1,7 +1,10 @@
 use crate::core::Engine;
+use crate::theme::embedded;
+use crate::theme::manager::{self, ThemeInfo};
+use crate::theme::validate::validate_theme;
 use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs::{self, File};
 use std::io::Write;
 use tracing::info;
@@ -39,6 +42,9 @@ pub enum Commands {
     
     /// 插件管理
     Plugin(PluginArgs),
+    
+    /// 主题管理
+    Theme(ThemeArgs),
 }
 
 #[derive(Args)]
@@ -86,6 +92,10 @@ pub struct ServerArgs {
     /// 监视文件变化并自动重新生成
     #[arg(short, long)]
     pub watch: bool,
+    
+    /// 不提供 /__api/ 下的开发接口（搜索、文章和标签列表）
+    #[arg(long)]
+    pub no_api: bool,
 }
 
 #[derive(Args)]
@@ -104,6 +114,66 @@ pub enum PluginCommands {
     List,
 }
 
+#[derive(Args)]
+pub struct ThemeArgs {
+    #[command(subcommand)]
+    command: ThemeCommands,
+}
+
+#[derive(Subcommand)]
+pub enum ThemeCommands {
+    /// 列出已安装的主题
+    List,
+    /// 从本地目录、压缩包或 git 仓库安装主题
+    Install(InstallArgs),
+    /// 创建新主题的骨架
+    New(NewThemeArgs),
+    /// 检查主题模板是否齐全、能否编译以及引用的变量
+    Validate(ValidateArgs),
+    /// 将内置默认主题写入 themes/ 目录以便修改
+    Eject(EjectArgs),
+}
+
+#[derive(Args)]
+pub struct InstallArgs {
+    /// 主题目录、压缩包（.tar.gz 等）或 git 仓库地址，本地 git 仓库按仓库克隆
+    #[arg(value_name = "SOURCE")]
+    pub source: String,
+    
+    /// 主题名称，默认根据来源推断
+    #[arg(short, long)]
+    pub name: Option<String>,
+    
+    /// 替换已存在的同名主题
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct NewThemeArgs {
+    /// 主题名称
+    #[arg(value_name = "NAME")]
+    pub name: String,
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// 主题名称，默认为站点配置的主题
+    #[arg(value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(Args)]
+pub struct EjectArgs {
+    /// 主题目录名称，默认为站点配置的主题
+    #[arg(value_name = "NAME")]
+    pub name: Option<String>,
+    
+    /// 覆盖已存在的主题目录中的同名文件
+    #[arg(short, long)]
+    pub force: bool,
+}
+
 // 嵌入的默认配置模板
 const DEFAULT_CONFIG_TEMPLATE: &str = r#"# 站点信息
 title: {title}
@@ -158,6 +228,19 @@ time_format: HH:mm:ss
 per_page: 10
 pagination_dir: page
 
+# 列表页面配置，per_page 覆盖全局设置（0 表示不分页）
+index_generator:
+  per_page: 10
+category_generator:
+  per_page: 10
+tag_generator:
+  per_page: 10
+archive_generator:
+  per_page: 10
+  yearly: true
+  monthly: true
+  daily: false
+
 # 主题配置
 theme: default
 
@@ -178,37 +261,18 @@ search:
   format: html
 "#;
 
-// 嵌入的默认主题文件
-mod default_theme {
-    // 主题CSS文件
-    pub const STYLE_CSS: &str = include_str!("../../embed/theme/default/source/css/style.css");
-    
-    // 主题布局文件
-    pub const LAYOUT_HTML: &str = include_str!("../../embed/theme/default/layout/layout.html");
-    pub const INDEX_HTML: &str = include_str!("../../embed/theme/default/layout/index.html");
-    pub const POST_HTML: &str = include_str!("../../embed/theme/default/layout/post.html");
-    pub const CATEGORY_HTML: &str = include_str!("../../embed/theme/default/layout/category.html");
-    pub const TAG_HTML: &str = include_str!("../../embed/theme/default/layout/tag.html");
-}
-
 // 初始化网站文件结构，包括创建默认主题和示例文件
 fn initialize_site_structure(site_path: &PathBuf, site_title: &str) -> Result<()> {
     // 创建目录结构
     let source_dir = site_path.join("source");
     let posts_dir = source_dir.join("_posts");
-    let theme_dir = site_path.join("themes").join("default");
-    let theme_layout_dir = theme_dir.join("layout");
-    let theme_source_dir = theme_dir.join("source");
-    let theme_css_dir = theme_source_dir.join("css");
-    let theme_js_dir = theme_source_dir.join("js");
-    let theme_images_dir = theme_source_dir.join("images");
+    let theme_dir = site_path.join("themes").join(embedded::EMBEDDED_THEME_NAME);
     let scaffolds_dir = site_path.join("scaffolds");
     let plugins_dir = site_path.join("plugins");
 
     // 创建所有必要的目录
     for dir in &[
-        &source_dir, &posts_dir, &theme_dir, &theme_layout_dir, &theme_source_dir,
-        &theme_css_dir, &theme_js_dir, &theme_images_dir, &scaffolds_dir, &plugins_dir
+        &source_dir, &posts_dir, &scaffolds_dir, &plugins_dir
     ] {
         fs::create_dir_all(dir)?;
     }
@@ -218,12 +282,7 @@ fn initialize_site_structure(site_path: &PathBuf, site_title: &str) -> Result<()
     fs::write(site_path.join("_config.yml"), config_content)?;
 
     // 创建默认主题文件
-    fs::write(theme_css_dir.join("style.css"), default_theme::STYLE_CSS)?;
-    fs::write(theme_layout_dir.join("layout.html"), default_theme::LAYOUT_HTML)?;
-    fs::write(theme_layout_dir.join("index.html"), default_theme::INDEX_HTML)?;
-    fs::write(theme_layout_dir.join("post.html"), default_theme::POST_HTML)?;
-    fs::write(theme_layout_dir.join("category.html"), default_theme::CATEGORY_HTML)?;
-    fs::write(theme_layout_dir.join("tag.html"), default_theme::TAG_HTML)?;
+    embedded::eject(&theme_dir, true)?;
 
     // 创建示例博文
     let hello_post = posts_dir.join("hello-world.md");
@@ -287,6 +346,54 @@ date: {{ date }}
     Ok(())
 }
 
+/// 输出一行主题信息，当前使用的主题以 `*` 标记
+fn print_theme(theme: &ThemeInfo, active: bool) {
+    let mut line = format!("{} {}", if active { "*" } else { " " }, theme.name);
+    if let Some(version) = &theme.version {
+        line.push_str(&format!(" v{}", version));
+    }
+    line.push_str(&format!(" [{}]", theme.engine));
+    if let Some(parent) = &theme.extends {
+        line.push_str(&format!(" 继承 {}", parent));
+    }
+    if let Some(author) = &theme.author {
+        line.push_str(&format!(" 作者: {}", author));
+    }
+    println!("{}", line);
+    if let Some(description) = &theme.description {
+        println!("    {}", description);
+    }
+}
+
+/// 验证主题并输出结果，返回主题是否可用
+fn print_validation(theme_dir: &Path) -> Result<bool> {
+    let report = validate_theme(theme_dir)?;
+    println!("主题 {}（{} 模板）", theme_dir.display(), report.engine);
+    
+    for name in &report.missing_required {
+        println!("  错误: 缺少模板 {}", name);
+    }
+    for error in &report.errors {
+        println!("  错误: {}", error);
+    }
+    for name in &report.missing_optional {
+        println!("  提示: 缺少模板 {}，将使用内置页面", name);
+    }
+    match &report.unknown_variables {
+        Some(unknown) => {
+            for (template, variables) in unknown {
+                println!("  警告: {} 引用了生成器不提供的变量: {}", template, variables.join(", "));
+            }
+        }
+        None => println!("  提示: {} 模板不支持检查引用的变量", report.engine),
+    }
+    
+    if report.is_valid() {
+        println!("主题验证通过");
+    }
+    Ok(report.is_valid())
+}
+
 /// 执行命令
 pub async fn execute(cli: Cli) -> Result<()> {
     let site_path = cli.path.clone();
@@ -369,7 +476,7 @@ pub async fn execute(cli: Cli) -> Result<()> {
             }
             
             // 启动服务器
-            engine.server(args.port).await?;
+            engine.server(args.port, !args.no_api).await?;
             
             // 等待用户中断
             tokio::signal::ctrl_c().await?;
@@ -417,6 +524,43 @@ pub async fn execute(cli: Cli) -> Result<()> {
                 }
             }
         }
+        Commands::Theme(args) => {
+            let themes_dir = engine.base_dir.join("themes");
+            match args.command {
+                ThemeCommands::List => {
+                    let active = engine.config.theme.clone().unwrap_or_else(|| embedded::EMBEDDED_THEME_NAME.to_string());
+                    let themes = manager::list_themes(&themes_dir)?;
+                    println!("已安装的主题:");
+                    for theme in &themes {
+                        print_theme(theme, theme.name == active);
+                    }
+                    if !themes.iter().any(|theme| theme.name == active) {
+                        println!("* {} (内置)", active);
+                    }
+                }
+                ThemeCommands::Install(args) => {
+                    let theme_dir = manager::install_theme(&themes_dir, &args.source, args.name.as_deref(), args.force)?;
+                    println!("主题已安装: {}", theme_dir.display());
+                }
+                ThemeCommands::New(args) => {
+                    let theme_dir = manager::new_theme(&themes_dir, &args.name)?;
+                    println!("主题已创建: {}", theme_dir.display());
+                }
+                ThemeCommands::Validate(args) => {
+                    let theme_dir = match args.name {
+                        Some(name) => themes_dir.join(name),
+                        None => engine.theme_dir.clone(),
+                    };
+                    if !print_validation(&theme_dir)? {
+                        return Err(anyhow::anyhow!("主题验证失败: {}", theme_dir.display()));
+                    }
+                }
+                ThemeCommands::Eject(args) => {
+                    let theme_dir = engine.eject_theme(args.name.as_deref(), args.force)?;
+                    println!("内置主题已写入: {}", theme_dir.display());
+                }
+            }
+        }
     }
     
     Ok(())
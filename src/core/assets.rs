@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::models::config::AssetConfig;
+
+/// 待处理的资源文件
+#[derive(Debug, Clone)]
+pub struct AssetFile {
+    /// 相对于资源目录的路径，以 `/` 分隔
+    pub path: String,
+    pub content: Vec<u8>,
+    /// 磁盘上的源文件，Sass 从其所在目录查找引用的文件；内置主题的资源没有源文件
+    pub source: Option<PathBuf>,
+}
+
+/// 处理后的资源文件
+#[derive(Debug, Clone)]
+pub struct ProcessedAsset {
+    /// 模板中引用的路径，如 `style.scss` 编译后为 `css/style.css`
+    pub path: String,
+    /// 输出路径，开启指纹时包含内容哈希
+    pub output_path: String,
+    pub content: Vec<u8>,
+}
+
+/// 主题资源处理：编译 Sass、压缩 CSS 和 JS、在文件名中加入内容哈希
+pub struct AssetPipeline {
+    config: AssetConfig,
+}
+
+impl AssetPipeline {
+    pub fn new(config: AssetConfig) -> Self {
+        Self { config }
+    }
+    
+    /// 处理所有资源，返回处理后的文件和资源清单（原路径到带哈希的路径，未开启指纹时为空）
+    pub fn process_all(&self, files: &[AssetFile]) -> Result<(Vec<ProcessedAsset>, HashMap<String, String>)> {
+        let mut processed = Vec::new();
+        let mut manifest = HashMap::new();
+        
+        for file in files {
+            let Some(asset) = self.process(file)? else {
+                continue;
+            };
+            if asset.output_path != asset.path {
+                manifest.insert(asset.path.clone(), asset.output_path.clone());
+            }
+            processed.push(asset);
+        }
+        
+        Ok((processed, manifest))
+    }
+    
+    /// 资源在模板中引用的路径，Sass 文件编译后扩展名变为 `css`
+    pub fn target_path(&self, path: &str) -> String {
+        if self.is_sass(path) {
+            replace_extension(path, "css")
+        } else {
+            path.to_string()
+        }
+    }
+    
+    /// 是否为需要编译的 Sass 文件
+    pub fn is_sass(&self, path: &str) -> bool {
+        self.config.sass && matches!(extension(path), "scss" | "sass")
+    }
+    
+    /// 是否为 Sass 局部文件（以 `_` 开头），局部文件只被其他样式表引用，不单独输出
+    pub fn is_sass_partial(&self, path: &str) -> bool {
+        self.is_sass(path) && file_name(path).starts_with('_')
+    }
+    
+    /// 处理单个资源，Sass 局部文件不单独输出，返回 `None`
+    pub fn process(&self, file: &AssetFile) -> Result<Option<ProcessedAsset>> {
+        let source_ext = extension(&file.path);
+        let (path, content) = match source_ext {
+            _ if self.is_sass_partial(&file.path) => return Ok(None),
+            "scss" | "sass" if self.config.sass => {
+                let css = self.compile_sass(file, source_ext == "sass")?;
+                (self.target_path(&file.path), css.into_bytes())
+            }
+            "css" if self.config.minify_css => (file.path.clone(), Self::minify_css(file)),
+            "js" if self.config.minify_js && !file.path.ends_with(".min.js") => (file.path.clone(), Self::minify_js(file)),
+            _ => (file.path.clone(), file.content.clone()),
+        };
+        
+        let output_path = if self.config.fingerprint && self.config.fingerprint_extensions.iter().any(|ext| ext == extension(&path)) {
+            fingerprint(&path, &content)
+        } else {
+            path.clone()
+        };
+        
+        Ok(Some(ProcessedAsset { path, output_path, content }))
+    }
+    
+    fn compile_sass(&self, file: &AssetFile, indented: bool) -> Result<String> {
+        let source = String::from_utf8_lossy(&file.content).into_owned();
+        let style = if self.config.minify_css { grass::OutputStyle::Compressed } else { grass::OutputStyle::Expanded };
+        let syntax = if indented { grass::InputSyntax::Sass } else { grass::InputSyntax::Scss };
+        let mut options = grass::Options::default().style(style).input_syntax(syntax);
+        if let Some(dir) = file.source.as_deref().and_then(Path::parent) {
+            options = options.load_path(dir);
+        }
+        
+        debug!("编译 Sass: {}", file.path);
+        grass::from_string(source, &options).map_err(|e| anyhow!("编译 Sass 失败: {}: {}", file.path, e))
+    }
+    
+    /// 压缩 CSS，无法解析时保留原内容
+    fn minify_css(file: &AssetFile) -> Vec<u8> {
+        let source = String::from_utf8_lossy(&file.content).into_owned();
+        let options = grass::Options::default()
+            .style(grass::OutputStyle::Compressed)
+            .input_syntax(grass::InputSyntax::Css);
+        match grass::from_string(source, &options) {
+            Ok(css) => css.into_bytes(),
+            Err(e) => {
+                warn!("压缩 CSS 失败，保留原文件: {}: {}", file.path, e);
+                file.content.clone()
+            }
+        }
+    }
+    
+    /// 压缩 JS，无法解析时保留原内容
+    fn minify_js(file: &AssetFile) -> Vec<u8> {
+        let session = minify_js::Session::new();
+        let mut output = Vec::new();
+        match minify_js::minify(&session, minify_js::TopLevelMode::Global, &file.content, &mut output) {
+            Ok(()) => output,
+            Err(e) => {
+                warn!("压缩 JS 失败，保留原文件: {}: {:?}", file.path, e);
+                file.content.clone()
+            }
+        }
+    }
+}
+
+/// 在扩展名前加入内容哈希，如 `css/style.css` 变为 `css/style.3fa9c1.css`
+fn fingerprint(path: &str, content: &[u8]) -> String {
+    let hash = Sha256::digest(content);
+    let hash: String = hash.iter().take(3).map(|byte| format!("{:02x}", byte)).collect();
+    let ext = extension(path);
+    if ext.is_empty() {
+        return format!("{}.{}", path, hash);
+    }
+    format!("{}.{}.{}", &path[..path.len() - ext.len() - 1], hash, ext)
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn extension(path: &str) -> &str {
+    file_name(path).rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default()
+}
+
+fn replace_extension(path: &str, new_ext: &str) -> String {
+    let ext = extension(path);
+    format!("{}.{}", &path[..path.len() - ext.len() - 1], new_ext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn asset(path: &str, content: &str) -> AssetFile {
+        AssetFile { path: path.to_string(), content: content.as_bytes().to_vec(), source: None }
+    }
+    
+    #[test]
+    fn test_asset_pipeline() {
+        let pipeline = AssetPipeline::new(AssetConfig {
+            minify_css: true,
+            minify_js: true,
+            fingerprint: true,
+            ..AssetConfig::default()
+        });
+        let files = [
+            asset("css/style.scss", "$color: red;\nbody {\n  a { color: $color; }\n}\n"),
+            asset("css/_vars.scss", "$size: 1px;"),
+            asset("js/app.js", "function add(first, second) {\n  return first + second;\n}\n"),
+            asset("images/logo.svg", "<svg/>"),
+        ];
+        
+        let (processed, manifest) = pipeline.process_all(&files).unwrap();
+        assert_eq!(processed.len(), 3);
+        
+        let css = &processed[0];
+        assert_eq!(css.path, "css/style.css");
+        assert_eq!(String::from_utf8_lossy(&css.content), "body a{color:red}");
+        assert_eq!(css.output_path, fingerprint("css/style.css", &css.content));
+        assert_eq!(manifest["css/style.css"], css.output_path);
+        
+        assert!(processed[1].content.len() < files[2].content.len());
+        assert_eq!(processed[2].output_path, "images/logo.svg");
+        assert!(!manifest.contains_key("images/logo.svg"));
+        
+        assert_eq!(fingerprint("css/style.css", b"body{}").len(), "css/style.000000.css".len());
+    }
+}
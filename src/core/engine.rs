@@ -2,29 +2,32 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::any::Any;
+use std::time::Instant;
 
 use anyhow::{Context as AnyhowContext, Result};
 use chrono::{Utc, TimeZone};
 use colored::Colorize;
 use gray_matter::Matter;
 use gray_matter::engine::YAML;
-use notify::{Config as NotifyConfig, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use rayon::prelude::*;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
-use yaml_rust2::{YamlLoader, Yaml};
 use serde_yaml::{Mapping, Value};
 use gray_matter::Pod;
-use pulldown_cmark::{html, Options, Parser};
 use slug;
 
 use crate::models::config::Config;
 use crate::models::{Category, Page, Post, SiteConfig, Tag};
 use crate::plugins::{PluginManager, PluginHook, PluginContext, ContentType};
+use crate::theme::embedded;
+use crate::theme::inheritance::resolve_theme_chain;
 use crate::theme::renderer::ThemeRenderer;
 use crate::core::generator::HtmlGenerator;
+use crate::core::images::ImagePipeline;
+use crate::core::rebuild::ChangeSet;
+use crate::utils::url::UrlHelper;
 
 /// Hexo引擎的核心实现
 #[derive(Clone)]
@@ -45,6 +48,10 @@ pub struct Engine {
     pub theme_config: HashMap<String, Value>,
     /// 所有文章
     pub posts: Arc<RwLock<Vec<Post>>>,
+    /// 开发接口读取的站点配置，重新加载配置时同步更新
+    shared_config: Arc<RwLock<Config>>,
+    /// 文章或配置每次变化后递增，开发接口据此判断缓存的搜索索引是否过期
+    revision: Arc<AtomicU64>,
     /// 所有页面
     pub pages: Arc<RwLock<Vec<Page>>>,
     /// 所有分类
@@ -55,8 +62,8 @@ pub struct Engine {
     is_watching: Arc<RwLock<bool>>,
     /// 插件管理器
     pub plugin_manager: PluginManager,
-    /// 主题渲染器
-    theme_renderer: Option<ThemeRenderer>,
+    /// 主题渲染器，所有页面共享同一套模板和模板函数
+    theme_renderer: Option<Arc<ThemeRenderer>>,
     /// 文件监视器
     file_watcher: Arc<RwLock<Option<Box<dyn Any + Send + Sync>>>>,
 }
@@ -73,7 +80,6 @@ impl Engine {
         // 创建必要的目录
         let source_dir = base_dir.join("source");
         let public_dir = base_dir.join("public");
-        let theme_dir = base_dir.join("themes").join("default");
         let scaffold_dir = base_dir.join("scaffolds");
 
         // 检查必要目录是否存在，如果不存在且不是在initialize_site_structure之后，再创建
@@ -95,6 +101,16 @@ impl Engine {
             config
         };
         
+        // 主题目录及主题配置
+        let theme_dir = Self::resolve_theme_dir(&base_dir, &config);
+        let theme_config = match Self::load_theme_config(&theme_dir, &config) {
+            Ok(theme_config) => theme_config,
+            Err(e) => {
+                warn!("加载主题配置失败: {}", e);
+                HashMap::new()
+            }
+        };
+        
         // 克隆base_dir以便在后续使用
         let base_dir_clone = base_dir.clone();
         
@@ -104,9 +120,11 @@ impl Engine {
             public_dir,
             theme_dir,
             scaffold_dir,
+            shared_config: Arc::new(RwLock::new(config.clone())),
             config,
-            theme_config: HashMap::new(),
+            theme_config,
             posts: Arc::new(RwLock::new(Vec::new())),
+            revision: Arc::new(AtomicU64::new(0)),
             pages: Arc::new(RwLock::new(Vec::new())),
             categories: Arc::new(RwLock::new(Vec::new())),
             tags: Arc::new(RwLock::new(Vec::new())),
@@ -117,6 +135,18 @@ impl Engine {
         })
     }
     
+    /// 将内置默认主题写入 `themes/<name>`，未指定名称时使用站点配置的主题，返回主题目录
+    pub fn eject_theme(&self, name: Option<&str>, force: bool) -> Result<PathBuf> {
+        let theme_dir = match name {
+            Some(name) => self.base_dir.join("themes").join(name),
+            None => self.theme_dir.clone(),
+        };
+        
+        let count = embedded::eject(&theme_dir, force)?;
+        info!("已写入内置主题的 {} 个文件到 {}", count, theme_dir.display());
+        Ok(theme_dir)
+    }
+    
     /// 创建插件上下文
     fn create_plugin_context(&self) -> PluginContext {
         info!("创建插件上下文...");
@@ -149,26 +179,37 @@ impl Engine {
         }
     }
     
+    /// 根据站点配置确定主题目录
+    fn resolve_theme_dir(base_dir: &Path, config: &Config) -> PathBuf {
+        let theme = config.theme.clone().unwrap_or_else(|| "default".to_string());
+        base_dir.join("themes").join(theme)
+    }
+    
     /// 加载主题配置
     fn load_theme_config(theme_dir: &Path, site_config: &Config) -> Result<HashMap<String, Value>> {
-        let theme_config_path = theme_dir.join("_config.yml");
         let mut theme_config = HashMap::new();
         
-        if theme_config_path.exists() {
+        // 从父主题到子主题依次合并，子主题的配置覆盖父主题的默认值
+        let theme_dirs = if theme_dir.exists() { resolve_theme_chain(theme_dir)? } else { Vec::new() };
+        for dir in theme_dirs.iter().rev() {
+            let theme_config_path = dir.join("_config.yml");
+            if !theme_config_path.exists() {
+                continue;
+            }
+            
             let config_str = std::fs::read_to_string(&theme_config_path)
                 .with_context(|| format!("Failed to read theme config file: {}", theme_config_path.display()))?;
             
-            theme_config = serde_yaml::from_str(&config_str)
-                .with_context(|| "Failed to parse theme _config.yml")?;
+            let config: Option<HashMap<String, Value>> = serde_yaml::from_str(&config_str)
+                .with_context(|| format!("Failed to parse theme config file: {}", theme_config_path.display()))?;
+            theme_config.extend(config.unwrap_or_default());
         }
         
         // 合并站点配置中的主题配置
-        if let Some(site_theme_config) = &site_config.theme_config {
-            if let Value::Mapping(mapping) = site_theme_config {
-                for (key, value) in mapping {
-                    if let Some(key_str) = key.as_str() {
-                        theme_config.insert(key_str.to_string(), value.clone());
-                    }
+        if let Some(Value::Mapping(mapping)) = &site_config.theme_config {
+            for (key, value) in mapping {
+                if let Some(key_str) = key.as_str() {
+                    theme_config.insert(key_str.to_string(), value.clone());
                 }
             }
         }
@@ -194,7 +235,16 @@ impl Engine {
         self.plugin_manager.init()?;
         
         // 初始化主题渲染器
+        self.build_theme_renderer()?;
+        
+        info!("{}", "Initialization complete.".green());
+        Ok(())
+    }
+    
+    /// 根据当前配置构建主题渲染器
+    fn build_theme_renderer(&mut self) -> Result<()> {
         let mut theme_renderer = ThemeRenderer::new(&self.base_dir, self.config.clone())?;
+        theme_renderer.set_override_dirs(self.layout_override_dirs())?;
         
         // 将插件功能注册到主题渲染器
         if let Err(e) = self.plugin_manager.register_to_theme_renderer(&mut theme_renderer) {
@@ -203,12 +253,32 @@ impl Engine {
         }
         
         // 保存主题渲染器
-        self.theme_renderer = Some(theme_renderer);
-        
-        info!("{}", "Initialization complete.".green());
+        self.theme_renderer = Some(Arc::new(theme_renderer));
         Ok(())
     }
     
+    /// 站点级模板覆盖目录，按优先级从低到高排列：`layout/`、`source/_layouts/`
+    fn layout_override_dirs(&self) -> Vec<PathBuf> {
+        vec![self.base_dir.join("layout"), self.source_dir.join("_layouts")]
+    }
+    
+    /// 获取主题渲染器，尚未构建时先构建
+    fn theme_renderer(&mut self) -> Result<Arc<ThemeRenderer>> {
+        if self.theme_renderer.is_none() {
+            self.build_theme_renderer()?;
+        }
+        self.theme_renderer.clone()
+            .ok_or_else(|| anyhow::anyhow!("主题渲染器未初始化"))
+    }
+    
+    /// 重新加载主题模板，用于主题文件变化后
+    fn reload_templates(&mut self) -> Result<()> {
+        match self.theme_renderer.as_mut() {
+            Some(renderer) => Arc::make_mut(renderer).reload_templates(),
+            None => self.build_theme_renderer(),
+        }
+    }
+    
     /// 处理内容
     fn process_content(&self, content: &str, content_type: ContentType) -> String {
         // 使用插件处理内容
@@ -232,16 +302,37 @@ impl Engine {
     }
     
     /// 加载配置文件
+    ///
+    /// 重新读取站点配置和主题配置，并通知插件配置已变更。
+    /// 配置解析失败时保留之前的配置并返回错误。
     pub fn load_config(&mut self) -> Result<()> {
         let config_path = self.base_dir.join("_config.yml");
-        let config_str = std::fs::read_to_string(&config_path)?;
-        let config: Config = serde_yaml::from_str(&config_str)?;
-        self.config = config.clone();
+        let config = Config::load(&config_path)
+            .with_context(|| format!("解析配置文件失败: {}", config_path.display()))?;
+        
+        let theme_dir = Self::resolve_theme_dir(&self.base_dir, &config);
+        let theme_config = Self::load_theme_config(&theme_dir, &config)?;
+        
+        *self.shared_config.write().unwrap() = config.clone();
+        self.config = config;
+        self.theme_dir = theme_dir;
+        self.theme_config = theme_config;
+        self.mark_changed();
         
         // 更新插件上下文中的配置
         let plugin_context = self.create_plugin_context();
         self.plugin_manager.set_context(plugin_context);
         
+        // 调用插件钩子：配置变更
+        if let Err(e) = self.plugin_manager.execute_hook(&PluginHook::ConfigChanged) {
+            warn!("执行配置变更钩子失败: {}", e);
+        }
+        
+        // 使用新配置重建主题渲染器
+        if let Err(e) = self.build_theme_renderer() {
+            warn!("重建主题渲染器失败: {}", e);
+        }
+        
         Ok(())
     }
     
@@ -299,120 +390,20 @@ impl Engine {
                     continue;
                 }
                 
-                // 读取文件内容
-                let content = std::fs::read_to_string(path)?;
-                
-                // 使用插件处理Markdown内容
-                let processed_content = self.process_markdown(&content);
-                
-                // 解析 Front Matter
-                let result = matter.parse(&processed_content);
-                
-                // 获取 YAML 数据
-                let yaml_data = if let Some(data) = result.data {
-                    data
-                } else {
-                    continue;
-                };
-                
-                // 获取标题
-                let title = if let Ok(title) = yaml_data["title"].as_string() {
-                    title
-                } else {
-                    // 如果没有标题，使用文件名
-                    path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("Untitled")
-                        .to_string()
-                };
-                
-                // 解析日期
-                let date = if let Ok(date_str) = yaml_data["date"].as_string() {
-                    match chrono::DateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S %z") {
-                        Ok(dt) => dt.with_timezone(&Utc),
-                        Err(_) => {
-                            // 尝试另一种格式
-                            match chrono::NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S") {
-                                Ok(dt) => Utc.from_utc_datetime(&dt),
-                                Err(_) => Utc::now(), // 如果无法解析，使用当前时间
-                            }
-                        }
-                    }
-                } else {
-                    // 如果没有日期，使用文件的修改时间
-                    let metadata = std::fs::metadata(path)?;
-                    let modified = metadata.modified()?;
-                    let system_time: chrono::DateTime<Utc> = modified.into();
-                    system_time
-                };
-                
-                // 创建前置数据的HashMap
-                let mut front_matter = HashMap::new();
-                if let Ok(hash) = yaml_data.as_hashmap() {
-                    for (k, v) in hash {
-                        let value = match v {
-                            Pod::String(s) => Value::String(s),
-                            Pod::Integer(i) => Value::Number(serde_yaml::Number::from(i)),
-                            Pod::Float(f) => Value::Number(serde_yaml::Number::from(f)),
-                            Pod::Boolean(b) => Value::Bool(b),
-                            Pod::Array(arr) => Value::Sequence(arr.into_iter().map(pod_to_value).collect()),
-                            Pod::Hash(map) => {
-                                let mut yaml_map = Mapping::new();
-                                for (map_k, map_v) in map {
-                                    yaml_map.insert(Value::String(map_k), pod_to_value(map_v));
-                                }
-                                Value::Mapping(yaml_map)
-                            },
-                            Pod::Null => Value::Null,
-                        };
-                        front_matter.insert(k, value);
-                    }
+                if let Some(post) = self.load_post(path, &matter)? {
+                    found_posts.push(post);
                 }
-                
-                // 创建文章的URL路径
-                let filename = path.file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                
-                let url_path = format!("posts/{}.html", filename);
-                
-                // 将Markdown转换为HTML
-                let html_content = crate::utils::markdown::render(&result.content)?;
-                
-                // 使用插件处理HTML内容
-                let final_content = self.process_html(&html_content);
-                
-                // 创建新的文章对象
-                let post = Post {
-                    title,
-                    date,
-                    updated: Some(date), // 默认使用相同的时间
-                    comments: true,
-                    layout: if let Ok(hash) = yaml_data.as_hashmap() { if let Some(layout_pod) = hash.get("layout") { if let Ok(layout) = layout_pod.as_string() { layout } else { "post".to_string() } } else { "post".to_string() } } else { "post".to_string() },
-                    content: html_content,  // 使用已经渲染好的HTML内容
-                    rendered_content: Some(final_content),  // 存储处理后的内容
-                    source: path.to_path_buf(),
-                    path: format!("posts/{}.html", filename),
-                    permalink: url_path,
-                    excerpt: None, // TODO: 实现摘要提取
-                    url: None,
-                    categories: Vec::new(), // 稍后处理
-                    tags: Vec::new(),       // 稍后处理
-                    front_matter,
-                };
-                
-                found_posts.push(post);
             }
         }
         
         // 更新文章列表
         if !found_posts.is_empty() {
             // 按日期排序
-            found_posts.sort_by(|a, b| b.date.cmp(&a.date));
+            found_posts.sort_by_key(|p| std::cmp::Reverse(p.date));
             
             let mut posts = self.posts.write().unwrap();
             *posts = found_posts;
+            self.mark_changed();
             
             info!("加载了 {} 篇文章", posts.len());
         }
@@ -422,11 +413,129 @@ impl Engine {
         Ok(())
     }
     
+    /// 加载单篇文章
+    ///
+    /// 没有 Front Matter 的文件会被忽略，返回 `None`。
+    fn load_post(&self, path: &Path, matter: &Matter<YAML>) -> Result<Option<Post>> {
+        // 读取文件内容
+        let content = std::fs::read_to_string(path)?;
+        
+        // 使用插件处理Markdown内容
+        let processed_content = self.process_markdown(&content);
+        
+        // 解析 Front Matter
+        let result = matter.parse(&processed_content);
+        
+        // 获取 YAML 数据
+        let yaml_data = if let Some(data) = result.data {
+            data
+        } else {
+            return Ok(None);
+        };
+        
+        // 获取标题
+        let title = if let Ok(title) = yaml_data["title"].as_string() {
+            title
+        } else {
+            // 如果没有标题，使用文件名
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled")
+                .to_string()
+        };
+        
+        // 解析日期
+        let date = if let Ok(date_str) = yaml_data["date"].as_string() {
+            match chrono::DateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S %z") {
+                Ok(dt) => dt.with_timezone(&Utc),
+                Err(_) => {
+                    // 尝试另一种格式
+                    match chrono::NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S") {
+                        Ok(dt) => Utc.from_utc_datetime(&dt),
+                        Err(_) => Utc::now(), // 如果无法解析，使用当前时间
+                    }
+                }
+            }
+        } else {
+            // 如果没有日期，使用文件的修改时间
+            let metadata = std::fs::metadata(path)?;
+            let modified = metadata.modified()?;
+            let system_time: chrono::DateTime<Utc> = modified.into();
+            system_time
+        };
+        
+        // 创建前置数据的HashMap
+        let mut front_matter = HashMap::new();
+        if let Ok(hash) = yaml_data.as_hashmap() {
+            for (k, v) in hash {
+                let value = match v {
+                    Pod::String(s) => Value::String(s),
+                    Pod::Integer(i) => Value::Number(serde_yaml::Number::from(i)),
+                    Pod::Float(f) => Value::Number(serde_yaml::Number::from(f)),
+                    Pod::Boolean(b) => Value::Bool(b),
+                    Pod::Array(arr) => Value::Sequence(arr.into_iter().map(pod_to_value).collect()),
+                    Pod::Hash(map) => {
+                        let mut yaml_map = Mapping::new();
+                        for (map_k, map_v) in map {
+                            yaml_map.insert(Value::String(map_k), pod_to_value(map_v));
+                        }
+                        Value::Mapping(yaml_map)
+                    },
+                    Pod::Null => Value::Null,
+                };
+                front_matter.insert(k, value);
+            }
+        }
+        
+        // 布局：`layout: false` 表示不使用模板，未指定时使用默认布局
+        let layout = match yaml_data.as_hashmap().ok().and_then(|hash| hash.get("layout").cloned()) {
+            Some(Pod::Boolean(false)) => Post::NO_LAYOUT.to_string(),
+            Some(Pod::String(layout)) if !layout.is_empty() => layout,
+            _ => self.config.default_layout.clone().unwrap_or_else(|| "post".to_string()),
+        };
+        
+        // 创建文章的URL路径
+        let filename = path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        
+        let url_path = format!("posts/{}.html", filename);
+        
+        // 将Markdown转换为HTML
+        let html_content = crate::utils::markdown::render(&result.content)?;
+        
+        // 使用插件处理HTML内容
+        let final_content = self.process_html(&html_content);
+        
+        // 创建新的文章对象
+        let post = Post {
+            title,
+            date,
+            updated: Some(date), // 默认使用相同的时间
+            comments: true,
+            layout,
+            content: html_content,  // 使用已经渲染好的HTML内容
+            rendered_content: Some(final_content),  // 存储处理后的内容
+            source: path.to_path_buf(),
+            path: format!("posts/{}.html", filename),
+            permalink: url_path,
+            excerpt: None, // TODO: 实现摘要提取
+            url: None,
+            categories: Vec::new(), // 稍后处理
+            tags: Vec::new(),       // 稍后处理
+            front_matter,
+        };
+        
+        Ok(Some(post))
+    }
+    
     /// 处理分类和标签
     fn process_categories_and_tags(&self) -> Result<()> {
         info!("处理分类和标签...");
         
         let mut posts = self.posts.write().unwrap();
+        self.mark_changed();
         let mut categories_map = HashMap::new();
         let mut tags_map = HashMap::new();
         
@@ -493,11 +602,11 @@ impl Engine {
         // 更新分类列表
         let categories: Vec<Category> = categories_map.into_iter()
             .map(|(name, posts)| {
-                let slug = slug::slugify(&name);
+                let slug = UrlHelper::slug(&name);
                 Category {
                     name: name.clone(),
+                    path: format!("categories/{}", slug),
                     slug,
-                    path: format!("categories/{}", name.to_lowercase()),
                     parent: None,
                     post_count: posts.len(),
                 }
@@ -507,11 +616,11 @@ impl Engine {
         // 更新标签列表
         let tags: Vec<Tag> = tags_map.into_iter()
             .map(|(name, posts)| {
-                let slug = slug::slugify(&name);
+                let slug = UrlHelper::slug(&name);
                 Tag {
                     name: name.clone(),
+                    path: format!("tags/{}", slug),
                     slug,
-                    path: format!("tags/{}", name.to_lowercase()),
                     post_count: posts.len(),
                 }
             })
@@ -617,19 +726,216 @@ impl Engine {
         
         // 调用HTML生成器，使用已初始化的插件管理器
         info!("创建HTML生成器，使用已初始化的插件管理器");
-        let generator = HtmlGenerator::new_with_plugin_manager(
+        let renderer = self.theme_renderer()?;
+        renderer.set_posts(&posts);
+        renderer.clear_partial_cache();
+        let mut generator = HtmlGenerator::new(
             output_dir,
             self.config.clone(),
-            self.plugin_manager.clone()
+            self.plugin_manager.clone(),
+            renderer.clone(),
         );
+        generator.set_image_pipeline(self.image_pipeline());
+        generator.set_source_dir(self.source_dir.clone());
         
         // 生成HTML文件
         generator.generate(&posts)?;
         
+        Self::report_partial_cache(&renderer);
         info!("静态网站生成完成");
         Ok(())
     }
     
+    /// 根据监视到的文件变化重新生成
+    ///
+    /// 配置、布局或无法归类的变化会触发完整重新生成；其余变化只重新渲染受影响的页面。
+    pub fn rebuild(&mut self, changes: &ChangeSet) -> Result<()> {
+        let start = Instant::now();
+        let public_dir = self.public_dir.clone();
+        
+        // 模板变化后重新加载主题模板，无法归类的变化可能来自父主题
+        if !changes.templates.is_empty() || !changes.others.is_empty() {
+            self.reload_templates()?;
+        }
+        
+        if changes.requires_full_rebuild() {
+            info!("完整重新生成 ({})", changes.summary());
+            self.generate(&public_dir)?;
+            info!("重新生成完成: 全部页面, 耗时 {}ms", start.elapsed().as_millis());
+            return Ok(());
+        }
+        
+        let mut generator = HtmlGenerator::new(
+            public_dir.clone(),
+            self.config.clone(),
+            self.plugin_manager.clone(),
+            self.theme_renderer()?,
+        );
+        generator.set_image_pipeline(self.image_pipeline());
+        generator.set_source_dir(self.source_dir.clone());
+        let mut rebuilt = Vec::new();
+        
+        // 只重新处理变化的主题资源；带哈希的文件名变化后所有页面都需要重新生成
+        if !changes.assets.is_empty() || !changes.removed_assets.is_empty() {
+            let changed: Vec<String> = changes.assets.iter()
+                .chain(&changes.removed_assets)
+                .map(|asset| asset.to_string_lossy().replace('\\', "/"))
+                .collect();
+            if generator.process_changed_assets(&changed)? {
+                info!("资源指纹变化，完整重新生成");
+                self.generate(&public_dir)?;
+                info!("重新生成完成: 全部页面, 耗时 {}ms", start.elapsed().as_millis());
+                return Ok(());
+            }
+            rebuilt.extend(changes.assets.iter().map(|asset| asset.display().to_string()));
+            rebuilt.extend(changes.removed_assets.iter().map(|asset| format!("删除 {}", asset.display())));
+        }
+        
+        // 更新变化的文章，并删除已删除文章的输出
+        if !changes.posts.is_empty() || !changes.removed_posts.is_empty() {
+            let old_categories: Vec<String> = self.categories.read().unwrap().iter().map(|c| c.name.clone()).collect();
+            let old_tags: Vec<String> = self.tags.read().unwrap().iter().map(|t| t.name.clone()).collect();
+            
+            for output in self.update_posts(changes)? {
+                let output_path = public_dir.join(&output);
+                if output_path.exists() {
+                    fs::remove_file(&output_path)?;
+                    rebuilt.push(format!("删除 {}", output));
+                }
+            }
+            self.process_categories_and_tags()?;
+            self.plugin_manager.set_context(self.create_plugin_context());
+            
+            // 删除不再包含文章的分类和标签页面
+            let categories = self.categories.read().unwrap().clone();
+            for name in old_categories.iter().filter(|n| !categories.iter().any(|c| &c.name == *n)) {
+                let dir = generator.category_output_dir(name);
+                if dir.exists() {
+                    fs::remove_dir_all(&dir)?;
+                    rebuilt.push(format!("删除分类 {}", name));
+                }
+            }
+            let tags = self.tags.read().unwrap().clone();
+            for name in old_tags.iter().filter(|n| !tags.iter().any(|t| &t.name == *n)) {
+                let dir = generator.tag_output_dir(name);
+                if dir.exists() {
+                    fs::remove_dir_all(&dir)?;
+                    rebuilt.push(format!("删除标签 {}", name));
+                }
+            }
+        }
+        
+        let posts = self.posts.read().unwrap().clone();
+        let renderer = self.theme_renderer()?;
+        renderer.set_posts(&posts);
+        renderer.clear_partial_cache();
+        let has_post_changes = !changes.posts.is_empty() || !changes.removed_posts.is_empty();
+        
+        if has_post_changes {
+            // 变化的文章可能引用了新的图片
+            generator.process_images(&posts)?;
+            
+            // 重新渲染变化的文章
+            for post in posts.iter().filter(|p| changes.posts.contains(&p.source)) {
+                generator.generate_post(post)?;
+                rebuilt.push(post.path.clone());
+            }
+            
+            // 文章列表发生变化，重新生成依赖它的页面
+            generator.generate_lists(&posts)?;
+            rebuilt.push("列表页面".to_string());
+        }
+        
+        // 重新渲染使用变化模板的页面
+        for template in &changes.templates {
+            match template.as_str() {
+                // 列表页面和搜索页面已随文章变化重新生成
+                "index.html" | "category.html" | "categories.html" | "tag.html" | "tags.html" | "archive.html" | "search.html" if has_post_changes => {},
+                "index.html" => generator.generate_index(&posts)?,
+                "category.html" | "categories.html" => generator.generate_categories(&posts)?,
+                "tag.html" | "tags.html" => generator.generate_tags(&posts)?,
+                "archive.html" => generator.generate_archives(&posts)?,
+                "search.html" => generator.generate_search_page()?,
+                name => {
+                    let layout = name.trim_end_matches(".html");
+                    // post.html 是所有文章的后备模板
+                    for post in posts.iter().filter(|p| layout == "post" || p.layout == layout) {
+                        generator.generate_post(post)?;
+                    }
+                }
+            }
+            rebuilt.push(format!("模板 {}", template));
+        }
+        
+        Self::report_partial_cache(&renderer);
+        info!("重新生成完成: {}, 耗时 {}ms", rebuilt.join(", "), start.elapsed().as_millis());
+        Ok(())
+    }
+    
+    /// 文章图片处理，缓存目录相对于站点目录
+    fn image_pipeline(&self) -> ImagePipeline {
+        let config = self.config.images.clone().unwrap_or_default();
+        let cache_dir = self.base_dir.join(&config.cache_dir);
+        ImagePipeline::new(config, self.source_dir.clone(), cache_dir)
+    }
+    
+    /// 输出本次生成中局部模板缓存的命中情况
+    fn report_partial_cache(renderer: &ThemeRenderer) {
+        let hits = renderer.partial_cache_hits();
+        if hits.is_empty() {
+            return;
+        }
+        
+        let total: usize = hits.values().sum();
+        let details: Vec<String> = hits.iter().map(|(name, count)| format!("{} {} 次", name, count)).collect();
+        info!("局部模板缓存命中 {} 次: {}", total, details.join(", "));
+    }
+    
+    /// 根据变化重新加载文章，返回需要删除的文章输出路径
+    fn update_posts(&self, changes: &ChangeSet) -> Result<Vec<String>> {
+        let matter = Matter::<YAML>::new();
+        let mut removed_outputs = Vec::new();
+        
+        // 先在锁外解析变化的文章，避免插件处理内容时持有锁
+        let mut loaded = Vec::new();
+        for path in &changes.posts {
+            loaded.push((path.clone(), self.load_post(path, &matter)?));
+        }
+        
+        let mut posts = self.posts.write().unwrap();
+        
+        // 删除的文章
+        posts.retain(|p| {
+            if changes.removed_posts.contains(&p.source) {
+                removed_outputs.push(p.path.clone());
+                false
+            } else {
+                true
+            }
+        });
+        
+        // 新增或修改的文章
+        for (path, post) in loaded {
+            let existing = posts.iter().position(|p| p.source == path);
+            match (existing, post) {
+                (Some(idx), Some(post)) => {
+                    if posts[idx].path != post.path {
+                        removed_outputs.push(posts[idx].path.clone());
+                    }
+                    posts[idx] = post;
+                },
+                (None, Some(post)) => posts.push(post),
+                // Front Matter 被移除，文章不再有效
+                (Some(idx), None) => removed_outputs.push(posts.remove(idx).path),
+                (None, None) => {},
+            }
+        }
+        
+        posts.sort_by_key(|p| std::cmp::Reverse(p.date));
+        self.mark_changed();
+        Ok(removed_outputs)
+    }
+    
     /// 复制静态文件（如CSS和JS）到输出目录
     fn copy_static_files(&self) -> Result<()> {
         let static_dir = self.source_dir.join("static");
@@ -789,13 +1095,22 @@ impl Engine {
         use std::sync::mpsc;
         use std::time::Duration;
         
-        info!("创建文件监视器，基础目录: {:?}", self.base_dir);
+        // 创建一个引擎的克隆，用于生成
+        // notify 报告的事件路径是绝对路径，统一使用绝对路径以便归类变化
+        let mut engine = self.clone();
+        engine.use_absolute_paths();
+        
+        // 使用绝对路径重新加载文章，使文章的源文件路径与事件路径一致
+        engine.load_posts_and_pages()?;
+        engine.process_categories_and_tags()?;
+        
+        info!("创建文件监视器，基础目录: {:?}", engine.base_dir);
         
         // 创建通道以接收文件系统事件
         let (tx, rx) = mpsc::channel();
         
         // 创建一个监视器，使用明确的配置
-        let mut watcher_config = Config::default();
+        let watcher_config = Config::default();
         // 注意：notify 6.x 版本的 poll_interval 不接受参数
         // 使用推荐的自动配置
         
@@ -828,23 +1143,52 @@ impl Engine {
         };
         
         // 监视源目录和所有子目录
-        info!("正在监控目录: {:?}", self.source_dir);
-        match watcher.watch(&self.source_dir, RecursiveMode::Recursive) {
+        info!("正在监控目录: {:?}", engine.source_dir);
+        match watcher.watch(&engine.source_dir, RecursiveMode::Recursive) {
             Ok(_) => info!("成功添加源目录到监控"),
             Err(e) => error!("监控源目录失败: {:?}", e),
         }
         
         // 如果主题目录存在，也监视它
-        if self.theme_dir.exists() {
-            info!("正在监控主题目录: {:?}", self.theme_dir);
-            match watcher.watch(&self.theme_dir, RecursiveMode::Recursive) {
+        if engine.theme_dir.exists() {
+            info!("正在监控主题目录: {:?}", engine.theme_dir);
+            match watcher.watch(&engine.theme_dir, RecursiveMode::Recursive) {
                 Ok(_) => info!("成功添加主题目录到监控"),
                 Err(e) => error!("监控主题目录失败: {:?}", e),
             }
         }
         
+        // 监视父主题目录，父主题中的变化会触发完整重新生成
+        if let Ok(theme_dirs) = resolve_theme_chain(&engine.theme_dir) {
+            for parent_dir in theme_dirs.iter().skip(1) {
+                info!("正在监控父主题目录: {:?}", parent_dir);
+                if let Err(e) = watcher.watch(parent_dir, RecursiveMode::Recursive) {
+                    error!("监控父主题目录失败: {:?}", e);
+                }
+            }
+        }
+        
+        // 监视站点根目录（非递归），以捕获 _config.yml 的变化
+        // 监视目录而不是文件本身，这样编辑器以替换方式保存文件时也能收到事件
+        info!("正在监控站点配置文件: {:?}", engine.base_dir.join("_config.yml"));
+        match watcher.watch(&engine.base_dir, RecursiveMode::NonRecursive) {
+            Ok(_) => info!("成功添加站点根目录到监控"),
+            Err(e) => error!("监控站点根目录失败: {:?}", e),
+        }
+        
+        // 监视站点级模板覆盖目录（source/_layouts 已包含在源目录中）
+        let layout_dirs = engine.layout_override_dirs();
+        let site_layout_dir = engine.base_dir.join("layout");
+        if site_layout_dir.exists() {
+            info!("正在监控站点模板目录: {:?}", site_layout_dir);
+            match watcher.watch(&site_layout_dir, RecursiveMode::Recursive) {
+                Ok(_) => info!("成功添加站点模板目录到监控"),
+                Err(e) => error!("监控站点模板目录失败: {:?}", e),
+            }
+        }
+        
         // 尝试明确地监控一些特定的子目录，以增加监控范围
-        let source_posts_dir = self.source_dir.join("_posts");
+        let source_posts_dir = engine.source_dir.join("_posts");
         if source_posts_dir.exists() {
             info!("明确监控文章目录: {:?}", source_posts_dir);
             match watcher.watch(&source_posts_dir, RecursiveMode::Recursive) {
@@ -861,14 +1205,13 @@ impl Engine {
             info!("文件监视器已保存到引擎实例中，确保其生命周期持续整个监控过程");
         }
         
-        // 创建一个引擎的克隆，用于生成
-        let mut engine = self.clone();
-        
         // 在后台启动监视任务
         tokio::spawn(async move {
             // 创建一个防抖动计时器，避免频繁生成
             let mut last_event = std::time::Instant::now();
             let debounce_time = Duration::from_millis(1000);
+            // 上次重新生成以来收集到的文件变化
+            let mut pending = ChangeSet::new();
             // let mut event_count = 0;
             
             info!("启动文件监控循环");
@@ -886,7 +1229,7 @@ impl Engine {
                         // info!("收到事件 #{}: {:?}", event_count, event);
                         
                         // 检查事件路径
-                        if let Some(path) = event.paths.get(0) {
+                        if let Some(path) = event.paths.first() {
                             info!("事件路径: {:?}", path);
                             
                             // 输出文件是否存在的信息
@@ -919,11 +1262,40 @@ impl Engine {
                                     // 详细检查路径
                                     info!("检查路径: {}", path.display());
                                     
+                                    // 特别处理站点和主题的 _config.yml 文件
+                                    if engine.is_config_file(path) {
+                                        is_relevant = true;
+                                        pending.config = true;
+                                        info!("检测到配置文件变化: {}", path.display());
+                                        continue;
+                                    }
+                                    
+                                    // 站点级模板覆盖目录中的模板
+                                    if pending.record_layout_override(path, &layout_dirs) {
+                                        is_relevant = true;
+                                        info!("检测到站点模板变化: {}", path.display());
+                                        continue;
+                                    }
+                                    
+                                    // 站点根目录只为配置文件而监视，忽略其中的其他变化（如 public 目录）
+                                    if path.parent() == Some(engine.base_dir.as_path()) {
+                                        continue;
+                                    }
+                                    
                                     // 对于目录，直接认为是相关变化
                                     if path.is_dir() {
                                         is_relevant = true;
                                         info!("检测到目录变化: {}", path.display());
-                                        break;
+                                        pending.record(path, &engine.source_dir, &engine.theme_dir);
+                                        continue;
+                                    }
+                                    
+                                    // 主题资源目录中的任何文件都需要复制
+                                    if path.starts_with(engine.theme_dir.join("source")) {
+                                        is_relevant = true;
+                                        info!("检测到主题资源变化: {}", path.display());
+                                        pending.record(path, &engine.source_dir, &engine.theme_dir);
+                                        continue;
                                     }
                                     
                                     // 对于文件，更详细地检查扩展名
@@ -936,18 +1308,11 @@ impl Engine {
                                            ext_str == "html" || ext_str == "css" || ext_str == "js" {
                                             is_relevant = true;
                                             info!("检测到相关文件变化: {}", path.display());
-                                            break;
+                                            pending.record(path, &engine.source_dir, &engine.theme_dir);
                                         }
                                     } else {
                                         info!("文件没有扩展名: {}", path.display());
                                     }
-                                    
-                                    // 特别处理 _config.yml 文件
-                                    if path.file_name().map_or(false, |name| name == "_config.yml") {
-                                        is_relevant = true;
-                                        info!("检测到配置文件变化: {}", path.display());
-                                        break;
-                                    }
                                 }
                                 
                                 if is_relevant {
@@ -969,30 +1334,34 @@ impl Engine {
                         //     info!("监控超时，等待文件变化... (上次事件距今: {}毫秒)", last_event.elapsed().as_millis());
                         // }
                         
-                        // 如果自上次事件以来已过去debounce时间，且有事件发生，则重新生成
-                        if last_event.elapsed() >= debounce_time {
-                            // 移除了额外的条件限制，只要超过防抖时间就重新生成
-                            let elapsed = last_event.elapsed().as_millis();
+                        // 如果自上次事件以来已过去debounce时间，且有待处理的变化，则重新生成
+                        if last_event.elapsed() >= debounce_time && !pending.is_empty() {
+                            let changes = std::mem::take(&mut pending);
+                            info!("检测到文件变化，重新生成... ({})", changes.summary());
                             
-                            if elapsed < 10000 { // 只有在过去10秒内有事件时才重新生成
-                                info!("检测到文件变化，重新生成... (上次事件距今: {}毫秒)", elapsed);
-                                
-                                // 重新生成前重新加载文章
-                                info!("重新加载文章内容...");
-                                if let Err(e) = engine.load_posts_and_pages() {
-                                    error!("重新加载文章失败: {}", e);
-                                }
+                            // 配置文件变化时先重新加载配置
+                            if changes.config {
+                                let previous_theme_dir = engine.theme_dir.clone();
                                 
-                                // 重新生成静态文件
-                                let public_dir = engine.public_dir.clone();
-                                if let Err(e) = engine.generate(&public_dir) {
-                                    error!("重新生成失败: {}", e);
-                                } else {
-                                    info!("重新生成成功");
+                                info!("重新加载配置文件...");
+                                match engine.load_config() {
+                                    Ok(_) => {
+                                        info!("配置文件已重新加载");
+                                        
+                                        // 主题切换后监视新的主题目录
+                                        if engine.theme_dir != previous_theme_dir {
+                                            engine.watch_theme_dir();
+                                        }
+                                    },
+                                    Err(e) => {
+                                        error!("重新加载配置失败，继续使用之前的配置: {:#}", e);
+                                    }
                                 }
-                                
-                                // 重置最后事件时间，使用足够长的时间以避免连续触发
-                                last_event = std::time::Instant::now() - Duration::from_secs(10);
+                            }
+                            
+                            // 根据变化重新生成
+                            if let Err(e) = engine.rebuild(&changes) {
+                                error!("重新生成失败: {}", e);
                             }
                         }
                         
@@ -1013,6 +1382,44 @@ impl Engine {
         Ok(())
     }
 
+    /// 将站点相关目录转换为绝对路径
+    fn use_absolute_paths(&mut self) {
+        if let Ok(base_dir) = fs::canonicalize(&self.base_dir) {
+            self.source_dir = base_dir.join("source");
+            self.public_dir = base_dir.join("public");
+            self.scaffold_dir = base_dir.join("scaffolds");
+            self.theme_dir = Self::resolve_theme_dir(&base_dir, &self.config);
+            self.base_dir = base_dir;
+        }
+    }
+    
+    /// 判断路径是否为站点或主题的配置文件
+    fn is_config_file(&self, path: &Path) -> bool {
+        if path == self.base_dir.join("_config.yml") || path == self.theme_dir.join("_config.yml") {
+            return true;
+        }
+        
+        // 父主题的配置文件
+        path.file_name().is_some_and(|name| name == "_config.yml")
+            && resolve_theme_chain(&self.theme_dir)
+                .is_ok_and(|dirs| dirs.iter().skip(1).any(|dir| path == dir.join("_config.yml")))
+    }
+    
+    /// 将当前主题目录加入文件监视器
+    fn watch_theme_dir(&self) {
+        if !self.theme_dir.exists() {
+            return;
+        }
+        
+        let mut file_watcher = self.file_watcher.write().unwrap();
+        if let Some(watcher) = file_watcher.as_mut().and_then(|w| w.downcast_mut::<RecommendedWatcher>()) {
+            info!("正在监控主题目录: {:?}", self.theme_dir);
+            if let Err(e) = watcher.watch(&self.theme_dir, RecursiveMode::Recursive) {
+                error!("监控主题目录失败: {:?}", e);
+            }
+        }
+    }
+    
     /// 停止监视文件变化
     pub fn unwatch(&self) {
         info!("停止监视文件变化");
@@ -1027,8 +1434,13 @@ impl Engine {
         Ok(())
     }
 
-    /// 启动本地服务器
-    pub async fn server(&mut self, port: u16) -> Result<()> {
+    /// 标记文章或配置已变化，开发接口会在下次请求时重建搜索索引
+    fn mark_changed(&self) {
+        self.revision.fetch_add(1, Ordering::SeqCst);
+    }
+    
+    /// 启动本地服务器，`api` 为真时提供 `/__api/` 下的开发接口
+    pub async fn server(&mut self, port: u16, api: bool) -> Result<()> {
         info!("启动本地服务器在端口 {}", port);
         
         // 确保生成了静态文件
@@ -1040,8 +1452,15 @@ impl Engine {
             self.generate(&public_dir)?;
         }
         
-        // 创建服务器实例
-        let server = super::server::Server::new(public_dir, port);
+        // 创建服务器实例，开发接口与引擎共享文章列表
+        let mut server = super::server::Server::new(public_dir, port);
+        if api {
+            server = server.with_api(super::server::DevApi::new(
+                self.posts.clone(),
+                self.shared_config.clone(),
+                self.revision.clone(),
+            ));
+        }
         
         // 启动服务器
         info!("启动Web服务器在 http://localhost:{}", port);
@@ -1087,4 +1506,100 @@ fn pod_to_value(pod: Pod) -> Value {
         },
         Pod::Null => Value::Null,
     }
-} 
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 创建一个带有配置文件的临时站点目录，返回值离开作用域时删除目录
+    fn temp_site(config: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("_config.yml"), config).unwrap();
+        dir
+    }
+    
+    #[test]
+    fn test_load_config_keeps_previous_config_on_invalid_yaml() -> Result<()> {
+        let site = temp_site("title: First\n");
+        let mut engine = Engine::new(site.path().to_path_buf())?;
+        assert_eq!(engine.config.title, "First");
+        
+        // 无效的 YAML 不应覆盖当前配置
+        fs::write(site.path().join("_config.yml"), "title: [unclosed\n")?;
+        assert!(engine.load_config().is_err());
+        assert_eq!(engine.config.title, "First");
+        Ok(())
+    }
+    
+    #[test]
+    fn test_load_config_switches_theme() -> Result<()> {
+        let site = temp_site("title: First\n");
+        let mut engine = Engine::new(site.path().to_path_buf())?;
+        
+        fs::write(site.path().join("_config.yml"), "title: Second\ntheme: other\n")?;
+        engine.load_config()?;
+        assert_eq!(engine.config.title, "Second");
+        assert_eq!(engine.theme_dir, site.path().join("themes").join("other"));
+        Ok(())
+    }
+    
+    #[test]
+    fn test_theme_config_inherits_parent_defaults() -> Result<()> {
+        let site = temp_site("title: Site
+theme: child
+theme_config:
+  footer: site
+");
+        let themes_dir = site.path().join("themes");
+        fs::create_dir_all(themes_dir.join("base"))?;
+        fs::create_dir_all(themes_dir.join("child"))?;
+        fs::write(themes_dir.join("base").join("_config.yml"), "color: blue
+sidebar: left
+footer: base
+")?;
+        fs::write(themes_dir.join("child").join("_config.yml"), "extends: base
+color: red
+")?;
+        
+        let engine = Engine::new(site.path().to_path_buf())?;
+        assert_eq!(engine.theme_config["color"], Value::from("red"));
+        assert_eq!(engine.theme_config["sidebar"], Value::from("left"));
+        assert_eq!(engine.theme_config["footer"], Value::from("site"));
+        Ok(())
+    }
+    
+    #[test]
+    fn test_rebuild_list_templates() -> Result<()> {
+        let site = temp_site("title: Site\ntheme: plain\nsearch:\n  enable: true\n");
+        let base_dir = site.path();
+        let layout_dir = base_dir.join("themes").join("plain").join("layout");
+        fs::create_dir_all(&layout_dir)?;
+        fs::create_dir_all(base_dir.join("source").join("_posts"))?;
+        fs::write(base_dir.join("source").join("_posts").join("a.md"), "---\ntitle: A\ndate: 2024-01-01 00:00:00\ncategories: [Notes]\n---\nbody\n")?;
+        fs::write(layout_dir.join("index.html"), "<p>index</p>")?;
+        fs::write(layout_dir.join("post.html"), "<p>post</p>")?;
+        fs::write(layout_dir.join("categories.html"), "<p>categories v1</p>")?;
+        fs::write(layout_dir.join("search.html"), "<p>search v1</p>")?;
+        
+        let mut engine = Engine::new(base_dir.to_path_buf())?;
+        let public_dir = engine.public_dir.clone();
+        engine.generate(&public_dir)?;
+        let categories_page = public_dir.join("categories").join("index.html");
+        let search_page = public_dir.join(crate::core::search::PAGE_PATH);
+        assert!(fs::read_to_string(&categories_page)?.contains("categories v1"));
+        assert!(fs::read_to_string(&search_page)?.contains("search v1"));
+        
+        // 只修改模板，没有文章变化时也要重新渲染使用它们的页面
+        fs::write(layout_dir.join("categories.html"), "<p>categories v2</p>")?;
+        fs::write(layout_dir.join("search.html"), "<p>search v2</p>")?;
+        let mut changes = ChangeSet::new();
+        changes.record(&layout_dir.join("categories.html"), &engine.source_dir, &engine.theme_dir);
+        changes.record(&layout_dir.join("search.html"), &engine.source_dir, &engine.theme_dir);
+        assert!(!changes.requires_full_rebuild());
+        engine.rebuild(&changes)?;
+        
+        assert!(fs::read_to_string(&categories_page)?.contains("categories v2"));
+        assert!(fs::read_to_string(&search_page)?.contains("search v2"));
+        Ok(())
+    }
+}
@@ -0,0 +1,685 @@
+use anyhow::Result;
+use atom_syndication::{Content, Entry, Feed, Link, Person, Text};
+use chrono::{DateTime, Utc};
+use rss::extension::itunes::{ITunesCategory, ITunesChannelExtension, ITunesItemExtension, ITunesOwner};
+use rss::extension::{Extension, ExtensionMap};
+use rss::{Channel, Guid, ImageBuilder, Item};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::models::config::{Config, FeedConfig, PodcastConfig};
+use crate::models::types::Post;
+use crate::theme::helpers::{strip_html, truncate};
+use crate::utils::url::UrlHelper;
+
+/// 文章没有摘要时，从正文截取的摘要长度
+const SUMMARY_LENGTH: usize = 200;
+
+/// JSON Feed 版本
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/// Podcasting 2.0 命名空间
+const PODCAST_NAMESPACE: &str = "https://podcastindex.org/namespace/1.0";
+
+/// 订阅源生成器，按站点配置中的 `feed` 生成 RSS、Atom 和 JSON Feed
+pub struct FeedGenerator<'a> {
+    config: &'a FeedConfig,
+    site: &'a Config,
+    urls: &'a UrlHelper,
+    /// 查找音频文件的目录
+    media_dirs: Vec<PathBuf>,
+}
+
+impl<'a> FeedGenerator<'a> {
+    pub fn new(config: &'a FeedConfig, site: &'a Config, urls: &'a UrlHelper) -> Self {
+        Self { config, site, urls, media_dirs: Vec::new() }
+    }
+    
+    /// 设置查找音频文件的目录，用于读取附件的大小和类型
+    pub fn with_media_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.media_dirs = dirs;
+        self
+    }
+    
+    /// 生成 `dir` 目录下的订阅源，返回输出路径和内容
+    ///
+    /// 文章按 `order_by` 排序并截取前 `limit` 篇，`limit` 为 0 时包含全部文章。
+    pub fn render(&self, posts: &[&Post], dir: &str, title: &str) -> Result<Vec<(String, String)>> {
+        let posts = self.select(posts);
+        let dir = dir.trim_matches('/');
+        let path = |file: &str| if dir.is_empty() { file.to_string() } else { format!("{}/{}", dir, file) };
+        
+        let mut files = Vec::new();
+        if self.config.atom {
+            let atom_path = path("atom.xml");
+            let feed = self.atom(&posts, &atom_path, title);
+            files.push((atom_path, feed.to_string()));
+        }
+        if self.config.rss {
+            let rss_path = path("rss.xml");
+            let channel = self.rss(&posts, title);
+            files.push((rss_path, channel.to_string()));
+        }
+        if self.config.json {
+            let json_path = path("feed.json");
+            let feed = self.json(&posts, &json_path, title);
+            files.push((json_path, serde_json::to_string_pretty(&feed)?));
+        }
+        Ok(files)
+    }
+    
+    /// 生成播客订阅源，包含 `podcast.category` 分类中带有 `audio` 的全部文章
+    pub fn render_podcast(&self, podcast: &PodcastConfig, posts: &[&Post]) -> Result<(String, String)> {
+        let mut episodes: Vec<(&Post, Enclosure)> = posts.iter()
+            .filter(|post| post.categories.contains(&podcast.category))
+            .filter_map(|post| match self.enclosure(post) {
+                Some(enclosure) => Some((*post, enclosure)),
+                None => {
+                    debug!("播客分类中的文章 {} 没有 audio，跳过", post.title);
+                    None
+                }
+            })
+            .collect();
+        episodes.sort_by_key(|(post, _)| std::cmp::Reverse(post.date));
+        
+        let title = podcast.title.clone().unwrap_or_else(|| self.site.title.clone());
+        let author = podcast.author.clone().or_else(|| self.site.author.clone());
+        let image = podcast.image.as_ref().map(|image| self.urls.full_url_for(image));
+        
+        let mut channel = Channel::default();
+        channel.set_title(title.clone());
+        channel.set_link(self.urls.full_url_for(""));
+        channel.set_description(podcast.description.clone().or_else(|| self.site.description.clone()).unwrap_or_default());
+        channel.set_language(self.site.language.clone());
+        channel.set_generator(Some("rust-hexo".to_string()));
+        if let Some(date) = episodes.iter().map(|(post, _)| updated(post)).max() {
+            channel.set_last_build_date(Some(date.to_rfc2822()));
+        }
+        if let Some(image) = &image {
+            channel.set_image(Some(ImageBuilder::default()
+                .url(image.clone())
+                .title(title)
+                .link(self.urls.full_url_for(""))
+                .build()));
+        }
+        
+        let category = podcast.itunes_category.as_ref().map(|text| ITunesCategory {
+            text: text.clone(),
+            subcategory: podcast.itunes_subcategory.as_ref().map(|text| Box::new(ITunesCategory { text: text.clone(), subcategory: None })),
+        });
+        let owner = (podcast.owner_name.is_some() || podcast.owner_email.is_some()).then(|| ITunesOwner {
+            name: podcast.owner_name.clone(),
+            email: podcast.owner_email.clone(),
+        });
+        channel.set_itunes_ext(ITunesChannelExtension {
+            author,
+            categories: category.into_iter().collect(),
+            image,
+            explicit: Some(podcast.explicit.to_string()),
+            owner,
+            summary: podcast.description.clone(),
+            ..ITunesChannelExtension::default()
+        });
+        
+        let mut podcast_ext = Vec::new();
+        if let Some(locked) = podcast.locked {
+            let mut extension = podcast_extension("locked", if locked { "yes" } else { "no" });
+            if let Some(email) = &podcast.owner_email {
+                extension.attrs.insert("owner".to_string(), email.clone());
+            }
+            podcast_ext.push(extension);
+        }
+        if let Some(guid) = &podcast.guid {
+            podcast_ext.push(podcast_extension("guid", guid));
+        }
+        channel.set_extensions(extension_map(podcast_ext));
+        channel.set_namespaces(BTreeMap::from([("podcast".to_string(), PODCAST_NAMESPACE.to_string())]));
+        
+        for (post, enclosure) in episodes {
+            let post_url = self.post_url(post);
+            let (summary, content) = self.content(post);
+            let episode = front_matter_number(post, "episode");
+            let season = front_matter_number(post, "season");
+            let explicit = post.front_matter.get("explicit").and_then(serde_yaml::Value::as_bool).unwrap_or(podcast.explicit);
+            
+            let mut item = Item::default();
+            item.set_title(post.title.clone());
+            item.set_link(post_url.clone());
+            item.set_guid(Guid { value: post_url, permalink: true });
+            item.set_pub_date(post.date.to_rfc2822());
+            item.set_description(summary.clone());
+            item.set_content(content);
+            item.set_enclosure(rss::Enclosure {
+                url: enclosure.url,
+                length: enclosure.length.unwrap_or_default().to_string(),
+                mime_type: enclosure.mime_type,
+            });
+            item.set_itunes_ext(ITunesItemExtension {
+                duration: enclosure.duration.map(format_duration),
+                explicit: Some(explicit.to_string()),
+                image: front_matter_str(post, "image").map(|image| self.link_url(post, image)),
+                episode: episode.clone(),
+                season: season.clone(),
+                episode_type: Some("full".to_string()),
+                summary,
+                ..ITunesItemExtension::default()
+            });
+            
+            let mut item_ext = Vec::new();
+            if let Some(episode) = &episode {
+                item_ext.push(podcast_extension("episode", episode));
+            }
+            if let Some(season) = &season {
+                item_ext.push(podcast_extension("season", season));
+            }
+            item.set_extensions(extension_map(item_ext));
+            channel.items.push(item);
+        }
+        
+        Ok((podcast.path.trim_start_matches('/').to_string(), channel.to_string()))
+    }
+    
+    fn select<'p>(&self, posts: &[&'p Post]) -> Vec<&'p Post> {
+        let mut posts = posts.to_vec();
+        let (field, descending) = match self.config.order_by.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (self.config.order_by.as_str(), false),
+        };
+        posts.sort_by(|a, b| {
+            let ordering = match field {
+                "title" => a.title.cmp(&b.title),
+                "updated" => updated(a).cmp(&updated(b)),
+                _ => a.date.cmp(&b.date),
+            };
+            if descending { ordering.reverse() } else { ordering }
+        });
+        if self.config.limit > 0 {
+            posts.truncate(self.config.limit);
+        }
+        posts
+    }
+    
+    fn rss(&self, posts: &[&Post], title: &str) -> Channel {
+        let mut channel = Channel::default();
+        channel.set_title(title.to_string());
+        channel.set_link(self.urls.full_url_for(""));
+        channel.set_description(self.site.description.clone().unwrap_or_default());
+        channel.set_language(self.site.language.clone());
+        channel.set_generator(Some("rust-hexo".to_string()));
+        if let Some(date) = posts.iter().map(|post| updated(post)).max() {
+            channel.set_last_build_date(Some(date.to_rfc2822()));
+        }
+        if let Some(logo) = &self.config.logo {
+            channel.set_image(Some(ImageBuilder::default()
+                .url(self.urls.full_url_for(logo))
+                .title(title.to_string())
+                .link(self.urls.full_url_for(""))
+                .build()));
+        }
+        
+        for post in posts {
+            let post_url = self.post_url(post);
+            let (summary, content) = self.content(post);
+            
+            let mut item = Item::default();
+            item.set_title(post.title.clone());
+            item.set_link(post_url.clone());
+            item.set_guid(Guid { value: post_url, permalink: true });
+            item.set_pub_date(post.date.to_rfc2822());
+            item.set_description(summary);
+            item.set_content(content);
+            item.set_categories(post.categories.iter().chain(&post.tags)
+                .map(|name| rss::Category { name: name.clone(), domain: None })
+                .collect::<Vec<_>>());
+            channel.items.push(item);
+        }
+        
+        channel
+    }
+    
+    fn atom(&self, posts: &[&Post], path: &str, title: &str) -> Feed {
+        let mut feed = Feed::default();
+        feed.set_title(title.to_string());
+        feed.set_id(self.urls.full_url_for(path));
+        // 使用最新文章的时间，文章不变时输出不变
+        feed.set_updated(posts.iter().map(|post| updated(post)).max().unwrap_or_else(Utc::now).fixed_offset());
+        feed.set_lang(self.site.language.clone());
+        if let Some(subtitle) = &self.site.subtitle {
+            feed.set_subtitle(Text::plain(subtitle.clone()));
+        }
+        if let Some(author) = &self.site.author {
+            feed.set_authors(vec![Person { name: author.clone(), ..Person::default() }]);
+        }
+        feed.set_icon(self.config.icon.as_ref().map(|icon| self.urls.full_url_for(icon)));
+        feed.set_logo(self.config.logo.as_ref().map(|logo| self.urls.full_url_for(logo)));
+        feed.set_links(vec![
+            link(self.urls.full_url_for(path), "self"),
+            link(self.urls.full_url_for(""), "alternate"),
+        ]);
+        
+        for post in posts {
+            let post_url = self.post_url(post);
+            let (summary, content) = self.content(post);
+            
+            let mut entry = Entry::default();
+            entry.set_id(post_url.clone());
+            entry.set_title(Text::plain(post.title.clone()));
+            entry.set_links(vec![link(post_url, "alternate")]);
+            entry.set_published(Some(post.date.fixed_offset()));
+            entry.set_updated(updated(post).fixed_offset());
+            entry.set_summary(summary.map(Text::html));
+            entry.set_content(content.map(|html| {
+                let mut content = Content::default();
+                content.set_value(Some(html));
+                content.set_content_type(Some("html".to_string()));
+                content
+            }));
+            entry.set_categories(post.categories.iter().chain(&post.tags)
+                .map(|name| atom_syndication::Category { term: name.clone(), ..atom_syndication::Category::default() })
+                .collect::<Vec<_>>());
+            feed.entries.push(entry);
+        }
+        
+        feed
+    }
+    
+    fn json(&self, posts: &[&Post], path: &str, title: &str) -> JsonFeed {
+        let items = posts.iter()
+            .map(|post| {
+                let post_url = self.post_url(post);
+                let (summary, content) = self.content(post);
+                let author = front_matter_str(post, "author").map(|name| vec![JsonAuthor { name: name.to_string() }]);
+                
+                JsonItem {
+                    id: post_url.clone(),
+                    url: post_url,
+                    title: post.title.clone(),
+                    // JSON Feed 要求有正文，只输出摘要时以摘要作为正文
+                    content_html: content.or_else(|| summary.clone()).unwrap_or_default(),
+                    summary,
+                    image: front_matter_str(post, "image").map(|image| self.link_url(post, image)),
+                    banner_image: front_matter_str(post, "banner_image").map(|image| self.link_url(post, image)),
+                    date_published: post.date.to_rfc3339(),
+                    date_modified: updated(post).to_rfc3339(),
+                    authors: author,
+                    tags: post.tags.clone(),
+                    attachments: self.enclosure(post).map(|enclosure| vec![JsonAttachment {
+                        url: enclosure.url,
+                        mime_type: enclosure.mime_type,
+                        size_in_bytes: enclosure.length,
+                        duration_in_seconds: enclosure.duration,
+                    }]),
+                }
+            })
+            .collect();
+        
+        JsonFeed {
+            version: JSON_FEED_VERSION,
+            title: title.to_string(),
+            home_page_url: self.urls.full_url_for(""),
+            feed_url: self.urls.full_url_for(path),
+            description: self.site.description.clone(),
+            icon: self.config.logo.as_ref().map(|logo| self.urls.full_url_for(logo)),
+            favicon: self.config.icon.as_ref().map(|icon| self.urls.full_url_for(icon)),
+            authors: self.site.author.as_ref().map(|name| vec![JsonAuthor { name: name.clone() }]),
+            language: self.site.language.clone(),
+            items,
+        }
+    }
+    
+    /// 文章 front matter 中 `audio` 指定的音频附件，本地文件的大小和类型从文件读取
+    fn enclosure(&self, post: &Post) -> Option<Enclosure> {
+        let audio = front_matter_str(post, "audio")?;
+        let file = self.audio_file(post, audio);
+        if file.is_none() && self.urls.site_path(audio, &post.path).is_some() && !self.media_dirs.is_empty() {
+            warn!("文章 {} 的音频文件不存在: {}", post.title, audio);
+        }
+        
+        Some(Enclosure {
+            url: self.link_url(post, audio),
+            mime_type: file.as_deref()
+                .and_then(sniff_audio_type)
+                .unwrap_or_else(|| audio_mime_type(audio))
+                .to_string(),
+            length: file.as_deref().and_then(|file| file.metadata().ok()).map(|metadata| metadata.len()),
+            duration: post.front_matter.get("duration").and_then(parse_duration),
+        })
+    }
+    
+    /// 音频对应的本地文件，依次查找各个目录和文章的资源目录
+    fn audio_file(&self, post: &Post, audio: &str) -> Option<PathBuf> {
+        let path = self.urls.site_path(audio, &post.path)?;
+        let mut candidates: Vec<PathBuf> = self.media_dirs.iter().map(|dir| dir.join(&path)).collect();
+        if !audio.starts_with('/') && !post.source.as_os_str().is_empty() {
+            candidates.push(post.source.with_extension("").join(audio.split(['?', '#']).next().unwrap_or_default()));
+        }
+        candidates.into_iter().find(|file| file.is_file())
+    }
+    
+    /// 文章中的链接对应的完整 URL，相对链接相对于文章所在目录
+    fn link_url(&self, post: &Post, link: &str) -> String {
+        match self.urls.site_path(link, &post.path) {
+            Some(path) => self.urls.full_url_for(&path),
+            None => link.to_string(),
+        }
+    }
+    
+    /// 文章的摘要和正文
+    ///
+    /// `content_type` 为 `html` 时包含渲染后的完整正文；否则只有摘要，文章没有摘要时截取正文的开头。
+    fn content(&self, post: &Post) -> (Option<String>, Option<String>) {
+        let html = post.rendered_content.clone().unwrap_or_default();
+        let summary = post.excerpt.clone()
+            .filter(|excerpt| !excerpt.is_empty())
+            .unwrap_or_else(|| truncate(&strip_html(&html), SUMMARY_LENGTH));
+        
+        match self.config.content_type.as_str() {
+            "html" => (Some(summary), Some(html)),
+            _ => (Some(summary), None),
+        }
+    }
+    
+    /// 目录页面使用以 `/` 结尾的链接
+    fn post_url(&self, post: &Post) -> String {
+        self.urls.full_url_for(post.path.strip_suffix("index.html").unwrap_or(&post.path))
+    }
+}
+
+/// 文章的更新时间，没有时使用发布时间
+fn updated(post: &Post) -> DateTime<Utc> {
+    post.updated.filter(|updated| *updated > post.date).unwrap_or(post.date)
+}
+
+fn front_matter_str<'p>(post: &'p Post, key: &str) -> Option<&'p str> {
+    post.front_matter.get(key).and_then(serde_yaml::Value::as_str).filter(|value| !value.is_empty())
+}
+
+fn front_matter_number(post: &Post, key: &str) -> Option<String> {
+    match post.front_matter.get(key)? {
+        serde_yaml::Value::Number(number) => Some(number.to_string()),
+        serde_yaml::Value::String(value) if !value.is_empty() => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn podcast_extension(name: &str, value: &str) -> Extension {
+    Extension {
+        name: format!("podcast:{}", name),
+        value: Some(value.to_string()),
+        ..Extension::default()
+    }
+}
+
+fn extension_map(extensions: Vec<Extension>) -> ExtensionMap {
+    let mut map = ExtensionMap::new();
+    for extension in extensions {
+        let name = extension.name.trim_start_matches("podcast:").to_string();
+        map.entry("podcast".to_string()).or_default().entry(name).or_default().push(extension);
+    }
+    map
+}
+
+/// 秒数转换为 `时:分:秒`
+fn format_duration(seconds: u64) -> String {
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// 时长转换为秒，支持秒数和 `时:分:秒`、`分:秒` 格式
+fn parse_duration(value: &serde_yaml::Value) -> Option<u64> {
+    if let Some(seconds) = value.as_u64() {
+        return Some(seconds);
+    }
+    value.as_str()?
+        .split(':')
+        .try_fold(0u64, |total, part| part.trim().parse::<u64>().ok().map(|n| total * 60 + n))
+}
+
+/// 根据文件头判断音频类型
+fn sniff_audio_type(file: &Path) -> Option<&'static str> {
+    let mut header = [0u8; 12];
+    let len = File::open(file).and_then(|mut file| file.read(&mut header)).ok()?;
+    let header = &header[..len];
+    
+    if header.starts_with(b"ID3") || (header.len() >= 2 && header[0] == 0xFF && header[1] & 0xE0 == 0xE0 && header[1] & 0x06 != 0) {
+        Some("audio/mpeg")
+    } else if header.len() >= 8 && &header[4..8] == b"ftyp" {
+        Some("audio/mp4")
+    } else if header.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if header.starts_with(b"fLaC") {
+        Some("audio/flac")
+    } else if header.starts_with(b"RIFF") && header.len() >= 12 && &header[8..12] == b"WAVE" {
+        Some("audio/wav")
+    } else if header.len() >= 2 && header[0] == 0xFF && header[1] & 0xF6 == 0xF0 {
+        Some("audio/aac")
+    } else {
+        None
+    }
+}
+
+fn audio_mime_type(path: &str) -> &'static str {
+    let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "mp3" => "audio/mpeg",
+        "m4a" | "mp4" => "audio/mp4",
+        "aac" => "audio/aac",
+        "ogg" | "oga" => "audio/ogg",
+        "opus" => "audio/opus",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        _ => "application/octet-stream",
+    }
+}
+
+fn link(href: String, rel: &str) -> Link {
+    let mut link = Link::default();
+    link.set_href(href);
+    link.set_rel(rel.to_string());
+    link
+}
+
+/// 文章的音频附件
+struct Enclosure {
+    url: String,
+    mime_type: String,
+    /// 文件大小（字节）
+    length: Option<u64>,
+    /// 时长（秒）
+    duration: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct JsonFeed {
+    version: &'static str,
+    title: String,
+    home_page_url: String,
+    feed_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    favicon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authors: Option<Vec<JsonAuthor>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    items: Vec<JsonItem>,
+}
+
+#[derive(Serialize)]
+struct JsonAuthor {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct JsonItem {
+    id: String,
+    url: String,
+    title: String,
+    content_html: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    banner_image: Option<String>,
+    date_published: String,
+    date_modified: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authors: Option<Vec<JsonAuthor>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attachments: Option<Vec<JsonAttachment>>,
+}
+
+#[derive(Serialize)]
+struct JsonAttachment {
+    url: String,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size_in_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_in_seconds: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    fn post(title: &str, day: u32, tags: &[&str]) -> Post {
+        Post {
+            title: title.to_string(),
+            date: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            content: "# raw markdown".to_string(),
+            rendered_content: Some(format!("<p>{} body</p>", title)),
+            path: format!("{}/index.html", title),
+            categories: vec!["dev".to_string()],
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Post::default()
+        }
+    }
+    
+    #[test]
+    fn test_feed_generator() {
+        let site = Config {
+            url: Some("https://example.com".to_string()),
+            root: Some("/blog/".to_string()),
+            ..Config::default()
+        };
+        let urls = UrlHelper::from_config(&site);
+        // 默认包含完整正文
+        assert_eq!(FeedConfig::default().content_type, "html");
+        let config = FeedConfig { json: true, limit: 2, content_type: "excerpt".to_string(), logo: Some("logo.png".to_string()), ..FeedConfig::default() };
+        let posts = [post("a", 1, &["rust"]), post("b", 2, &[]), post("c", 3, &[])];
+        let posts: Vec<&Post> = posts.iter().collect();
+        
+        let files = FeedGenerator::new(&config, &site, &urls).render(&posts, "categories/dev/", "Blog - dev").unwrap();
+        assert_eq!(files.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>(), vec!["categories/dev/atom.xml", "categories/dev/rss.xml", "categories/dev/feed.json"]);
+        
+        let atom = &files[0].1;
+        assert!(atom.contains("<id>https://example.com/blog/categories/dev/atom.xml</id>"));
+        assert!(atom.contains("<logo>https://example.com/blog/logo.png</logo>"));
+        assert!(atom.contains("<id>https://example.com/blog/c/</id>"));
+        assert!(!atom.contains("https://example.com/blog/a/"));
+        assert!(atom.contains("c body"));
+        assert!(!atom.contains("raw markdown"));
+        assert!(!atom.contains("<content"));
+        assert!(files[1].1.contains("<category>dev</category>"));
+        
+        let config = FeedConfig { content_type: "html".to_string(), order_by: "title".to_string(), ..config };
+        let files = FeedGenerator::new(&config, &site, &urls).render(&posts, "", "Blog").unwrap();
+        assert_eq!(files[0].0, "atom.xml");
+        assert!(files[0].1.contains("<content type=\"html\">&lt;p&gt;a body&lt;/p&gt;</content>"));
+        assert!(files[0].1.contains("<category term=\"rust\""));
+    }
+    
+    #[test]
+    fn test_json_feed() {
+        let site = Config {
+            url: Some("https://example.com".to_string()),
+            author: Some("Site Author".to_string()),
+            ..Config::default()
+        };
+        let urls = UrlHelper::from_config(&site);
+        // JSON Feed 默认关闭
+        assert!(!FeedConfig::default().json);
+        let config = FeedConfig { rss: false, atom: false, json: true, ..FeedConfig::default() };
+        let mut episode = post("ep1", 2, &["audio"]);
+        episode.updated = Some(Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap());
+        episode.front_matter.insert("image".to_string(), "cover.jpg".into());
+        episode.front_matter.insert("author".to_string(), "Guest".into());
+        episode.front_matter.insert("audio".to_string(), "/media/ep1.mp3".into());
+        episode.front_matter.insert("duration".to_string(), "1:02:03".into());
+        let posts = [post("a", 1, &[]), episode];
+        let posts: Vec<&Post> = posts.iter().collect();
+        
+        let files = FeedGenerator::new(&config, &site, &urls).render(&posts, "", "Blog").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "feed.json");
+        let feed: serde_json::Value = serde_json::from_str(&files[0].1).unwrap();
+        assert_eq!(feed["version"], JSON_FEED_VERSION);
+        assert_eq!(feed["feed_url"], "https://example.com/feed.json");
+        assert_eq!(feed["authors"][0]["name"], "Site Author");
+        
+        let item = &feed["items"][0];
+        assert_eq!(item["url"], "https://example.com/ep1/");
+        assert_eq!(item["content_html"], "<p>ep1 body</p>");
+        assert_eq!(item["image"], "https://example.com/ep1/cover.jpg");
+        assert_eq!(item["date_modified"], "2024-01-05T00:00:00+00:00");
+        assert_eq!(item["authors"][0]["name"], "Guest");
+        assert_eq!(item["tags"][0], "audio");
+        assert_eq!(item["attachments"][0]["url"], "https://example.com/media/ep1.mp3");
+        assert_eq!(item["attachments"][0]["mime_type"], "audio/mpeg");
+        assert_eq!(item["attachments"][0]["duration_in_seconds"], 3723);
+        assert!(feed["items"][1].get("attachments").is_none());
+    }
+    
+    #[test]
+    fn test_podcast_feed() {
+        let dir = tempfile::tempdir().unwrap();
+        let media_dir = dir.path().to_path_buf();
+        std::fs::create_dir_all(media_dir.join("media")).unwrap();
+        std::fs::write(media_dir.join("media/ep1.audio"), b"ID3\x04\0\0\0\0\0\0audio").unwrap();
+        
+        let site = Config { url: Some("https://example.com".to_string()), ..Config::default() };
+        let urls = UrlHelper::from_config(&site);
+        let config = FeedConfig::default();
+        let podcast = PodcastConfig {
+            category: "dev".to_string(),
+            owner_email: Some("host@example.com".to_string()),
+            itunes_category: Some("Technology".to_string()),
+            locked: Some(true),
+            ..PodcastConfig::default()
+        };
+        let mut episode = post("ep1", 2, &[]);
+        episode.front_matter.insert("audio".to_string(), "/media/ep1.audio?v=1".into());
+        episode.front_matter.insert("duration".to_string(), 3723.into());
+        episode.front_matter.insert("episode".to_string(), 1.into());
+        episode.front_matter.insert("explicit".to_string(), true.into());
+        let posts = [post("a", 1, &[]), episode];
+        let posts: Vec<&Post> = posts.iter().collect();
+        
+        let (path, xml) = FeedGenerator::new(&config, &site, &urls)
+            .with_media_dirs(vec![media_dir])
+            .render_podcast(&podcast, &posts)
+            .unwrap();
+        
+        assert_eq!(path, "podcast.xml");
+        assert!(xml.contains("xmlns:itunes="));
+        assert!(xml.contains(&format!("xmlns:podcast=\"{}\"", PODCAST_NAMESPACE)));
+        assert!(xml.contains("<podcast:locked owner=\"host@example.com\">yes</podcast:locked>"));
+        assert!(xml.contains("<itunes:category text=\"Technology\"></itunes:category>"));
+        assert!(xml.contains("<enclosure url=\"https://example.com/media/ep1.audio\" length=\"15\" type=\"audio/mpeg\"/>"));
+        assert!(xml.contains("<itunes:duration>01:02:03</itunes:duration>"));
+        assert!(xml.contains("<itunes:explicit>true</itunes:explicit>"));
+        assert!(xml.contains("<podcast:episode>1</podcast:episode>"));
+        assert_eq!(xml.matches("<item>").count(), 1);
+    }
+}
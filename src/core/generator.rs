@@ -1,35 +1,47 @@
 use std::{
-    fs::{self, File},
-    io::{self, Write as IoWrite},
+    fs,
+    sync::{Arc, Mutex},
     path::{Path, PathBuf},
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Write,
 };
 use anyhow::{Result, anyhow};
-use chrono::{DateTime, Utc, Datelike, FixedOffset, TimeZone, Local};
-use pulldown_cmark::{html, Parser};
+use chrono::Datelike;
 use tracing::{debug, info, warn};
-use atom_syndication::{Feed, FeedBuilder, Entry, Text};
-use rss::{Channel, ChannelBuilder, Item, Guid};
 use serde_json::{json, Value};
 use walkdir::WalkDir;
-use url::Url;
-use tera::{Context, Tera};
+use crate::core::assets::{AssetFile, AssetPipeline};
+use crate::core::feed::FeedGenerator;
+use crate::core::images::{picture_rewriter, ImagePipeline};
+use crate::core::postprocess::HtmlPostProcessor;
+use crate::core::sitemap::{robots_txt, SitemapEntry, SitemapGenerator};
+use crate::theme::embedded;
+use crate::theme::engine::TemplateContext;
 
 use crate::{
     models::{
         types::Post,
-        config::Config,
+        config::{Config, GeneratorConfig},
     },
     plugins::{
         PluginManager,
-        PluginContext,
-        PluginError,
         PluginHook,
     },
-    core::search::SearchIndexGenerator,
+    core::search::{self, SearchIndexGenerator},
+    theme::renderer::ThemeRenderer,
+    utils::url::{with_current_path, UrlHelper},
 };
 
+/// 归档页面共享的渲染参数
+struct ArchivePage<'a> {
+    /// 主题是否提供归档模板，没有时使用内置页面
+    has_template: bool,
+    /// 归档列表（`page.archives`）
+    items: &'a [Value],
+    /// 每页文章数
+    per_page: usize,
+}
+
 /// HTML 生成器
 pub struct HtmlGenerator {
     /// 输出目录
@@ -38,26 +50,55 @@ pub struct HtmlGenerator {
     pub config: Config,
     /// 插件管理器
     plugin_manager: PluginManager,
+    /// 主题渲染器，由引擎持有并在所有页面间共享
+    renderer: Arc<ThemeRenderer>,
+    /// 站点链接生成器
+    urls: UrlHelper,
+    /// 页面写入前的改写和压缩
+    post_processor: HtmlPostProcessor,
+    /// 文章图片处理，由引擎设置
+    images: Option<ImagePipeline>,
+    /// 站点源文件目录，由引擎设置
+    source_dir: Option<PathBuf>,
+    /// 本次生成写入的页面，用于生成站点地图
+    written_pages: Mutex<BTreeSet<String>>,
 }
 
 impl HtmlGenerator {
-    /// 创建新的 HTML 生成器
-    pub fn new(output_dir: PathBuf, config: Config, base_dir: PathBuf) -> Self {
-        let plugin_manager = PluginManager::new(base_dir.clone(), PluginContext::default());
+    /// 创建新的 HTML 生成器，使用引擎提供的插件管理器和主题渲染器
+    pub fn new(output_dir: PathBuf, config: Config, plugin_manager: PluginManager, renderer: Arc<ThemeRenderer>) -> Self {
+        let urls = renderer.urls().clone();
+        
+        // 内置的图片改写在插件的规则之前执行
+        let mut rewriters = Vec::new();
+        let image_config = config.images.clone().unwrap_or_default();
+        if image_config.enable {
+            rewriters.push(picture_rewriter(urls.clone(), image_config.sizes));
+        }
+        let post_processor = HtmlPostProcessor::new(config.html.clone().unwrap_or_default(), rewriters)
+            .with_page_rewriters(plugin_manager.page_rewriters());
+        
         Self {
             output_dir,
             config,
             plugin_manager,
+            renderer,
+            urls,
+            post_processor,
+            images: None,
+            source_dir: None,
+            written_pages: Mutex::default(),
         }
     }
     
-    /// 使用已初始化的插件管理器创建HTML生成器
-    pub fn new_with_plugin_manager(output_dir: PathBuf, config: Config, plugin_manager: PluginManager) -> Self {
-        Self {
-            output_dir,
-            config,
-            plugin_manager,
-        }
+    /// 设置文章图片处理，未设置时不复制文章引用的图片
+    pub fn set_image_pipeline(&mut self, pipeline: ImagePipeline) {
+        self.images = Some(pipeline);
+    }
+    
+    /// 设置站点源文件目录，用于查找播客音频等文章引用的文件
+    pub fn set_source_dir(&mut self, source_dir: PathBuf) {
+        self.source_dir = Some(source_dir);
     }
     
     /// 生成所有内容
@@ -70,15 +111,30 @@ impl HtmlGenerator {
         // 确保输出目录存在
         fs::create_dir_all(&self.output_dir)?;
         
-        // 复制主题资源文件
-        self.copy_theme_assets()?;
+        // 处理主题资源文件
+        self.process_theme_assets()?;
+        
+        // 处理文章引用的图片，页面中的图片链接依赖处理结果
+        self.process_images(posts)?;
         
         // 生成文章页面
         self.generate_posts(posts)?;
         
+        // 生成依赖文章列表的页面
+        self.generate_lists(posts)?;
+        
+        // 生成成功，调用生成后钩子
+        self.plugin_manager.execute_hook(&PluginHook::AfterGenerate)?;
+        
+        info!("Generated HTML files successfully");
+        
+        Ok(())
+    }
+    
+    /// 生成依赖文章列表的页面：索引、分类、标签、归档、订阅源和搜索索引
+    pub fn generate_lists(&self, posts: &[Post]) -> Result<()> {
         // 生成索引页面（带分页）
-        let per_page = self.config.per_page.unwrap_or(10) as usize;
-        self.generate_paginated_index(posts, per_page)?;
+        self.generate_index(posts)?;
         
         // 生成分类页面
         self.generate_categories(posts)?;
@@ -89,23 +145,34 @@ impl HtmlGenerator {
         // 生成归档页面
         self.generate_archives(posts)?;
         
-        // 生成 RSS feed
-        self.generate_rss_feed(posts)?;
-        
-        // 生成 Atom feed
-        self.generate_atom_feed(posts)?;
+        // 生成 RSS 和 Atom 订阅源
+        self.generate_feeds(posts)?;
         
         // 生成搜索索引
         self.generate_search_index(posts)?;
         
-        // 生成成功，调用生成后钩子
-        self.plugin_manager.execute_hook(&PluginHook::AfterGenerate)?;
-        
-        info!("Generated HTML files successfully");
+        // 生成站点地图，包含以上列表页面
+        self.generate_sitemap(posts)?;
         
         Ok(())
     }
     
+    /// 生成首页索引（带分页）
+    pub fn generate_index(&self, posts: &[Post]) -> Result<()> {
+        let per_page = self.per_page(self.config.index_generator.as_ref());
+        self.generate_paginated_index(posts, per_page)
+    }
+    
+    /// 分类页面的输出目录
+    pub fn category_output_dir(&self, category: &str) -> PathBuf {
+        self.output_dir.join("categories").join(UrlHelper::slug(category))
+    }
+    
+    /// 标签页面的输出目录
+    pub fn tag_output_dir(&self, tag: &str) -> PathBuf {
+        self.output_dir.join("tags").join(UrlHelper::slug(tag))
+    }
+    
     /// 清理临时文件和资源
     pub fn cleanup(&self) -> Result<()> {
         // 调用清理钩子
@@ -117,39 +184,141 @@ impl HtmlGenerator {
         Ok(())
     }
     
-    /// 复制主题资源文件
-    fn copy_theme_assets(&self) -> Result<()> {
-        let theme_name = self.config.theme.clone().unwrap_or_default();
-        let theme_source = PathBuf::from("themes").join(&theme_name).join("source");
+    /// 处理主题资源文件，返回资源清单是否变化（变化后引用资源的页面需要重新生成）
+    ///
+    /// 按继承链收集父主题和子主题的资源，子主题中的同名文件覆盖父主题；使用内置主题时处理编译进程序的资源。
+    /// 资源经过 Sass 编译、压缩和指纹处理后写入输出目录，开启指纹时同时写出资源清单供 `asset_url` 使用。
+    /// 上次清单中不再引用的带哈希文件会被删除，避免输出目录中残留旧版本。
+    pub fn process_theme_assets(&self) -> Result<bool> {
+        self.update_theme_assets(None)
+    }
+    
+    /// 只重新处理变化的主题资源，`changed` 为相对于主题资源目录的路径，包括已删除的文件，返回资源清单是否变化
+    ///
+    /// Sass 局部文件变化时重新编译所有 Sass 样式表，无法得知哪些样式表引用了它。
+    /// 已删除的资源会同时删除其输出文件，其他主题中的同名资源随之生效。
+    pub fn process_changed_assets(&self, changed: &[String]) -> Result<bool> {
+        self.update_theme_assets(Some(changed))
+    }
+    
+    fn update_theme_assets(&self, changed: Option<&[String]>) -> Result<bool> {
+        self.plugin_manager.execute_hook(&PluginHook::BeforeAssetProcess)?;
+        
+        let files = self.collect_theme_assets()?;
+        let config = self.config.assets.clone().unwrap_or_default();
+        let pipeline = AssetPipeline::new(config.clone());
+        let manifest_path = self.output_dir.join(&config.manifest);
+        let previous = match changed {
+            Some(_) => self.urls.asset_manifest(),
+            None => Self::read_asset_manifest(&manifest_path),
+        };
         
-        if theme_source.exists() {
-            info!("Copying theme assets from {:?}", theme_source);
-            
-            // 遍历主题资源目录
-            for entry in WalkDir::new(&theme_source)
+        let (processed, manifest) = match changed {
+            None => pipeline.process_all(&files)?,
+            Some(changed) => {
+                let partial_changed = changed.iter().any(|path| pipeline.is_sass_partial(path));
+                let selected: Vec<AssetFile> = files.iter()
+                    .filter(|file| changed.contains(&file.path) || (partial_changed && pipeline.is_sass(&file.path)))
+                    .cloned()
+                    .collect();
+                let (processed, updated) = pipeline.process_all(&selected)?;
+                
+                // 没有重新处理的资源沿用之前的清单
+                let mut manifest = previous.clone();
+                for path in changed.iter().chain(selected.iter().map(|file| &file.path)) {
+                    manifest.remove(&pipeline.target_path(path));
+                }
+                manifest.extend(updated);
+                
+                // 已删除的资源，带哈希的输出在下面随清单一起清理
+                for path in changed.iter().filter(|path| !files.iter().any(|file| &file.path == *path)) {
+                    let target = pipeline.target_path(path);
+                    let output = self.output_dir.join(&target);
+                    if !previous.contains_key(&target) && output.is_file() {
+                        debug!("删除已删除的资源: {}", target);
+                        fs::remove_file(output)?;
+                    }
+                }
+                (processed, manifest)
+            }
+        };
+        
+        // 删除上次生成的、不再被资源清单引用的带哈希文件
+        let outputs: HashSet<&str> = processed.iter().map(|asset| asset.output_path.as_str()).collect();
+        for (path, output) in previous {
+            if manifest.get(&path) != Some(&output) && !outputs.contains(output.as_str()) {
+                let stale = self.output_dir.join(&output);
+                if stale.is_file() {
+                    debug!("删除过期的资源文件: {}", output);
+                    fs::remove_file(stale)?;
+                }
+            }
+        }
+        
+        for asset in &processed {
+            let target_path = self.output_dir.join(&asset.output_path);
+            if let Some(parent) = target_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target_path, &asset.content)?;
+        }
+        
+        if config.fingerprint {
+            if let Some(parent) = manifest_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let sorted: BTreeMap<&String, &String> = manifest.iter().collect();
+            fs::write(manifest_path, serde_json::to_string_pretty(&sorted)?)?;
+        } else if manifest_path.is_file() {
+            fs::remove_file(manifest_path)?;
+        }
+        let changed = self.urls.set_asset_manifest(manifest);
+        
+        self.plugin_manager.execute_hook(&PluginHook::AfterAssetProcess)?;
+        info!("已处理 {} 个主题资源文件", processed.len());
+        Ok(changed)
+    }
+    
+    /// 读取上次生成时写出的资源清单，文件不存在或无法解析时返回空清单
+    fn read_asset_manifest(path: &Path) -> HashMap<String, String> {
+        fs::read_to_string(path).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+    
+    /// 收集主题资源，同名文件以继承链中靠前的主题为准
+    fn collect_theme_assets(&self) -> Result<Vec<AssetFile>> {
+        if self.renderer.embedded {
+            return Ok(embedded::assets()
+                .map(|(path, content)| AssetFile { path: path.to_string(), content: content.as_bytes().to_vec(), source: None })
+                .collect());
+        }
+        
+        let source_dirs: Vec<PathBuf> = self.renderer.source_dirs().into_iter().filter(|dir| dir.exists()).collect();
+        if source_dirs.is_empty() {
+            warn!("Theme source directory not found: {:?}", self.renderer.source_dir());
+            return Ok(Vec::new());
+        }
+        
+        let mut files = BTreeMap::new();
+        for theme_source in source_dirs.iter().rev() {
+            debug!("收集主题资源: {:?}", theme_source);
+            for entry in WalkDir::new(theme_source)
                 .into_iter()
                 .filter_map(|e| e.ok())
                 .filter(|e| e.file_type().is_file())
             {
                 let source_path = entry.path();
-                let relative_path = source_path.strip_prefix(&theme_source)?;
-                let target_path = self.output_dir.join(relative_path);
-                
-                // 确保目标目录存在
-                if let Some(parent) = target_path.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                
-                // 复制文件
-                fs::copy(source_path, target_path)?;
+                let path = source_path.strip_prefix(theme_source)?.to_string_lossy().replace('\\', "/");
+                files.insert(path.clone(), AssetFile {
+                    path,
+                    content: fs::read(source_path)?,
+                    source: Some(source_path.to_path_buf()),
+                });
             }
-            
-            info!("Theme assets copied successfully");
-        } else {
-            warn!("Theme source directory not found: {:?}", theme_source);
         }
         
-        Ok(())
+        Ok(files.into_values().collect())
     }
     
     /// 生成所有文章页面
@@ -165,33 +334,114 @@ impl HtmlGenerator {
     fn generate_paginated_index(&self, posts: &[Post], page_size: usize) -> Result<()> {
         info!("Generating paginated index pages...");
         
-        let total_posts = posts.len();
-        let total_pages = (total_posts + page_size - 1) / page_size;
+        let template_name = "index.html";
+        if !self.renderer.has_layout(template_name) {
+            return Err(anyhow!("找不到首页模板文件: {:?}", self.renderer.theme_dir.join("layout").join(template_name)));
+        }
+        
+        let posts: Vec<&Post> = posts.iter().collect();
+        self.generate_paginated(&posts, page_size, "", &self.output_dir, |page_posts, pagination, output_file| {
+            let page = self.list_page("index", &self.config.title, page_posts, pagination);
+            self.render_list_page(template_name, page, output_file)
+        })
+    }
+    
+    /// 按 `per_page` 分页生成列表页面
+    ///
+    /// 第一页写入 `output_dir/index.html`，第 N 页写入 `output_dir/<pagination_dir>/N/index.html`。
+    /// `per_page` 为 0 时所有文章显示在同一页。
+    fn generate_paginated<F>(&self, posts: &[&Post], per_page: usize, base: &str, output_dir: &Path, mut render: F) -> Result<()>
+    where
+        F: FnMut(&[&Post], serde_json::Map<String, Value>, &Path) -> Result<()>,
+    {
+        let pagination_dir = self.pagination_dir();
+        let per_page = if per_page == 0 { posts.len().max(1) } else { per_page };
+        let total_pages = posts.len().div_ceil(per_page).max(1);
+        
+        // 清理之前生成的多余分页，避免文章减少后残留页面。
+        // 只删除页码超出总页数的目录，分页目录下还可能有同名分类或标签的页面
+        let stale_dir = output_dir.join(&pagination_dir);
+        if stale_dir.is_dir() {
+            for entry in fs::read_dir(&stale_dir)? {
+                let entry = entry?;
+                let stale = entry.file_name().to_str()
+                    .and_then(|name| name.parse::<usize>().ok())
+                    .is_some_and(|page_num| page_num > total_pages);
+                if stale && entry.file_type()?.is_dir() {
+                    fs::remove_dir_all(entry.path())?;
+                }
+            }
+        }
         
         for page_num in 1..=total_pages {
-            let start_idx = (page_num - 1) * page_size;
-            let end_idx = std::cmp::min(start_idx + page_size, total_posts);
-            let page_posts: Vec<&Post> = posts[start_idx..end_idx].iter().collect();
+            let start_idx = (page_num - 1) * per_page;
+            let end_idx = std::cmp::min(start_idx + per_page, posts.len());
             
-            let file_name = if page_num == 1 {
-                "index.html".to_string()
+            let output_file = if page_num == 1 {
+                output_dir.join("index.html")
             } else {
-                format!("page/{}/index.html", page_num)
+                output_dir.join(&pagination_dir).join(page_num.to_string()).join("index.html")
             };
             
-            let output_file = self.output_dir.join(&file_name);
-            if let Some(parent) = output_file.parent() {
-                fs::create_dir_all(parent)?;
-            }
-            
-            self.generate_index_page(&page_posts, page_num, total_pages, &output_file)?;
+            // 渲染期间以当前页面为相对链接的起点
+            let pagination = self.pagination(base, page_num, total_pages);
+            with_current_path(&self.page_path(&output_file), || render(&posts[start_idx..end_idx], pagination, &output_file))?;
         }
         
         Ok(())
     }
     
+    /// 列表页面的分页信息
+    fn pagination(&self, base: &str, current: usize, total: usize) -> serde_json::Map<String, Value> {
+        let prev = (current > 1).then(|| current - 1);
+        let next = (current < total).then(|| current + 1);
+        
+        let mut pagination = serde_json::Map::new();
+        pagination.insert("current".to_string(), json!(current));
+        pagination.insert("total".to_string(), json!(total));
+        pagination.insert("prev".to_string(), json!(prev));
+        pagination.insert("next".to_string(), json!(next));
+        pagination.insert("prev_link".to_string(), json!(prev.map(|n| self.page_link(base, n))));
+        pagination.insert("next_link".to_string(), json!(next.map(|n| self.page_link(base, n))));
+        pagination.insert("current_url".to_string(), json!(self.page_link(base, current)));
+        pagination.insert("base".to_string(), json!(base));
+        pagination
+    }
+    
+    /// 输出文件相对于输出目录的路径，用作页面路径
+    fn page_path(&self, output_file: &Path) -> String {
+        output_file.strip_prefix(&self.output_dir)
+            .unwrap_or(output_file)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+    
+    /// 列表第 N 页的链接（相对于站点根目录）
+    fn page_link(&self, base: &str, page_num: usize) -> String {
+        if page_num == 1 {
+            base.to_string()
+        } else {
+            format!("{}{}/{}/", base, self.pagination_dir(), page_num)
+        }
+    }
+    
+    /// 分页目录名，默认为 `page`
+    fn pagination_dir(&self) -> String {
+        self.config.pagination_dir.clone()
+            .map(|dir| dir.trim_matches('/').to_string())
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or_else(|| "page".to_string())
+    }
+    
+    /// 列表页面的每页文章数：优先使用对应生成器的配置，否则使用全局 `per_page`
+    fn per_page(&self, generator: Option<&GeneratorConfig>) -> usize {
+        generator
+            .and_then(|g| g.per_page)
+            .unwrap_or_else(|| self.config.per_page.unwrap_or(10).max(0) as usize)
+    }
+    
     /// 生成分类页面
-    fn generate_categories(&self, posts: &[Post]) -> Result<()> {
+    pub fn generate_categories(&self, posts: &[Post]) -> Result<()> {
         info!("Generating category pages...");
         
         // 按分类对文章进行分组
@@ -208,43 +458,35 @@ impl HtmlGenerator {
         let categories_dir = self.output_dir.join("categories");
         fs::create_dir_all(&categories_dir)?;
         
+        let per_page = self.per_page(self.config.category_generator.as_ref());
+        
         // 生成分类索引页面
-        self.generate_categories_index(&categories)?;
+        self.generate_categories_index(&categories, per_page)?;
         
         // 生成每个分类的页面
+        let has_template = self.renderer.has_layout("category.html");
         for (category, category_posts) in categories {
-            let category_dir = categories_dir.join(&category);
+            let category_dir = self.category_output_dir(&category);
             fs::create_dir_all(&category_dir)?;
+            let base = UrlHelper::category_path(&category);
             
-            // 生成分类文章列表页面
-            let mut content = String::with_capacity(4096);
-            self.write_html_header(&mut content, &format!("Category: {}", category))?;
-            
-            writeln!(&mut content, "    <div class=\"category-posts\">")?;
-            writeln!(&mut content, "        <h1>Category: {}</h1>", category)?;
-            writeln!(&mut content, "        <p>Posts: {}</p>", category_posts.len())?;
-            
-            // 添加文章列表
-            for post in category_posts {
-                writeln!(&mut content, "        <article class=\"post-item\">")?;
-                writeln!(&mut content, "            <h2><a href=\"/{}\">{}</a></h2>", post.path, post.title)?;
-                writeln!(&mut content, "            <time>{}</time>", post.date.format("%Y-%m-%d"))?;
-                writeln!(&mut content, "        </article>")?;
-            }
-            
-            writeln!(&mut content, "    </div>")?;
-            
-            self.write_html_footer(&mut content)?;
-            
-            // 写入文件
-            fs::write(category_dir.join("index.html"), content)?;
+            self.generate_paginated(&category_posts, per_page, &base, &category_dir, |page_posts, pagination, output_file| {
+                if has_template {
+                    let mut page = self.list_page("category", &format!("分类: {}", category), page_posts, pagination);
+                    page.insert("category".to_string(), json!(category));
+                    self.render_list_page("category.html", page, output_file)
+                } else {
+                    let content = self.fallback_post_list(&format!("Category: {}", category), "category-posts", page_posts, &pagination)?;
+                    self.write_page(output_file, content)
+                }
+            })?;
         }
         
         Ok(())
     }
     
     /// 生成标签页面
-    fn generate_tags(&self, posts: &[Post]) -> Result<()> {
+    pub fn generate_tags(&self, posts: &[Post]) -> Result<()> {
         info!("Generating tag pages...");
         
         // 按标签对文章进行分组
@@ -261,309 +503,444 @@ impl HtmlGenerator {
         let tags_dir = self.output_dir.join("tags");
         fs::create_dir_all(&tags_dir)?;
         
+        let per_page = self.per_page(self.config.tag_generator.as_ref());
+        
         // 生成标签索引页面
-        self.generate_tags_index(&tags)?;
+        self.generate_tags_index(&tags, per_page)?;
         
         // 生成每个标签的页面
+        let has_template = self.renderer.has_layout("tag.html");
         for (tag, tag_posts) in tags {
-            let tag_dir = tags_dir.join(&tag);
+            let tag_dir = self.tag_output_dir(&tag);
             fs::create_dir_all(&tag_dir)?;
+            let base = UrlHelper::tag_path(&tag);
             
-            // 生成标签文章列表页面
-            let mut content = String::with_capacity(4096);
-            self.write_html_header(&mut content, &format!("Tag: {}", tag))?;
-            
-            writeln!(&mut content, "    <div class=\"tag-posts\">")?;
-            writeln!(&mut content, "        <h1>Tag: {}</h1>", tag)?;
-            writeln!(&mut content, "        <p>Posts: {}</p>", tag_posts.len())?;
-            
-            // 添加文章列表
-            for post in tag_posts {
-                writeln!(&mut content, "        <article class=\"post-item\">")?;
-                writeln!(&mut content, "            <h2><a href=\"/{}\">{}</a></h2>", post.path, post.title)?;
-                writeln!(&mut content, "            <time>{}</time>", post.date.format("%Y-%m-%d"))?;
-                writeln!(&mut content, "        </article>")?;
-            }
-            
-            writeln!(&mut content, "    </div>")?;
-            
-            self.write_html_footer(&mut content)?;
-            
-            // 写入文件
-            fs::write(tag_dir.join("index.html"), content)?;
+            self.generate_paginated(&tag_posts, per_page, &base, &tag_dir, |page_posts, pagination, output_file| {
+                if has_template {
+                    let mut page = self.list_page("tag", &format!("标签: {}", tag), page_posts, pagination);
+                    page.insert("tag".to_string(), json!(tag));
+                    self.render_list_page("tag.html", page, output_file)
+                } else {
+                    let content = self.fallback_post_list(&format!("Tag: {}", tag), "tag-posts", page_posts, &pagination)?;
+                    self.write_page(output_file, content)
+                }
+            })?;
         }
         
         Ok(())
     }
     
     /// 生成归档页面
-    fn generate_archives(&self, posts: &[Post]) -> Result<()> {
+    ///
+    /// 生成 `archives/` 总归档，以及按配置生成的年（`archives/2024/`）、
+    /// 月（`archives/2024/05/`）和日（`archives/2024/05/01/`）归档，均支持分页。
+    pub fn generate_archives(&self, posts: &[Post]) -> Result<()> {
         info!("Generating archive pages...");
         
-        // 按年月对文章进行分组
-        let mut archives: HashMap<(i32, u32), Vec<&Post>> = HashMap::new();
+        let generator = self.config.archive_generator.clone().unwrap_or_default();
+        let per_page = self.per_page(Some(&generator));
+        let yearly = generator.yearly.unwrap_or(true);
+        let monthly = yearly && generator.monthly.unwrap_or(true);
+        let daily = monthly && generator.daily.unwrap_or(false);
+        
+        // 按年、月、日对文章进行分组
+        let mut years: BTreeMap<i32, Vec<&Post>> = BTreeMap::new();
+        let mut months: BTreeMap<(i32, u32), Vec<&Post>> = BTreeMap::new();
+        let mut days: BTreeMap<(i32, u32, u32), Vec<&Post>> = BTreeMap::new();
         for post in posts {
-            let year = post.date.year();
-            let month = post.date.month();
-            archives.entry((year, month))
-                .or_default()
-                .push(post);
+            let (year, month, day) = (post.date.year(), post.date.month(), post.date.day());
+            years.entry(year).or_default().push(post);
+            months.entry((year, month)).or_default().push(post);
+            days.entry((year, month, day)).or_default().push(post);
         }
         
-        // 创建归档目录
+        // 归档页面每次完整生成，先清理旧的年月日目录
         let archives_dir = self.output_dir.join("archives");
+        if archives_dir.is_dir() {
+            fs::remove_dir_all(&archives_dir)?;
+        }
         fs::create_dir_all(&archives_dir)?;
         
+        // 归档列表：优先按月，未生成月归档时按年
+        let archive_items: Vec<Value> = if monthly {
+            months.iter().rev().map(|((year, month), month_posts)| json!({
+                "name": format!("{}-{:02}", year, month),
+                "year": year,
+                "month": month,
+                "path": format!("archives/{}/{:02}/", year, month),
+                "count": month_posts.len(),
+            })).collect()
+        } else if yearly {
+            years.iter().rev().map(|(year, year_posts)| json!({
+                "name": year.to_string(),
+                "year": year,
+                "month": Value::Null,
+                "path": format!("archives/{}/", year),
+                "count": year_posts.len(),
+            })).collect()
+        } else {
+            Vec::new()
+        };
+        
+        let archive_page = ArchivePage {
+            has_template: self.renderer.has_layout("archive.html"),
+            items: &archive_items,
+            per_page,
+        };
+        
         // 生成归档索引页面
-        self.generate_archives_index(&archives)?;
+        let all_posts: Vec<&Post> = posts.iter().collect();
+        if archive_page.has_template {
+            self.generate_archive_page(&archive_page, "归档", &all_posts, "archives/", &archives_dir, (None, None, None))?;
+        } else {
+            with_current_path("archives/index.html", || self.generate_archives_index(&archive_items))?;
+        }
+        
+        if yearly {
+            for (year, year_posts) in &years {
+                let base = format!("archives/{}/", year);
+                let output_dir = archives_dir.join(year.to_string());
+                self.generate_archive_page(&archive_page, &format!("归档: {}", year), year_posts, &base, &output_dir,
+                    (Some(*year), None, None))?;
+            }
+        }
+        
+        if monthly {
+            for ((year, month), month_posts) in &months {
+                let base = format!("archives/{}/{:02}/", year, month);
+                let output_dir = archives_dir.join(year.to_string()).join(format!("{:02}", month));
+                self.generate_archive_page(&archive_page, &format!("归档: {}-{:02}", year, month), month_posts, &base, &output_dir,
+                    (Some(*year), Some(*month), None))?;
+            }
+        }
+        
+        if daily {
+            for ((year, month, day), day_posts) in &days {
+                let base = format!("archives/{}/{:02}/{:02}/", year, month, day);
+                let output_dir = archives_dir.join(year.to_string()).join(format!("{:02}", month)).join(format!("{:02}", day));
+                self.generate_archive_page(&archive_page, &format!("归档: {}-{:02}-{:02}", year, month, day), day_posts, &base, &output_dir,
+                    (Some(*year), Some(*month), Some(*day)))?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 生成一个（分页的）归档页面，`date` 为归档的年、月、日，总归档均为 `None`
+    fn generate_archive_page(&self, archive_page: &ArchivePage, title: &str, posts: &[&Post], base: &str, output_dir: &Path,
+        date: (Option<i32>, Option<u32>, Option<u32>)) -> Result<()> {
+        let (year, month, day) = date;
+        self.generate_paginated(posts, archive_page.per_page, base, output_dir, |page_posts, pagination, output_file| {
+            if archive_page.has_template {
+                let mut page = self.list_page("archive", title, page_posts, pagination);
+                page.insert("archives".to_string(), json!(archive_page.items));
+                page.insert("year".to_string(), json!(year));
+                page.insert("month".to_string(), json!(month));
+                page.insert("day".to_string(), json!(day));
+                self.render_list_page("archive.html", page, output_file)
+            } else {
+                let content = self.fallback_post_list(&format!("Archive: {}", title), "archive-posts", page_posts, &pagination)?;
+                self.write_page(output_file, content)
+            }
+        })
+    }
+    
+    /// 生成分类索引页面
+    fn generate_categories_index(&self, categories: &HashMap<String, Vec<&Post>>, per_page: usize) -> Result<()> {
+        // 按分类名称排序
+        let mut category_names: Vec<&String> = categories.keys().collect();
+        category_names.sort();
         
-        // 生成每个年月的归档页面
-        for ((year, month), archive_posts) in &archives {
-            let year_dir = archives_dir.join(year.to_string());
-            fs::create_dir_all(&year_dir)?;
+        // 获取所有文章并去重
+        let all_posts = Self::unique_posts(categories.values());
+        
+        let output_dir = self.output_dir.join("categories");
+        let has_template = self.renderer.has_layout("categories.html");
+        let items: Vec<Value> = category_names.iter().map(|name| json!({
+            "name": name,
+            "path": UrlHelper::category_path(name),
+            "count": categories[*name].len(),
+        })).collect();
+        
+        self.generate_paginated(&all_posts, per_page, "categories/", &output_dir, |page_posts, pagination, output_file| {
+            if has_template {
+                let mut page = self.list_page("categories", "分类", page_posts, pagination);
+                page.insert("categories".to_string(), json!(items));
+                return self.render_list_page("categories.html", page, output_file);
+            }
             
-            // 生成月度归档页面
             let mut content = String::with_capacity(4096);
-            self.write_html_header(&mut content, &format!("Archive: {}-{:02}", year, month))?;
+            self.write_html_header(&mut content, "分类")?;
             
-            writeln!(&mut content, "    <div class=\"archive-posts\">")?;
-            writeln!(&mut content, "        <h1>Archive: {}-{:02}</h1>", year, month)?;
-            writeln!(&mut content, "        <p>Posts: {}</p>", archive_posts.len())?;
+            writeln!(&mut content, "    <div class=\"categories-list\">")?;
+            writeln!(&mut content, "        <h1>分类</h1>")?;
             
-            // 添加文章列表
-            for post in archive_posts {
-                writeln!(&mut content, "        <article class=\"post-item\">")?;
-                writeln!(&mut content, "            <h2><a href=\"/{}\">{}</a></h2>", post.path, post.title)?;
-                writeln!(&mut content, "            <time>{}</time>", post.date.format("%Y-%m-%d"))?;
-                writeln!(&mut content, "        </article>")?;
+            for category_name in &category_names {
+                let posts = &categories[*category_name];
+                writeln!(&mut content, "        <div class=\"category-item\">")?;
+                writeln!(&mut content, "            <h2><a href=\"{}\">{}</a></h2>", 
+                         self.urls.url_for_category(category_name), category_name)?;
+                writeln!(&mut content, "            <span class=\"post-count\">{} 篇文章</span>", posts.len())?;
+                writeln!(&mut content, "        </div>")?;
             }
             
+            // 添加所有文章列表
+            writeln!(&mut content, "        <div class=\"all-posts\">")?;
+            writeln!(&mut content, "            <h2>全部分类文章</h2>")?;
+            self.write_post_items(&mut content, page_posts)?;
+            self.write_pagination_nav(&mut content, &pagination)?;
+            writeln!(&mut content, "        </div>")?;
             writeln!(&mut content, "    </div>")?;
             
             self.write_html_footer(&mut content)?;
             
-            // 写入文件
-            fs::write(year_dir.join(format!("{:02}.html", month)), content)?;
-        }
+            self.write_page(output_file, content)
+        })
+    }
+    
+    /// 生成标签索引页面
+    fn generate_tags_index(&self, tags: &HashMap<String, Vec<&Post>>, per_page: usize) -> Result<()> {
+        // 按标签名称排序
+        let mut tag_names: Vec<&String> = tags.keys().collect();
+        tag_names.sort();
         
-        Ok(())
+        // 获取所有文章并去重
+        let all_posts = Self::unique_posts(tags.values());
+        
+        let output_dir = self.output_dir.join("tags");
+        let has_template = self.renderer.has_layout("tags.html");
+        let items: Vec<Value> = tag_names.iter().map(|name| json!({
+            "name": name,
+            "path": UrlHelper::tag_path(name),
+            "count": tags[*name].len(),
+        })).collect();
+        
+        self.generate_paginated(&all_posts, per_page, "tags/", &output_dir, |page_posts, pagination, output_file| {
+            if has_template {
+                let mut page = self.list_page("tags", "标签", page_posts, pagination);
+                page.insert("tags".to_string(), json!(items));
+                return self.render_list_page("tags.html", page, output_file);
+            }
+            
+            let mut content = String::with_capacity(4096);
+            self.write_html_header(&mut content, "标签")?;
+            
+            writeln!(&mut content, "    <div class=\"tags-list\">")?;
+            writeln!(&mut content, "        <h1>标签</h1>")?;
+            writeln!(&mut content, "        <div class=\"tag-cloud\">")?;
+            
+            for tag_name in &tag_names {
+                let posts = &tags[*tag_name];
+                let font_size = 100.0 + (posts.len() as f32 * 10.0).min(100.0);
+                
+                writeln!(
+                    &mut content,
+                    "            <a href=\"{}\" style=\"font-size: {}%\">{} <span class=\"tag-count\">({})</span></a>",
+                    self.urls.url_for_tag(tag_name), font_size, tag_name, posts.len()
+                )?;
+            }
+            
+            writeln!(&mut content, "        </div>")?;
+            
+            // 添加所有文章列表
+            writeln!(&mut content, "        <div class=\"all-posts\">")?;
+            writeln!(&mut content, "            <h2>全部标签文章</h2>")?;
+            self.write_post_items(&mut content, page_posts)?;
+            self.write_pagination_nav(&mut content, &pagination)?;
+            writeln!(&mut content, "        </div>")?;
+            writeln!(&mut content, "    </div>")?;
+            
+            self.write_html_footer(&mut content)?;
+            
+            self.write_page(output_file, content)
+        })
     }
     
-    /// 生成分类索引页面
-    fn generate_categories_index(&self, categories: &HashMap<String, Vec<&Post>>) -> Result<()> {
+    /// 生成内置的归档索引页面，主题没有提供 `archive.html` 时使用
+    fn generate_archives_index(&self, archive_items: &[Value]) -> Result<()> {
+        let output_path = self.output_dir.join("archives").join("index.html");
+        
         let mut content = String::with_capacity(4096);
-        self.write_html_header(&mut content, "分类")?;
+        self.write_html_header(&mut content, "Archives")?;
         
-        writeln!(&mut content, "    <div class=\"categories-list\">")?;
-        writeln!(&mut content, "        <h1>分类</h1>")?;
+        writeln!(&mut content, "    <div class=\"archives-list\">")?;
+        writeln!(&mut content, "        <h1>Archives</h1>")?;
         
-        // 按分类名称排序
-        let mut category_names: Vec<&String> = categories.keys().collect();
-        category_names.sort();
+        let mut current_year = None;
+        for item in archive_items {
+            let year = item["year"].as_i64();
+            if current_year != year {
+                if current_year.is_some() {
+                    writeln!(&mut content, "        </div>")?;
+                }
+                writeln!(&mut content, "        <div class=\"year-group\">")?;
+                writeln!(&mut content, "            <h2>{}</h2>", year.unwrap_or_default())?;
+                current_year = year;
+            }
+            
+            writeln!(&mut content, "            <div class=\"month-group\">")?;
+            writeln!(&mut content, "                <h3><a href=\"{}\">{}</a> ({} posts)</h3>",
+                    self.urls.url_for(item["path"].as_str().unwrap_or_default(), None), item["name"].as_str().unwrap_or_default(), item["count"])?;
+            writeln!(&mut content, "            </div>")?;
+        }
         
-        for category_name in category_names {
-            let posts = &categories[category_name];
-            writeln!(&mut content, "        <div class=\"category-item\">")?;
-            writeln!(&mut content, "            <h2><a href=\"/categories/{}\">{}</a></h2>", 
-                     category_name, category_name)?;
-            writeln!(&mut content, "            <span class=\"post-count\">{} 篇文章</span>", posts.len())?;
+        if current_year.is_some() {
             writeln!(&mut content, "        </div>")?;
         }
         
-        // 获取所有文章并去重
-        let mut all_posts = Vec::new();
-        for (_, posts) in categories {
-            for &post in posts {
-                all_posts.push(post);
-            }
-        }
+        writeln!(&mut content, "    </div>")?;
+        
+        self.write_html_footer(&mut content)?;
+        
+        self.write_page(&output_path, content)
+    }
+    
+    /// 合并多个分组中的文章，去重后按日期倒序排列
+    fn unique_posts<'a, 'b: 'a>(groups: impl Iterator<Item = &'a Vec<&'b Post>>) -> Vec<&'b Post> {
+        let mut all_posts: Vec<&Post> = groups.flatten().copied().collect();
         
         // 去重
         all_posts.sort_by(|a, b| a.path.cmp(&b.path));
         all_posts.dedup_by(|a, b| a.path == b.path);
         
         // 按日期排序
-        all_posts.sort_by(|a, b| b.date.cmp(&a.date));
-        
-        // 添加所有文章列表
-        writeln!(&mut content, "        <div class=\"all-posts\">")?;
-        writeln!(&mut content, "            <h2>全部分类文章</h2>")?;
+        all_posts.sort_by_key(|p| std::cmp::Reverse(p.date));
+        all_posts
+    }
+    
+    /// 构建列表页面的 `page` 数据：页面类型、标题、文章列表和分页信息
+    fn list_page(&self, page_type: &str, title: &str, posts: &[&Post], pagination: serde_json::Map<String, Value>) -> serde_json::Map<String, Value> {
+        let mut page = serde_json::Map::new();
+        page.insert("type".to_string(), json!(page_type));
+        page.insert("title".to_string(), json!(title));
+        page.insert("posts".to_string(), Value::Array(posts.iter().map(|post| Self::post_summary(post)).collect()));
+        page.extend(pagination);
+        page
+    }
+    
+    /// 渲染列表页面并写入文件
+    fn render_list_page(&self, template_name: &str, page: serde_json::Map<String, Value>, output_file: &Path) -> Result<()> {
+        let mut context = self.base_context();
+        context.insert("page", &Value::Object(page));
         
-        for post in &all_posts {
-            writeln!(&mut content, "            <article class=\"post-item\">")?;
-            writeln!(&mut content, "                <h3><a href=\"/{}\">{}</a></h3>", post.path, post.title)?;
-            writeln!(&mut content, "                <div class=\"post-meta\">")?;
-            writeln!(&mut content, "                    <time>{}</time>", post.date.format("%Y-%m-%d"))?;
-            
-            // 显示分类
-            if !post.categories.is_empty() {
-                write!(&mut content, " | 分类: ")?;
-                for (i, category) in post.categories.iter().enumerate() {
-                    if i > 0 {
-                        write!(&mut content, ", ")?;
-                    }
-                    write!(&mut content, "<a href=\"/categories/{}\">{}</a>", category, category)?;
-                }
-            }
-            
-            // 显示标签
-            if !post.tags.is_empty() {
-                write!(&mut content, " | 标签: ")?;
-                for (i, tag) in post.tags.iter().enumerate() {
-                    if i > 0 {
-                        write!(&mut content, ", ")?;
-                    }
-                    write!(&mut content, "<a href=\"/tags/{}\">{}</a>", tag, tag)?;
-                }
-            }
-            
-            writeln!(&mut content, "                </div>")?;
-            writeln!(&mut content, "            </article>")?;
-        }
+        let rendered = self.renderer.render_template(template_name, &context)?;
+        self.write_page(output_file, rendered)
+    }
+    
+    /// 复制文章引用的图片并生成不同尺寸和格式的版本
+    pub fn process_images(&self, posts: &[Post]) -> Result<()> {
+        let Some(pipeline) = &self.images else {
+            return Ok(());
+        };
         
-        writeln!(&mut content, "        </div>")?;
-        writeln!(&mut content, "    </div>")?;
+        let sources = pipeline.collect(posts, &self.urls);
+        let processed = pipeline.process(&sources, &self.output_dir)?;
+        self.urls.images().extend(processed);
         
-        self.write_html_footer(&mut content)?;
+        Ok(())
+    }
+    
+    /// 写入页面文件，必要时创建父目录，写入前执行插件的改写规则和 HTML 压缩
+    fn write_page(&self, output_file: &Path, content: String) -> Result<()> {
+        if let Some(parent) = output_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let page_path = self.page_path(output_file);
+        self.written_pages.lock().unwrap().insert(page_path.clone());
         
-        let output_path = self.output_dir.join("categories").join("index.html");
-        fs::create_dir_all(output_path.parent().unwrap())?;
-        fs::write(output_path, content)?;
+        let content = if self.post_processor.is_enabled() {
+            // 页面中的相对链接以该页面为起点
+            with_current_path(&page_path, || self.post_processor.process(&content, &page_path))
+                .map_err(|e| anyhow!("处理页面失败: {}: {}", output_file.display(), e))?
+        } else {
+            content
+        };
+        fs::write(output_file, content)?;
         
         Ok(())
     }
     
-    /// 生成标签索引页面
-    fn generate_tags_index(&self, tags: &HashMap<String, Vec<&Post>>) -> Result<()> {
+    /// 内置的文章列表页面，主题没有提供对应模板时使用
+    fn fallback_post_list(&self, title: &str, class: &str, posts: &[&Post], pagination: &serde_json::Map<String, Value>) -> Result<String> {
         let mut content = String::with_capacity(4096);
-        self.write_html_header(&mut content, "标签")?;
+        self.write_html_header(&mut content, title)?;
         
-        writeln!(&mut content, "    <div class=\"tags-list\">")?;
-        writeln!(&mut content, "        <h1>标签</h1>")?;
-        writeln!(&mut content, "        <div class=\"tag-cloud\">")?;
+        writeln!(&mut content, "    <div class=\"{}\">", class)?;
+        writeln!(&mut content, "        <h1>{}</h1>", title)?;
+        writeln!(&mut content, "        <p>Posts: {}</p>", posts.len())?;
         
-        // 按标签名称排序
-        let mut tag_names: Vec<&String> = tags.keys().collect();
-        tag_names.sort();
-        
-        for tag_name in tag_names {
-            let posts = &tags[tag_name];
-            let font_size = 100.0 + (posts.len() as f32 * 10.0).min(100.0);
-            
-            writeln!(
-                &mut content,
-                "            <a href=\"/tags/{}\" style=\"font-size: {}%\">{} <span class=\"tag-count\">({})</span></a>",
-                tag_name, font_size, tag_name, posts.len()
-            )?;
+        // 添加文章列表
+        for post in posts {
+            writeln!(&mut content, "        <article class=\"post-item\">")?;
+            writeln!(&mut content, "            <h2><a href=\"{}\">{}</a></h2>", self.urls.url_for(&post.path, None), post.title)?;
+            writeln!(&mut content, "            <time>{}</time>", post.date.format("%Y-%m-%d"))?;
+            writeln!(&mut content, "        </article>")?;
         }
         
-        writeln!(&mut content, "        </div>")?;
-        
-        // 获取所有文章并去重
-        let mut all_posts = Vec::new();
-        for (_, posts) in tags {
-            for &post in posts {
-                all_posts.push(post);
-            }
-        }
+        self.write_pagination_nav(&mut content, pagination)?;
+        writeln!(&mut content, "    </div>")?;
         
-        // 去重
-        all_posts.sort_by(|a, b| a.path.cmp(&b.path));
-        all_posts.dedup_by(|a, b| a.path == b.path);
+        self.write_html_footer(&mut content)?;
         
-        // 按日期排序
-        all_posts.sort_by(|a, b| b.date.cmp(&a.date));
+        Ok(content)
+    }
+    
+    /// 写入内置页面的上一页/下一页导航
+    fn write_pagination_nav(&self, content: &mut String, pagination: &serde_json::Map<String, Value>) -> Result<()> {
+        let prev_link = pagination.get("prev_link").and_then(Value::as_str);
+        let next_link = pagination.get("next_link").and_then(Value::as_str);
+        if prev_link.is_none() && next_link.is_none() {
+            return Ok(());
+        }
         
-        // 添加所有文章列表
-        writeln!(&mut content, "        <div class=\"all-posts\">")?;
-        writeln!(&mut content, "            <h2>全部标签文章</h2>")?;
+        writeln!(content, "        <nav class=\"pagination\">")?;
+        if let Some(link) = prev_link {
+            writeln!(content, "            <a href=\"{}\" class=\"prev\">&laquo; Prev</a>", self.urls.url_for(link, None))?;
+        }
+        writeln!(content, "            <span class=\"page-number current\">{} / {}</span>",
+                 pagination["current"], pagination["total"])?;
+        if let Some(link) = next_link {
+            writeln!(content, "            <a href=\"{}\" class=\"next\">Next &raquo;</a>", self.urls.url_for(link, None))?;
+        }
+        writeln!(content, "        </nav>")?;
         
-        for post in &all_posts {
-            writeln!(&mut content, "            <article class=\"post-item\">")?;
-            writeln!(&mut content, "                <h3><a href=\"/{}\">{}</a></h3>", post.path, post.title)?;
-            writeln!(&mut content, "                <div class=\"post-meta\">")?;
-            writeln!(&mut content, "                    <time>{}</time>", post.date.format("%Y-%m-%d"))?;
+        Ok(())
+    }
+    
+    /// 写入带分类和标签的文章条目
+    fn write_post_items(&self, content: &mut String, posts: &[&Post]) -> Result<()> {
+        for post in posts {
+            writeln!(content, "            <article class=\"post-item\">")?;
+            writeln!(content, "                <h3><a href=\"{}\">{}</a></h3>", self.urls.url_for(&post.path, None), post.title)?;
+            writeln!(content, "                <div class=\"post-meta\">")?;
+            writeln!(content, "                    <time>{}</time>", post.date.format("%Y-%m-%d"))?;
             
             // 显示分类
             if !post.categories.is_empty() {
-                write!(&mut content, " | 分类: ")?;
+                write!(content, " | 分类: ")?;
                 for (i, category) in post.categories.iter().enumerate() {
                     if i > 0 {
-                        write!(&mut content, ", ")?;
+                        write!(content, ", ")?;
                     }
-                    write!(&mut content, "<a href=\"/categories/{}\">{}</a>", category, category)?;
+                    write!(content, "<a href=\"{}\">{}</a>", self.urls.url_for_category(category), category)?;
                 }
             }
             
             // 显示标签
             if !post.tags.is_empty() {
-                write!(&mut content, " | 标签: ")?;
+                write!(content, " | 标签: ")?;
                 for (i, tag) in post.tags.iter().enumerate() {
                     if i > 0 {
-                        write!(&mut content, ", ")?;
+                        write!(content, ", ")?;
                     }
-                    write!(&mut content, "<a href=\"/tags/{}\">{}</a>", tag, tag)?;
-                }
-            }
-            
-            writeln!(&mut content, "                </div>")?;
-            writeln!(&mut content, "            </article>")?;
-        }
-        
-        writeln!(&mut content, "        </div>")?;
-        writeln!(&mut content, "    </div>")?;
-        
-        self.write_html_footer(&mut content)?;
-        
-        let output_path = self.output_dir.join("tags").join("index.html");
-        fs::create_dir_all(output_path.parent().unwrap())?;
-        fs::write(output_path, content)?;
-        
-        Ok(())
-    }
-    
-    /// 生成归档索引页面
-    fn generate_archives_index(&self, archives: &HashMap<(i32, u32), Vec<&Post>>) -> Result<()> {
-        let mut content = String::with_capacity(4096);
-        self.write_html_header(&mut content, "Archives")?;
-        
-        writeln!(&mut content, "    <div class=\"archives-list\">")?;
-        writeln!(&mut content, "        <h1>Archives</h1>")?;
-        
-        // 按年月排序
-        let mut archive_dates: Vec<(i32, u32)> = archives.keys().cloned().collect();
-        archive_dates.sort_by(|a, b| b.cmp(a));
-        
-        let mut current_year = None;
-        for (year, month) in archive_dates {
-            if current_year != Some(year) {
-                if current_year.is_some() {
-                    writeln!(&mut content, "        </div>")?;
+                    write!(content, "<a href=\"{}\">{}</a>", self.urls.url_for_tag(tag), tag)?;
                 }
-                writeln!(&mut content, "        <div class=\"year-group\">")?;
-                writeln!(&mut content, "            <h2>{}</h2>", year)?;
-                current_year = Some(year);
             }
             
-            let posts = &archives[&(year, month)];
-            writeln!(&mut content, "            <div class=\"month-group\">")?;
-            writeln!(&mut content, "                <h3><a href=\"/archives/{}/{:02}.html\">{}-{:02}</a> ({} posts)</h3>",
-                    year, month, year, month, posts.len())?;
-            writeln!(&mut content, "            </div>")?;
-        }
-        
-        if current_year.is_some() {
-            writeln!(&mut content, "        </div>")?;
+            writeln!(content, "                </div>")?;
+            writeln!(content, "            </article>")?;
         }
         
-        writeln!(&mut content, "    </div>")?;
-        
-        self.write_html_footer(&mut content)?;
-        
-        let output_path = self.output_dir.join("archives").join("index.html");
-        fs::create_dir_all(output_path.parent().unwrap())?;
-        fs::write(output_path, content)?;
-        
         Ok(())
     }
     
@@ -575,20 +952,20 @@ impl HtmlGenerator {
         writeln!(content, "    <meta charset=\"UTF-8\">")?;
         writeln!(content, "    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">")?;
         writeln!(content, "    <title>{}</title>", title)?;
-        writeln!(content, "    <link rel=\"stylesheet\" href=\"/css/style.css\">")?;
+        writeln!(content, "    <link rel=\"stylesheet\" href=\"{}\">", self.urls.asset_url("css/style.css"))?;
         writeln!(content, "</head>")?;
         writeln!(content, "<body>")?;
         writeln!(content, "    <header class=\"site-header\">")?;
         writeln!(content, "        <div class=\"container\">")?;
-        writeln!(content, "            <h1><a href=\"/\">{}</a></h1>", self.config.title)?;
+        writeln!(content, "            <h1><a href=\"{}\">{}</a></h1>", self.urls.url_for("", None), self.config.title)?;
         if let Some(subtitle) = &self.config.subtitle {
             writeln!(content, "            <p class=\"site-description\">{}</p>", subtitle)?;
         }
         writeln!(content, "            <nav class=\"site-nav\">")?;
-        writeln!(content, "                <a href=\"/\">Home</a>")?;
-        writeln!(content, "                <a href=\"/archives/\">Archives</a>")?;
-        writeln!(content, "                <a href=\"/categories/\">Categories</a>")?;
-        writeln!(content, "                <a href=\"/tags/\">Tags</a>")?;
+        writeln!(content, "                <a href=\"{}\">Home</a>", self.urls.url_for("", None))?;
+        writeln!(content, "                <a href=\"{}\">Archives</a>", self.urls.url_for("archives/", None))?;
+        writeln!(content, "                <a href=\"{}\">Categories</a>", self.urls.url_for("categories/", None))?;
+        writeln!(content, "                <a href=\"{}\">Tags</a>", self.urls.url_for("tags/", None))?;
         writeln!(content, "            </nav>")?;
         writeln!(content, "        </div>")?;
         writeln!(content, "    </header>")?;
@@ -616,387 +993,119 @@ impl HtmlGenerator {
     }
     
     /// 生成单个文章页面
-    fn generate_post(&self, post: &Post) -> Result<()> {
+    pub fn generate_post(&self, post: &Post) -> Result<()> {
         debug!("Generating post: {}", post.title);
         
         // 创建输出路径
         let post_path = self.output_dir.join(&post.path);
-        fs::create_dir_all(post_path.parent().unwrap_or(&self.output_dir))?;
-        
-        // 使用Tera模板渲染文章页面
-        let template_name = "post.html";
-        let mut context = Context::new();
-        
-        // 添加页面数据
-        context.insert("page", post);
-        
-        // 添加站点数据
-        // 创建站点数据结构
-        let mut site_data = serde_json::Map::new();
-        
-        // 添加配置信息 - 为了避免missing字段问题，我们手动构建一个完整的config对象
-        let mut config_data = serde_json::Map::new();
-        config_data.insert("title".to_string(), serde_json::Value::String(self.config.title.clone()));
-        config_data.insert("subtitle".to_string(), serde_json::Value::String(self.config.subtitle.clone().unwrap_or_default()));
-        config_data.insert("description".to_string(), serde_json::Value::String(self.config.description.clone().unwrap_or_default()));
-        config_data.insert("author".to_string(), serde_json::Value::String(self.config.author.clone().unwrap_or_default()));
-        
-        // 语言字段处理，确保提供默认值
-        let language = if self.config.language.clone().unwrap_or_default().is_empty() {
-            "zh-CN".to_string()
-        } else {
-            self.config.language.clone().unwrap_or_default()
-        };
-        config_data.insert("language".to_string(), serde_json::Value::String(language));
-        
-        config_data.insert("timezone".to_string(), serde_json::Value::String(self.config.timezone.clone().unwrap_or_default()));
-        config_data.insert("url".to_string(), serde_json::Value::String(self.config.url.clone().unwrap_or_default()));
         
-        // root字段处理，确保提供默认值
-        let root = if self.config.root.clone().unwrap_or_default().is_empty() {
-            "/".to_string()
-        } else {
-            self.config.root.clone().unwrap_or_default()
-        };
-        config_data.insert("root".to_string(), serde_json::Value::String(root));
-        
-        // 默认的永久链接格式
-        config_data.insert("permalink".to_string(), serde_json::Value::String(":year/:month/:day/:title/".to_string()));
-        config_data.insert("theme".to_string(), serde_json::Value::String(self.config.theme.clone().unwrap_or_default()));
-        
-        // 添加各种目录配置
-        config_data.insert("source_dir".to_string(), serde_json::Value::String("source".to_string()));
-        config_data.insert("public_dir".to_string(), serde_json::Value::String("public".to_string()));
-        config_data.insert("tag_dir".to_string(), serde_json::Value::String("tags".to_string()));
-        config_data.insert("category_dir".to_string(), serde_json::Value::String("categories".to_string()));
-        config_data.insert("archive_dir".to_string(), serde_json::Value::String("archives".to_string()));
-        
-        // 添加关键字和其他可选配置
-        config_data.insert("keywords".to_string(), serde_json::Value::String("".to_string()));
-        
-        // 添加分页配置
-        let per_page = self.config.per_page.unwrap_or(10);
-        config_data.insert("per_page".to_string(), serde_json::Value::Number(serde_json::Number::from(per_page as i64)));
-        
-        config_data.insert("pagination_dir".to_string(), serde_json::Value::String("page".to_string()));
-        
-        // 将整个配置添加到站点数据中
-        site_data.insert("config".to_string(), serde_json::Value::Object(config_data));
-        
-        // 添加其它站点信息（顶级）
-        site_data.insert("title".to_string(), serde_json::Value::String(self.config.title.clone()));
-        site_data.insert("url".to_string(), serde_json::Value::String(self.config.url.clone().unwrap_or_default()));
-        site_data.insert("author".to_string(), serde_json::Value::String(self.config.author.clone().unwrap_or_default()));
-        
-        context.insert("site", &serde_json::Value::Object(site_data));
-        
-        // 添加当前时间函数
-        let now = chrono::Utc::now();
-        context.insert("now", &tera::Value::String(now.format("%Y-%m-%d %H:%M:%S").to_string()));
-        
-        // 添加插件数据
-        let plugin_manager = &self.plugin_manager;
-        match plugin_manager.get_all_plugins() {
-            Ok(plugins) => {
-                // 为特定插件添加标志
-                let mut plugin_enabled = HashMap::new();
-                for plugin in &plugins {
-                    let name = plugin.name();
-                    debug!("启用插件 {} 在文章模板中", name);
-                    // 使用插件名作为键（统一使用中横线格式，与配置名保持一致）
-                    let config_name = if name.contains('_') {
-                        name.replace('_', "-")
-                    } else {
-                        name.to_string()
-                    };
-                    plugin_enabled.insert(config_name, true);
-                }
-                context.insert("plugins", &plugin_enabled);
-            },
-            Err(e) => {
-                warn!("获取插件列表失败: {}", e);
-            }
-        }
-        
-        // 创建Tera实例
-        let mut tera = Tera::default();
-        
-        // 从配置中获取主题目录路径
-        let theme_name = &self.config.theme.clone().unwrap_or_default();
-        let base_dir = self.output_dir.parent().unwrap_or(&self.output_dir);
-        let theme_dir = base_dir.join("themes").join(theme_name);
-        let layout_dir = theme_dir.join("layout");
-        
-        // 加载所有必要的模板
-        debug!("加载主题模板，目录: {}", layout_dir.display());
-        
-        // 添加 layout.html 模板
-        let layout_path = layout_dir.join("layout.html");
-        if layout_path.exists() {
-            let layout_content = fs::read_to_string(&layout_path)?;
-            tera.add_raw_template("layout.html", &layout_content)?;
-            debug!("加载模板: layout.html");
-        } else {
-            return Err(anyhow!("基础布局模板文件不存在: {}", layout_path.display()));
+        // `layout: false` 的文章直接输出渲染后的内容
+        if !post.uses_layout() {
+            let content = post.rendered_content.clone().unwrap_or_else(|| post.content.clone());
+            return self.write_page(&post_path, content);
         }
         
-        // 添加需要的模板文件
-        let template_path = layout_dir.join(template_name);
-        if template_path.exists() {
-            let template_content = fs::read_to_string(&template_path)?;
-            tera.add_raw_template(template_name, &template_content)?;
-            debug!("加载模板: {}", template_name);
+        // 使用文章指定的布局模板，主题中没有时回退到 post.html
+        let layout_template = format!("{}.html", post.layout);
+        let template_name = if self.renderer.has_layout(&layout_template) {
+            layout_template.as_str()
         } else {
-            return Err(anyhow!("模板文件不存在: {}", template_path.display()));
-        }
-        
-        // 注册基本的日期格式化和默认值函数
-        self.register_basic_functions(&mut tera);
+            debug!("没有找到布局模板 {}，使用 post.html", layout_template);
+            "post.html"
+        };
+        let mut context = self.base_context();
         
-        // 从插件中动态注册模板函数
-        self.plugin_manager.register_template_functions(&mut tera)?;
+        // 添加页面数据
+        context.insert("page", post);
         
         // 渲染模板
-        let rendered = tera.render(template_name, &context)?;
+        let rendered = self.renderer.render_page(template_name, &context, &post.path)?;
         
         // 写入文件
-        fs::write(post_path, rendered)?;
-        
-        Ok(())
+        self.write_page(&post_path, rendered)
     }
     
-    /// 注册基本的模板函数和过滤器
-    fn register_basic_functions(&self, tera: &mut Tera) {
-        // 注册日期格式化函数
-        tera.register_function("date", |args: &HashMap<String, tera::Value>| {
-            let value = match args.get("value") {
-                Some(v) => v,
-                None => return Err(tera::Error::msg("缺少必要的参数: value"))
-            };
-            
-            let format = match args.get("format") {
-                Some(f) => match f.as_str() {
-                    Some(s) => s,
-                    None => return Err(tera::Error::msg("format 必须是字符串"))
-                },
-                None => "%Y-%m-%d"
-            };
-            
-            // 处理不同类型的日期值
-            if let Some(date_str) = value.as_str() {
-                // 尝试解析为RFC3339格式
-                if let Ok(date) = chrono::DateTime::parse_from_rfc3339(date_str) {
-                    return Ok(tera::Value::String(date.format(format).to_string()));
-                }
-                
-                // 尝试解析为其他常见格式
-                let formats = [
-                    "%Y-%m-%d %H:%M:%S",
-                    "%Y-%m-%d",
-                    "%Y/%m/%d %H:%M:%S",
-                    "%Y/%m/%d",
-                ];
-                
-                for fmt in &formats {
-                    if let Ok(date) = chrono::NaiveDateTime::parse_from_str(date_str, fmt) {
-                        let date = chrono::Utc.from_utc_datetime(&date);
-                        return Ok(tera::Value::String(date.format(format).to_string()));
-                    }
-                }
-                
-                // 返回原始字符串如果无法解析
-                return Ok(tera::Value::String(date_str.to_string()));
-            }
-            
-            // 处理数字类型（Unix时间戳）
-            if let Some(timestamp) = value.as_i64() {
-                if let Some(naive_dt) = chrono::NaiveDateTime::from_timestamp_opt(timestamp, 0) {
-                    let date = chrono::Utc.from_utc_datetime(&naive_dt);
-                    return Ok(tera::Value::String(date.format(format).to_string()));
-                }
-            }
-            
-            // 如果无法解析，返回错误
-            Err(tera::Error::msg("无法将value解析为日期时间"))
-        });
+    /// 所有页面共享的模板上下文：站点信息、插件标志和当前时间
+    fn base_context(&self) -> TemplateContext {
+        let mut context = TemplateContext::new();
+        context.insert("site", &self.site_data());
+        self.renderer.insert_plugin_data(&mut context);
         
-        // 注册默认函数
-        tera.register_function("default", |args: &HashMap<String, tera::Value>| {
-            let value = match args.get("value") {
-                Some(v) => v,
-                None => return Err(tera::Error::msg("缺少必要的参数: value"))
-            };
-            
-            let default_value = match args.get("default") {
-                Some(d) => d,
-                None => return Err(tera::Error::msg("缺少必要的参数: default"))
-            };
-            
-            if value.is_null() || (value.is_string() && value.as_str().unwrap_or("").is_empty()) {
-                Ok(default_value.clone())
-            } else {
-                Ok(value.clone())
-            }
-        });
+        // 添加当前时间
+        let now = chrono::Utc::now();
+        context.insert("now", &now.format("%Y-%m-%d %H:%M:%S").to_string());
+        
+        context
     }
     
-    /// 生成单个索引页面
-    fn generate_index_page(&self, posts: &[&Post], page_num: usize, total_pages: usize, output_file: &PathBuf) -> Result<()> {
-        // 创建Tera上下文
-        let mut context = Context::new();
-        
-        // 处理页面信息
-        let prev_link = if page_num == 2 { 
-            String::from("/") 
-        } else { 
-            format!("/page/{}/", page_num - 1) 
-        };
-        
-        let page = json!({
-            "posts": posts.iter().map(|post| {
-            // 创建文章摘要
-            let excerpt = post.excerpt.clone().unwrap_or_else(|| {
-                    // 如果没有摘要，使用内容的前200个字符
-                let content = &post.content;
-                    if content.len() > 200 {
-                        if let Some(idx) = content.char_indices().nth(200).map(|(i, _)| i) {
-                            // 安全处理截断内容，确保HTML标签闭合
-                            let truncated = &content[..idx];
-                            // 简单规则：如果截断内容中有<code但没有</code>，添加</code></pre>
-                            if truncated.contains("<code") && !truncated.contains("</code>") {
-                                format!("{}...</code></pre>", truncated)
-                            } else {
-                                format!("{}...", truncated)
-                            }
-                        } else { 
-                            content.clone() 
-                        }
-                } else {
-                    content.clone()
-                }
-            });
-            
-                // 构建文章信息
-                json!({
-                    "title": post.title,
-                    "path": post.path,
-                    "date": post.date,
-                    "categories": post.categories,
-                    "tags": post.tags,
-                    "excerpt": excerpt,
-                    "content": post.content,
-                    "permalink": post.permalink,
-                })
-            }).collect::<Vec<_>>(),
-            "current": page_num,
-            "total": total_pages,
-            "prev": if page_num > 1 { Some(page_num - 1) } else { None },
-            "next": if page_num < total_pages { Some(page_num + 1) } else { None },
-            "prev_link": if page_num > 1 { Some(prev_link) } else { None },
-            "next_link": if page_num < total_pages { Some(format!("/page/{}/", page_num + 1)) } else { None },
-            "base": "/",
-        });
-        context.insert("page", &page);
-        
-        // 添加网站配置信息
-        let site = json!({
+    /// 站点数据，为了避免模板中字段缺失的问题，手动构建完整的config对象
+    fn site_data(&self) -> Value {
+        json!({
             "config": {
                 "title": self.config.title.clone(),
                 "subtitle": self.config.subtitle.clone().unwrap_or_default(),
                 "description": self.config.description.clone().unwrap_or_default(),
-                "url": self.get_url(),
                 "author": self.config.author.clone().unwrap_or_default(),
                 "language": self.get_language(),
-                "keywords": "", // 添加空的keywords字段
+                "timezone": self.config.timezone.clone().unwrap_or_default(),
+                "url": self.get_url(),
                 "root": self.get_root(),
-                "permalink": self.config.permalink.clone().unwrap_or_default(),
-                "theme": self.config.theme.clone().unwrap_or_default()
+                "permalink": self.config.permalink.clone().unwrap_or_else(|| ":year/:month/:day/:title/".to_string()),
+                "theme": self.config.theme.clone().unwrap_or_default(),
+                // 各种目录配置
+                "source_dir": "source",
+                "public_dir": "public",
+                "tag_dir": "tags",
+                "category_dir": "categories",
+                "archive_dir": "archives",
+                // 关键字和其他可选配置
+                "keywords": "",
+                // 分页配置
+                "per_page": self.config.per_page.unwrap_or(10),
+                "pagination_dir": self.pagination_dir(),
             },
             "title": self.config.title.clone(),
             "url": self.get_url(),
-            "author": self.config.author.clone().unwrap_or_default()
-        });
-        context.insert("site", &site);
-        
-        // 添加插件信息
-        let plugin_manager = &self.plugin_manager;
-        let mut plugins = HashMap::new();
-        // 使用Arc和RwLock安全地访问插件
-        if let Ok(plugin_lock) = plugin_manager.plugins.read() {
-            for plugin_name in plugin_lock.keys() {
-                plugins.insert(plugin_name.clone(), true);
-            }
-        }
-        context.insert("plugins", &plugins);
-        
-        // 加载模板
-        let theme_path = self.get_theme_path()?;
-        let layout_dir = theme_path.join("layout");
-        let template_path = layout_dir.join("index.html");
-
-        if !template_path.exists() {
-            return Err(anyhow!("找不到首页模板文件: {:?}", template_path));
-        }
-
-        // 创建Tera实例
-        let mut tera = Tera::default();
-
-        // 首先显式加载layout.html布局模板
-        let layout_path = layout_dir.join("layout.html");
-        if layout_path.exists() {
-            tera.add_template_file(&layout_path, Some("layout.html"))?;
+            "author": self.config.author.clone().unwrap_or_default(),
+        })
+    }
+    
+    /// 列表页面中的文章摘要数据
+    fn post_summary(post: &Post) -> Value {
+        // 创建文章摘要
+        let excerpt = post.excerpt.clone().unwrap_or_else(|| {
+            // 如果没有摘要，使用内容的前200个字符
+            let content = &post.content;
+            if let Some(idx) = content.char_indices().nth(200).map(|(i, _)| i) {
+                // 安全处理截断内容，确保HTML标签闭合
+                let truncated = &content[..idx];
+                // 简单规则：如果截断内容中有<code但没有</code>，添加</code></pre>
+                if truncated.contains("<code") && !truncated.contains("</code>") {
+                    format!("{}...</code></pre>", truncated)
                 } else {
-            return Err(anyhow!("找不到布局模板文件: {:?}", layout_path));
-        }
-
-        // 然后加载index.html模板
-        let template_name = "index.html";
-        tera.add_template_file(&template_path, Some(template_name))?;
-
-        // 添加模板目录中的所有其他模板
-        if layout_dir.exists() {
-            // 使用 WalkDir 遍历目录
-            for entry in WalkDir::new(&layout_dir).into_iter().filter_map(|e| e.ok()) {
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |ext| ext == "html") {
-                    // 跳过已经手动加载的模板
-                    if path == layout_path || path == template_path {
-                        continue;
-                    }
-                    if let Ok(rel_path) = path.strip_prefix(&layout_dir) {
-                        let name = rel_path.to_string_lossy();
-                        tera.add_template_file(path, Some(name.as_ref()))?;
-                    }
+                    format!("{}...", truncated)
                 }
+            } else {
+                content.clone()
             }
-        }
-        
-        // 注册基本的日期格式化和默认值函数
-        self.register_basic_functions(&mut tera);
-        
-        // 从插件中动态注册模板函数
-        self.plugin_manager.register_template_functions(&mut tera)?;
-        
-        // 渲染模板
-        let rendered = tera.render(template_name, &context)?;
-        
-        // 确保目标目录存在
-        if let Some(parent) = output_file.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        
-        // 写入文件
-        fs::write(output_file, rendered)?;
+        });
         
-        Ok(())
-    }
-
-    // 获取主题目录路径的辅助方法
-    fn get_theme_path(&self) -> Result<PathBuf> {
-        let theme = self.config.theme.clone().unwrap_or_default();
-        Ok(PathBuf::from("themes").join(theme))
+        // 构建文章信息
+        json!({
+            "title": post.title,
+            "path": post.path,
+            "date": post.date,
+            "updated": post.updated,
+            "categories": post.categories,
+            "tags": post.tags,
+            "excerpt": excerpt,
+            "content": post.content,
+            "rendered_content": post.rendered_content,
+            "permalink": post.permalink,
+            "layout": post.layout,
+        })
     }
-
+    
     // 获取语言的辅助方法
     fn get_language(&self) -> String {
         self.config.language.clone().unwrap_or_else(|| "zh-CN".to_string())
@@ -1012,151 +1121,158 @@ impl HtmlGenerator {
         self.config.root.clone().unwrap_or_else(|| "/".to_string())
     }
 
-    /// 生成 RSS feed
-    fn generate_rss_feed(&self, posts: &[Post]) -> Result<()> {
-        let mut channel = Channel::default();
-        channel.set_title(self.config.title.clone());
-        channel.set_link(self.config.url.clone().unwrap_or_default());
-        channel.set_description(self.config.description.clone().unwrap_or_default());
-        channel.set_language(Some(self.config.language.clone().unwrap_or_default()));
-
-        let mut posts = posts.to_vec();
-        posts.sort_by(|a, b| b.date.cmp(&a.date));
-        posts.truncate(20); // 默认显示最新的20篇文章
-
-        for post in posts.iter().take(10) {
-            let mut item = Item::default();
-            item.set_title(post.title.clone());
-            let url_base = self.config.url.clone().unwrap_or_default();
-            item.set_link(format!("{}/{}", url_base.trim_end_matches('/'), post.path));
-            item.set_guid(Guid {
-                value: post.permalink.clone(),
-                permalink: true,
-            });
-            item.set_pub_date(post.date.to_rfc2822());
-            item.set_description(post.excerpt.clone().unwrap_or_default());
-            
-            channel.items.push(item);
+    /// 生成订阅源：全站的订阅源，以及按配置为每个分类、标签生成的订阅源
+    fn generate_feeds(&self, posts: &[Post]) -> Result<()> {
+        let config = self.config.feed.clone().unwrap_or_default();
+        if !config.enable {
+            return Ok(());
         }
-
-        let output_path = self.output_dir.join("rss.xml");
-        let mut content = String::with_capacity(4096);
-        write!(&mut content, "{}", channel.to_string())?;
-        fs::write(output_path, content)?;
-
+        
+        let mut feeds: Vec<(String, String, Vec<&Post>)> = vec![(
+            config.path.clone().unwrap_or_default(),
+            self.config.title.clone(),
+            posts.iter().collect(),
+        )];
+        if config.categories {
+            for (category, category_posts) in Self::group_posts(posts, |post| &post.categories) {
+                feeds.push((UrlHelper::category_path(&category), format!("{} - {}", self.config.title, category), category_posts));
+            }
+        }
+        if config.tags {
+            for (tag, tag_posts) in Self::group_posts(posts, |post| &post.tags) {
+                feeds.push((UrlHelper::tag_path(&tag), format!("{} - {}", self.config.title, tag), tag_posts));
+            }
+        }
+        
+        let mut media_dirs: Vec<PathBuf> = self.source_dir.iter()
+            .flat_map(|dir| [dir.clone(), dir.join("static")])
+            .collect();
+        media_dirs.push(self.output_dir.clone());
+        let generator = FeedGenerator::new(&config, &self.config, &self.urls).with_media_dirs(media_dirs);
+        
+        let mut files = Vec::new();
+        for (dir, title, feed_posts) in &feeds {
+            files.extend(generator.render(feed_posts, dir, title)?);
+        }
+        if let Some(podcast) = &config.podcast {
+            if podcast.category.is_empty() {
+                warn!("未设置播客分类 feed.podcast.category，跳过播客订阅源");
+            } else {
+                files.push(generator.render_podcast(podcast, &feeds[0].2)?);
+            }
+        }
+        
+        for (path, content) in files {
+            let output_path = self.output_dir.join(path);
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(output_path, content)?;
+        }
+        debug!("已生成 {} 个订阅源", feeds.len());
+        
         Ok(())
     }
     
-    /// 生成 Atom feed
-    fn generate_atom_feed(&self, posts: &[Post]) -> Result<()> {
-        let mut feed = Feed::default();
-        feed.set_title(self.config.title.clone());
-        feed.set_id(self.config.url.clone().unwrap_or_default());
-        feed.set_updated(Utc::now());
-
-        if let Some(subtitle) = &self.config.subtitle {
-            feed.set_subtitle(Text::plain(subtitle.clone()));
-        }
-
-        feed.set_lang(Some(self.config.language.clone().unwrap_or_default()));
-
-        let mut posts = posts.to_vec();
-        posts.sort_by(|a, b| b.date.cmp(&a.date));
-        posts.truncate(20); // 默认显示最新的20篇文章
-
+    /// 按分类或标签对文章分组
+    fn group_posts(posts: &[Post], names: impl Fn(&Post) -> &Vec<String>) -> BTreeMap<String, Vec<&Post>> {
+        let mut groups: BTreeMap<String, Vec<&Post>> = BTreeMap::new();
         for post in posts {
-            let mut entry = Entry::default();
-            
-            let url_base = self.config.url.clone().unwrap_or_default();
-            let post_url = format!("{}/{}", url_base.trim_end_matches('/'), post.path);
-            
-            entry.set_id(post_url.clone());
-            entry.set_title(Text::plain(post.title.clone()));
-            
-            let mut links = Vec::new();
-            let mut link = atom_syndication::Link::default();
-            link.set_href(post_url);
-            link.set_rel("alternate".to_string());
-            links.push(link);
-            entry.set_links(links);
-            
-            // 将 Utc 时间转换为固定偏移时间
-            let fixed_date = post.date.with_timezone(&chrono::FixedOffset::east_opt(0).unwrap());
-            entry.set_updated(fixed_date);
-            
-            // 发布时间同样需要转换
-            if let Some(published) = Some(post.date) {
-                let fixed_published = published.with_timezone(&chrono::FixedOffset::east_opt(0).unwrap());
-                entry.set_published(Some(fixed_published));
+            for name in names(post) {
+                groups.entry(name.clone()).or_default().push(post);
             }
-            
-            entry.set_summary(Text::plain(post.excerpt.clone().unwrap_or_default()));
-            
-            feed.entries.push(entry);
         }
-
-        let output_path = self.output_dir.join("atom.xml");
-        let mut content = String::with_capacity(4096);
-        write!(&mut content, "{}", feed.to_string())?;
-        fs::write(output_path, content)?;
-
-        Ok(())
+        groups
     }
     
     /// 生成搜索索引
     fn generate_search_index(&self, posts: &[Post]) -> Result<()> {
         // 检查是否启用搜索功能
-        if !self.config.search.as_ref().map_or(true, |s| s.enable) {
+        let config = self.config.search.clone().unwrap_or_default();
+        if !config.enable {
             return Ok(());
         }
         
         // 创建搜索索引生成器
-        let generator = SearchIndexGenerator::new(self.config.search.as_ref().map_or(false, |s| s.content));
+        let generator = SearchIndexGenerator::new(&config, &self.urls);
         
         // 生成搜索索引
         generator.generate(posts, &self.output_dir)?;
         
-        // 复制搜索页面和脚本
+        // 生成搜索页面
         self.generate_search_page()?;
         
         Ok(())
     }
     
-    /// 生成搜索页面
-    fn generate_search_page(&self) -> Result<()> {
+    /// 生成站点地图和 robots.txt
+    ///
+    /// 站点地图包含所有文章和本次生成写入的其他页面，front matter 中 `sitemap: false` 的文章不包含在内。
+    fn generate_sitemap(&self, posts: &[Post]) -> Result<()> {
+        let sitemap_config = self.config.sitemap.clone().unwrap_or_default();
+        let robots_config = self.config.robots.clone().unwrap_or_default();
+        if self.config.url.is_none() && (sitemap_config.enable || robots_config.enable) {
+            warn!("站点配置中没有 url，站点地图和 robots.txt 中的链接不是完整地址");
+        }
+        
+        if sitemap_config.enable {
+            let post_paths: HashSet<&str> = posts.iter().map(|post| post.path.as_str()).collect();
+            let mut entries: Vec<SitemapEntry> = self.written_pages.lock().unwrap().iter()
+                .filter(|path| !post_paths.contains(path.as_str()))
+                .map(|path| SitemapEntry { path: path.clone(), lastmod: None })
+                .collect();
+            entries.extend(posts.iter()
+                .filter(|post| post.front_matter.get("sitemap").and_then(serde_yaml::Value::as_bool) != Some(false))
+                .map(|post| SitemapEntry { path: post.path.clone(), lastmod: Some(post.updated.unwrap_or(post.date)) }));
+            
+            for (path, content) in SitemapGenerator::new(&sitemap_config, &self.urls).render(&entries)? {
+                fs::write(self.output_dir.join(path), content)?;
+            }
+            debug!("站点地图包含 {} 个页面", entries.len());
+        }
+        
+        if robots_config.enable {
+            let sitemap = sitemap_config.enable.then_some(sitemap_config.path.as_str());
+            fs::write(self.output_dir.join("robots.txt"), robots_txt(&robots_config, &self.urls, sitemap))?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 生成搜索页面，主题提供 `search.html` 时使用主题模板，站点未启用搜索时不生成
+    pub fn generate_search_page(&self) -> Result<()> {
+        let config = self.config.search.clone().unwrap_or_default();
+        if !config.enable {
+            return Ok(());
+        }
+        
+        let output_file = self.output_dir.join(search::PAGE_PATH);
+        let script_tag = search::script_tag(&config, &self.urls);
+        
+        if self.renderer.has_layout("search.html") {
+            let mut page = self.list_page("search", "搜索", &[], serde_json::Map::new());
+            page.insert("search".to_string(), json!({
+                "index": self.urls.url_for(&config.path, None),
+                "script": self.urls.url_for(search::SCRIPT_PATH, None),
+                "script_tag": script_tag,
+            }));
+            return self.render_list_page("search.html", page, &output_file);
+        }
+        
         let mut content = String::with_capacity(4096);
         self.write_html_header(&mut content, "Search")?;
-
+        
         writeln!(&mut content, "    <div class=\"search-container\">")?;
         writeln!(&mut content, "        <div class=\"search-box\">")?;
-        writeln!(&mut content, "            <input type=\"text\" id=\"search-input\" placeholder=\"Search...\">")?;
+        writeln!(&mut content, "            <input type=\"search\" id=\"search-input\" data-search-input placeholder=\"Search...\" autofocus>")?;
         writeln!(&mut content, "        </div>")?;
-        writeln!(&mut content, "        <div id=\"search-results\"></div>")?;
+        writeln!(&mut content, "        <div id=\"search-results\" data-search-results></div>")?;
         writeln!(&mut content, "    </div>")?;
-
-        writeln!(&mut content, "    <script>")?;
-        writeln!(&mut content, "        const searchIndex = {{}};")?;
-        writeln!(&mut content, "        document.getElementById('search-input').addEventListener('input', function(e) {{")?;
-        writeln!(&mut content, "            const query = e.target.value.toLowerCase();")?;
-        writeln!(&mut content, "            const results = Object.entries(searchIndex)")?;
-        writeln!(&mut content, "                .filter(([_, post]) => post.title.toLowerCase().includes(query) || post.content.toLowerCase().includes(query))")?;
-        writeln!(&mut content, "                .map(([_, post]) => `<div class=\"search-result\">")?;
-        writeln!(&mut content, "                    <h3><a href=\"${{{{post.url}}}}\">${{{{post.title}}}}</a></h3>")?;
-        writeln!(&mut content, "                    <p>${{{{post.excerpt}}}}</p>")?;
-        writeln!(&mut content, "                    <div class=\"meta\">")?;
-        writeln!(&mut content, "                        <span class=\"date\">${{{{post.date}}}}</span>")?;
-        writeln!(&mut content, "                        ${{{{post.categories ? `<span class=\"categories\">Categories: ${{{{post.categories.join(', ')}}}}` : ''}}}}")?;
-        writeln!(&mut content, "                        ${{{{post.tags ? `<span class=\"tags\">Tags: ${{{{post.tags.join(', ')}}}}` : ''}}}}")?;
-        writeln!(&mut content, "                    </div>")?;
-        writeln!(&mut content, "                </div>`)")?;
-        writeln!(&mut content, "                .join('');")?;
-        writeln!(&mut content, "            document.getElementById('search-results').innerHTML = results;")?;
-        writeln!(&mut content, "        }});")?;
-        writeln!(&mut content, "    </script>")?;
-
+        writeln!(&mut content, "    {}", script_tag)?;
+        
         self.write_html_footer(&mut content)?;
-
-        Ok(())
+        
+        self.write_page(&output_file, content)
     }
 }
 
@@ -1187,6 +1303,7 @@ fn count_words(content: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::config::{AssetConfig, FeedConfig};
     
     #[test]
     fn test_content_mutability() -> Result<()> {
@@ -1204,4 +1321,206 @@ mod tests {
         
         Ok(())
     }
-} 
\ No newline at end of file
+    
+    #[test]
+    fn test_pagination_links() {
+        let config = Config {
+            pagination_dir: Some("/p/".to_string()),
+            ..Config::default()
+        };
+        let theme_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(theme_dir.path().join("layout")).unwrap();
+        let renderer = ThemeRenderer::from_theme_dir(theme_dir.path().to_path_buf(), config.clone()).unwrap();
+        let plugin_manager = PluginManager::new(PathBuf::from("."), crate::plugins::PluginContext::default());
+        let generator = HtmlGenerator::new(PathBuf::from("public"), config, plugin_manager, Arc::new(renderer));
+        
+        let first = generator.pagination("/tags/rust/", 1, 3);
+        assert_eq!(first["prev_link"], Value::Null);
+        assert_eq!(first["next_link"], json!("/tags/rust/p/2/"));
+        
+        let second = generator.pagination("/tags/rust/", 2, 3);
+        assert_eq!(second["prev_link"], json!("/tags/rust/"));
+        assert_eq!(second["current_url"], json!("/tags/rust/p/2/"));
+    }
+    
+    #[test]
+    fn test_paginated_keeps_named_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::default();
+        fs::create_dir_all(dir.path().join("theme/layout")).unwrap();
+        let renderer = ThemeRenderer::from_theme_dir(dir.path().join("theme"), config.clone()).unwrap();
+        let plugin_manager = PluginManager::new(PathBuf::from("."), crate::plugins::PluginContext::default());
+        let output_dir = dir.path().join("public");
+        let generator = HtmlGenerator::new(output_dir.clone(), config, plugin_manager, Arc::new(renderer));
+        
+        // 名为 page 的分类与分页目录同名，其页面不应随多余的分页一起删除
+        let categories_dir = output_dir.join("categories");
+        for page in ["page/2", "page/3", "page/rust", "page"] {
+            fs::create_dir_all(categories_dir.join(page)).unwrap();
+            fs::write(categories_dir.join(page).join("index.html"), "old").unwrap();
+        }
+        
+        let mut rendered = Vec::new();
+        generator.generate_paginated(&[], 10, "/categories/", &categories_dir, |_, _, output_file| {
+            rendered.push(output_file.to_path_buf());
+            Ok(())
+        }).unwrap();
+        
+        assert_eq!(rendered, vec![categories_dir.join("index.html")]);
+        assert!(!categories_dir.join("page/2").exists());
+        assert!(!categories_dir.join("page/3").exists());
+        assert!(categories_dir.join("page/index.html").exists());
+        assert!(categories_dir.join("page/rust/index.html").exists());
+    }
+    
+    /// 使用空主题、输出到 `dir/public` 的生成器
+    fn site_generator(dir: &Path, config: Config) -> HtmlGenerator {
+        fs::create_dir_all(dir.join("theme/layout")).unwrap();
+        let renderer = ThemeRenderer::from_theme_dir(dir.join("theme"), config.clone()).unwrap();
+        let plugin_manager = PluginManager::new(PathBuf::from("."), crate::plugins::PluginContext::default());
+        HtmlGenerator::new(dir.join("public"), config, plugin_manager, Arc::new(renderer))
+    }
+    
+    #[test]
+    fn test_sitemap_list_page_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            url: Some("https://example.com/blog".to_string()),
+            root: Some("/blog/".to_string()),
+            ..Config::default()
+        };
+        let generator = site_generator(dir.path(), config);
+        let posts = vec![Post {
+            title: "a".to_string(),
+            path: "posts/a/index.html".to_string(),
+            categories: vec!["Dev Notes".to_string()],
+            tags: vec!["中文".to_string()],
+            ..Post::default()
+        }];
+        
+        generator.generate_categories(&posts).unwrap();
+        generator.generate_tags(&posts).unwrap();
+        generator.generate_sitemap(&posts).unwrap();
+        
+        // 分类和标签页面使用别名作为目录名，站点地图中的链接不含空格和非 ASCII 字符
+        let sitemap = fs::read_to_string(dir.path().join("public/sitemap.xml")).unwrap();
+        assert!(sitemap.contains("<loc>https://example.com/blog/categories/dev-notes/</loc>"));
+        assert!(sitemap.contains("<loc>https://example.com/blog/tags/zhong-wen/</loc>"));
+        assert!(!sitemap.contains("Dev Notes"));
+        assert!(dir.path().join("public/categories/dev-notes/index.html").is_file());
+    }
+    
+    #[test]
+    fn test_list_feeds_use_slugs() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            url: Some("https://example.com".to_string()),
+            feed: Some(FeedConfig { categories: true, tags: true, ..FeedConfig::default() }),
+            ..Config::default()
+        };
+        let generator = site_generator(dir.path(), config);
+        let posts = vec![Post {
+            title: "a".to_string(),
+            path: "posts/a/index.html".to_string(),
+            categories: vec!["Dev Notes".to_string()],
+            tags: vec!["中文".to_string()],
+            ..Post::default()
+        }];
+        
+        generator.generate_feeds(&posts).unwrap();
+        
+        // 订阅源与列表页面位于同一目录，链接使用别名
+        assert_eq!(generator.category_output_dir("Dev Notes"), dir.path().join("public/categories/dev-notes"));
+        let atom = fs::read_to_string(dir.path().join("public/categories/dev-notes/atom.xml")).unwrap();
+        assert!(atom.contains("<id>https://example.com/categories/dev-notes/atom.xml</id>"));
+        assert!(atom.contains(" - Dev Notes</title>"));
+        assert!(dir.path().join("public/tags/zhong-wen/rss.xml").is_file());
+    }
+    
+    #[test]
+    fn test_fingerprint_removes_stale_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let css = dir.path().join("theme/source/css/style.css");
+        fs::create_dir_all(css.parent().unwrap()).unwrap();
+        let fingerprinted = |fingerprint: bool| Config {
+            assets: Some(AssetConfig { fingerprint, ..AssetConfig::default() }),
+            ..Config::default()
+        };
+        let hashed_files = || -> Vec<String> {
+            fs::read_dir(dir.path().join("public/css")).unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .filter(|name| name != "style.css")
+                .collect()
+        };
+        
+        let generator = site_generator(dir.path(), fingerprinted(true));
+        fs::write(&css, "body { color: red; }").unwrap();
+        generator.process_theme_assets().unwrap();
+        let first = hashed_files();
+        assert_eq!(first.len(), 1);
+        
+        // 内容变化后只保留新的带哈希文件
+        fs::write(&css, "body { color: blue; }").unwrap();
+        generator.process_theme_assets().unwrap();
+        let second = hashed_files();
+        assert_eq!(second.len(), 1);
+        assert_ne!(first, second);
+        
+        // 关闭指纹后删除带哈希的文件和资源清单
+        let generator = site_generator(dir.path(), fingerprinted(false));
+        generator.process_theme_assets().unwrap();
+        assert!(hashed_files().is_empty());
+        assert!(dir.path().join("public/css/style.css").is_file());
+        assert!(!dir.path().join("public/assets-manifest.json").exists());
+    }
+    
+    #[test]
+    fn test_process_changed_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("theme/source");
+        let public = dir.path().join("public");
+        for (path, content) in [
+            ("css/_vars.scss", "$color: red;"),
+            ("css/style.scss", "@import 'vars';\nbody { color: $color; }"),
+            ("js/app.js", "var a = 1;"),
+            ("images/logo.svg", "<svg/>"),
+        ] {
+            fs::create_dir_all(source.join(path).parent().unwrap()).unwrap();
+            fs::write(source.join(path), content).unwrap();
+        }
+        let config = Config {
+            assets: Some(AssetConfig { fingerprint: true, ..AssetConfig::default() }),
+            ..Config::default()
+        };
+        let generator = site_generator(dir.path(), config);
+        generator.process_theme_assets().unwrap();
+        let output = |path: &str| generator.urls.asset_manifest().get(path).cloned();
+        let old_js = output("js/app.js").unwrap();
+        let old_css = output("css/style.css").unwrap();
+        
+        // 只重新处理变化的文件
+        fs::write(public.join("images/logo.svg"), "untouched").unwrap();
+        fs::write(source.join("js/app.js"), "var b = 2;").unwrap();
+        assert!(generator.process_changed_assets(&["js/app.js".to_string()]).unwrap());
+        assert_eq!(fs::read_to_string(public.join("images/logo.svg")).unwrap(), "untouched");
+        assert!(!public.join(&old_js).exists());
+        assert!(public.join(output("js/app.js").unwrap()).is_file());
+        assert_eq!(output("css/style.css").unwrap(), old_css);
+        
+        // Sass 局部文件变化时重新编译引用它的样式表
+        fs::write(source.join("css/_vars.scss"), "$color: blue;").unwrap();
+        generator.process_changed_assets(&["css/_vars.scss".to_string()]).unwrap();
+        let css = output("css/style.css").unwrap();
+        assert!(fs::read_to_string(public.join(&css)).unwrap().contains("blue"));
+        assert!(!public.join(&old_css).exists());
+        
+        // 删除源文件时删除对应的输出
+        fs::remove_file(source.join("css/style.scss")).unwrap();
+        fs::remove_file(source.join("images/logo.svg")).unwrap();
+        generator.process_changed_assets(&["css/style.scss".to_string(), "images/logo.svg".to_string()]).unwrap();
+        assert!(!public.join(&css).exists());
+        assert!(!public.join("images/logo.svg").exists());
+        assert_eq!(output("css/style.css"), None);
+        assert!(public.join(output("js/app.js").unwrap()).is_file());
+    }
+}
\ No newline at end of file
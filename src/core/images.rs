@@ -0,0 +1,406 @@
+use anyhow::{anyhow, Context, Result};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use lol_html::{element, RewriteStrSettings};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use crate::core::postprocess::HtmlRewriter;
+use crate::models::config::ImageConfig;
+use crate::models::image::{normalize_format, ImageVariant, ProcessedImage};
+use crate::models::types::Post;
+use crate::utils::url::UrlHelper;
+
+/// 可以生成的图片格式
+const SUPPORTED_FORMATS: &[&str] = &["jpg", "png", "webp"];
+
+/// 缓存中记录处理结果的文件
+const CACHE_META: &str = "meta.json";
+
+/// 文章引用的本地图片
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSource {
+    /// 相对于站点根目录的路径，也是原图的输出路径
+    pub path: String,
+    /// 源文件
+    pub file: PathBuf,
+}
+
+/// 缓存中的处理结果，与图片内容的哈希对应
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// 生成时的配置，配置变化后重新生成
+    key: String,
+    width: u32,
+    height: u32,
+    /// 生成的宽度和格式，缓存文件名为 `<宽度>.<格式>`
+    variants: Vec<(u32, String)>,
+}
+
+/// 文章图片处理：复制原图，生成不同宽度和格式的图片，并在页面中使用 `<picture>` 和 `srcset` 引用
+///
+/// 生成结果按原图内容的哈希缓存，内容和配置不变的图片不会重新编码。
+pub struct ImagePipeline {
+    config: ImageConfig,
+    /// 站点的 `source` 目录
+    source_dir: PathBuf,
+    /// 缓存目录
+    cache_dir: PathBuf,
+}
+
+impl ImagePipeline {
+    pub fn new(mut config: ImageConfig, source_dir: PathBuf, cache_dir: PathBuf) -> Self {
+        config.formats = config.formats.iter()
+            .map(|format| normalize_format(format))
+            .filter(|format| {
+                let supported = SUPPORTED_FORMATS.contains(&format.as_str());
+                if !supported {
+                    warn!("不支持生成 {} 格式的图片，已忽略", format);
+                }
+                supported
+            })
+            .collect();
+        config.widths.sort_unstable();
+        config.widths.dedup();
+        
+        Self { config, source_dir, cache_dir }
+    }
+    
+    /// 找出文章中引用的本地图片
+    ///
+    /// 图片在 `source` 目录中按链接对应的路径查找；相对链接还会在文章的资源文件夹（与文章同名的目录）中查找。
+    pub fn collect(&self, posts: &[Post], urls: &UrlHelper) -> Vec<ImageSource> {
+        let mut images = BTreeMap::new();
+        
+        for post in posts {
+            let Some(content) = post.rendered_content.as_deref() else {
+                continue;
+            };
+            for src in image_links(content) {
+                let Some(path) = urls.site_path(&src, &post.path) else {
+                    continue;
+                };
+                if images.contains_key(&path) {
+                    continue;
+                }
+                
+                let mut candidates = vec![self.source_dir.join(&path)];
+                if !src.starts_with('/') {
+                    candidates.push(post.source.with_extension("").join(src.split(['?', '#']).next().unwrap_or_default()));
+                }
+                match candidates.into_iter().find(|file| file.is_file()) {
+                    Some(file) => { images.insert(path.clone(), ImageSource { path, file }); },
+                    None => debug!("文章 {} 引用的图片不存在: {}", post.title, src),
+                }
+            }
+        }
+        
+        images.into_values().collect()
+    }
+    
+    /// 并行处理图片并写入输出目录，返回生成了尺寸信息的图片
+    pub fn process(&self, images: &[ImageSource], output_dir: &Path) -> Result<Vec<ProcessedImage>> {
+        let processed = images.par_iter()
+            .map(|image| self.process_image(image, output_dir).with_context(|| format!("处理图片失败: {}", image.file.display())))
+            .collect::<Result<Vec<_>>>()?;
+        
+        info!("已处理 {} 个文章图片", images.len());
+        Ok(processed.into_iter().flatten().collect())
+    }
+    
+    fn process_image(&self, source: &ImageSource, output_dir: &Path) -> Result<Option<ProcessedImage>> {
+        let content = fs::read(&source.file)?;
+        write_if_changed(&output_dir.join(&source.path), &content)?;
+        if !self.config.enable {
+            return Ok(None);
+        }
+        
+        let format = match image::guess_format(&content) {
+            Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)) => format,
+            Ok(ImageFormat::Gif) => {
+                // 动图只记录尺寸
+                let (width, height) = image::ImageReader::with_format(Cursor::new(&content), ImageFormat::Gif).into_dimensions()?;
+                return Ok(Some(ProcessedImage { path: source.path.clone(), width, height, variants: Vec::new() }));
+            }
+            _ => return Ok(None),
+        };
+        
+        let hash: String = Sha256::digest(&content).iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+        let cache_dir = self.cache_dir.join(hash);
+        let entry = match self.read_cache(&cache_dir) {
+            Some(entry) => entry,
+            None => {
+                debug!("生成图片: {}", source.path);
+                self.generate(&content, format, &cache_dir)?
+            }
+        };
+        
+        let mut variants = Vec::with_capacity(entry.variants.len());
+        for (width, variant_format) in &entry.variants {
+            let path = variant_path(&source.path, *width, variant_format);
+            let bytes = fs::read(cache_dir.join(format!("{}.{}", width, variant_format)))?;
+            write_if_changed(&output_dir.join(&path), &bytes)?;
+            variants.push(ImageVariant { path, width: *width, format: variant_format.clone() });
+        }
+        
+        Ok(Some(ProcessedImage { path: source.path.clone(), width: entry.width, height: entry.height, variants }))
+    }
+    
+    /// 读取缓存，配置变化或文件不完整时返回 `None`
+    fn read_cache(&self, cache_dir: &Path) -> Option<CacheEntry> {
+        let meta = fs::read_to_string(cache_dir.join(CACHE_META)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&meta).ok()?;
+        let complete = entry.variants.iter().all(|(width, format)| cache_dir.join(format!("{}.{}", width, format)).is_file());
+        (entry.key == self.cache_key() && complete).then_some(entry)
+    }
+    
+    /// 生成各尺寸和格式的图片并写入缓存
+    fn generate(&self, content: &[u8], format: ImageFormat, cache_dir: &Path) -> Result<CacheEntry> {
+        let image = image::load_from_memory_with_format(content, format)?;
+        let (width, height) = image.dimensions();
+        let original_format = normalize_format(format.extensions_str().first().copied().unwrap_or_default());
+        fs::create_dir_all(cache_dir)?;
+        
+        let mut variants = Vec::new();
+        let widths = self.config.widths.iter().copied().filter(|w| *w < width).chain(std::iter::once(width));
+        for target_width in widths {
+            let resized = if target_width == width {
+                image.clone()
+            } else {
+                image.resize(target_width, u32::MAX, FilterType::Lanczos3)
+            };
+            
+            for variant_format in std::iter::once(&original_format).chain(self.config.formats.iter()) {
+                // 原图本身就是原格式的最大尺寸
+                let is_original = target_width == width && *variant_format == original_format;
+                if is_original || variants.contains(&(target_width, variant_format.clone())) {
+                    continue;
+                }
+                let bytes = self.encode(&resized, variant_format)?;
+                fs::write(cache_dir.join(format!("{}.{}", target_width, variant_format)), bytes)?;
+                variants.push((target_width, variant_format.clone()));
+            }
+        }
+        
+        let entry = CacheEntry { key: self.cache_key(), width, height, variants };
+        fs::write(cache_dir.join(CACHE_META), serde_json::to_string(&entry)?)?;
+        Ok(entry)
+    }
+    
+    fn encode(&self, image: &DynamicImage, format: &str) -> Result<Vec<u8>> {
+        let mut output = Cursor::new(Vec::new());
+        match format {
+            "jpg" => {
+                let encoder = JpegEncoder::new_with_quality(&mut output, self.config.quality.clamp(1, 100));
+                DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)?;
+            }
+            "png" => image.write_to(&mut output, ImageFormat::Png)?,
+            "webp" => {
+                let image = if image.color().has_alpha() {
+                    DynamicImage::ImageRgba8(image.to_rgba8())
+                } else {
+                    DynamicImage::ImageRgb8(image.to_rgb8())
+                };
+                image.write_to(&mut output, ImageFormat::WebP)?;
+            }
+            format => return Err(anyhow!("不支持的图片格式: {}", format)),
+        }
+        Ok(output.into_inner())
+    }
+    
+    fn cache_key(&self) -> String {
+        format!("widths={:?};formats={:?};quality={}", self.config.widths, self.config.formats, self.config.quality)
+    }
+}
+
+/// 将页面中引用已处理图片的 `<img>` 改写为 `<picture>`，并补充 `srcset`、`sizes`、`width` 和 `height`
+///
+/// 已有 `srcset` 的图片只补充尺寸。
+pub fn picture_rewriter(urls: UrlHelper, sizes: String) -> HtmlRewriter {
+    HtmlRewriter::new("img[src]", move |element, page| {
+        let Some(src) = element.get_attribute("src") else {
+            return Ok(());
+        };
+        let Some(image) = urls.site_path(&src, page).and_then(|path| urls.images().get(&path)) else {
+            return Ok(());
+        };
+        
+        if !element.has_attribute("width") && !element.has_attribute("height") {
+            element.set_attribute("width", &image.width.to_string())?;
+            element.set_attribute("height", &image.height.to_string())?;
+        }
+        if image.variants.is_empty() || element.has_attribute("srcset") {
+            return Ok(());
+        }
+        
+        let url_for = |path: &str| urls.url_for(path, None);
+        element.set_attribute("srcset", &image.srcset(&image.format(), url_for))?;
+        if !element.has_attribute("sizes") {
+            element.set_attribute("sizes", &sizes)?;
+        }
+        
+        let sources: String = image.extra_formats().iter()
+            .map(|format| format!(
+                "<source type=\"{}\" srcset=\"{}\" sizes=\"{}\">",
+                mime_type(format),
+                escape_attribute(&image.srcset(format, url_for)),
+                escape_attribute(&sizes),
+            ))
+            .collect();
+        if !sources.is_empty() {
+            element.before(&format!("<picture>{}", sources), lol_html::html_content::ContentType::Html);
+            element.after("</picture>", lol_html::html_content::ContentType::Html);
+        }
+        Ok(())
+    })
+}
+
+/// 页面中所有 `<img>` 的 `src`
+fn image_links(html: &str) -> Vec<String> {
+    let links = RefCell::new(Vec::new());
+    let settings = RewriteStrSettings {
+        element_content_handlers: vec![element!("img[src]", |element| {
+            if let Some(src) = element.get_attribute("src") {
+                links.borrow_mut().push(src);
+            }
+            Ok(())
+        })],
+        ..RewriteStrSettings::new()
+    };
+    if let Err(e) = lol_html::rewrite_str(html, settings) {
+        warn!("解析文章中的图片失败: {}", e);
+    }
+    links.into_inner()
+}
+
+/// 生成的图片路径，如 `images/photo.jpg` 宽 480 的 WebP 为 `images/photo-480w.webp`
+fn variant_path(path: &str, width: u32, format: &str) -> String {
+    let ext = extension(path);
+    let stem = if ext.is_empty() { path } else { &path[..path.len() - ext.len() - 1] };
+    format!("{}-{}w.{}", stem, width, format)
+}
+
+/// 如果文件内容不同则写入，未变化的图片不会更新修改时间
+fn write_if_changed(path: &Path, content: &[u8]) -> Result<()> {
+    if fs::read(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn extension(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path).rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default()
+}
+
+fn mime_type(format: &str) -> String {
+    match format {
+        "jpg" => "image/jpeg".to_string(),
+        format => format!("image/{}", format),
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::postprocess::HtmlPostProcessor;
+    use crate::models::config::{Config, HtmlConfig};
+    use crate::utils::url::with_current_path;
+    
+    /// 包含站点图片和文章资源图片的站点，返回临时目录、图片处理流水线和引用这些图片的文章
+    fn site() -> (tempfile::TempDir, ImagePipeline, Post) {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("source");
+        fs::create_dir_all(source_dir.join("images")).unwrap();
+        fs::create_dir_all(source_dir.join("_posts/hello")).unwrap();
+        
+        let photo = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(1000, 500, image::Rgb([200, 100, 50])));
+        photo.save(source_dir.join("images/photo.jpg")).unwrap();
+        photo.resize(300, 300, FilterType::Nearest).save(source_dir.join("_posts/hello/small.png")).unwrap();
+        
+        let post = Post {
+            title: "hello".to_string(),
+            rendered_content: Some("<p><img src=\"/images/photo.jpg\"><img src=\"small.png\"><img src=\"missing.png\"><img src=\"https://example.com/a.jpg\"></p>".to_string()),
+            source: source_dir.join("_posts/hello.md"),
+            path: "posts/hello/index.html".to_string(),
+            ..Post::default()
+        };
+        let config = ImageConfig { enable: true, widths: vec![960, 480, 2000], ..ImageConfig::default() };
+        let pipeline = ImagePipeline::new(config, source_dir, dir.path().join("cache"));
+        (dir, pipeline, post)
+    }
+    
+    #[test]
+    fn test_image_config_default() {
+        // 图片处理默认关闭
+        assert!(!ImageConfig::default().enable);
+    }
+    
+    #[test]
+    fn test_collect_images() {
+        let (_dir, pipeline, post) = site();
+        let urls = UrlHelper::from_config(&Config::default());
+        
+        let sources = pipeline.collect(&[post], &urls);
+        assert_eq!(sources.iter().map(|s| s.path.as_str()).collect::<Vec<_>>(), vec!["images/photo.jpg", "posts/hello/small.png"]);
+    }
+    
+    #[test]
+    fn test_process_variants() {
+        let (dir, pipeline, post) = site();
+        let output_dir = dir.path().join("public");
+        let sources = pipeline.collect(&[post], &UrlHelper::from_config(&Config::default()));
+        
+        let processed = pipeline.process(&sources, &output_dir).unwrap();
+        let photo = &processed[0];
+        assert_eq!((photo.width, photo.height), (1000, 500));
+        let paths: Vec<_> = photo.variants.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["images/photo-480w.jpg", "images/photo-480w.webp", "images/photo-960w.jpg", "images/photo-960w.webp", "images/photo-1000w.webp"]);
+        assert!(output_dir.join("images/photo-960w.webp").is_file());
+        assert!(output_dir.join("posts/hello/small.png").is_file());
+        assert_eq!(photo.closest(500), "images/photo-960w.jpg");
+        assert_eq!(photo.closest(2000), "images/photo.jpg");
+    }
+    
+    #[test]
+    fn test_process_cache() {
+        let (dir, pipeline, post) = site();
+        let output_dir = dir.path().join("public");
+        let sources = pipeline.collect(&[post], &UrlHelper::from_config(&Config::default()));
+        let processed = pipeline.process(&sources, &output_dir).unwrap();
+        
+        // 第二次处理使用缓存
+        let meta = dir.path().join("cache").read_dir().unwrap().next().unwrap().unwrap().path().join(CACHE_META);
+        let modified = fs::metadata(&meta).unwrap().modified().unwrap();
+        assert_eq!(pipeline.process(&sources, &output_dir).unwrap(), processed);
+        assert_eq!(fs::metadata(&meta).unwrap().modified().unwrap(), modified);
+    }
+    
+    #[test]
+    fn test_picture_rewriter() {
+        let (dir, pipeline, post) = site();
+        let urls = UrlHelper::from_config(&Config::default());
+        let sources = pipeline.collect(&[post], &urls);
+        urls.images().extend(pipeline.process(&sources, &dir.path().join("public")).unwrap());
+        
+        let processor = HtmlPostProcessor::new(HtmlConfig::default(), vec![picture_rewriter(urls.clone(), "100vw".to_string())]);
+        let html = with_current_path("posts/hello/index.html", || processor.process("<img src=\"small.png\"><img src=\"/images/photo.jpg\" alt=\"p\">", "posts/hello/index.html")).unwrap();
+        assert!(html.starts_with("<picture><source type=\"image/webp\" srcset=\"/posts/hello/small-300w.webp 300w\" sizes=\"100vw\"><img src=\"small.png\" width=\"300\" height=\"150\" srcset=\"/posts/hello/small.png 300w\""));
+        assert!(html.contains("<picture><source type=\"image/webp\" srcset=\"/images/photo-480w.webp 480w, /images/photo-960w.webp 960w, /images/photo-1000w.webp 1000w\" sizes=\"100vw\"><img src=\"/images/photo.jpg\" alt=\"p\" width=\"1000\" height=\"500\" srcset=\"/images/photo-480w.jpg 480w, /images/photo-960w.jpg 960w, /images/photo.jpg 1000w\" sizes=\"100vw\"></picture>"));
+    }
+}
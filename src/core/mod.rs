@@ -1,7 +1,13 @@
+pub mod assets;
 pub mod engine;
+pub mod feed;
 pub mod generator;
+pub mod images;
+pub mod postprocess;
 pub mod server;
 pub mod search;
+pub mod sitemap;
+pub mod rebuild;
 
 pub use engine::Engine;
 pub use generator::HtmlGenerator;
@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Result};
+use lol_html::{ElementContentHandlers, HandlerResult, RewriteStrSettings, Selector};
+use std::borrow::Cow;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::models::config::HtmlConfig;
+
+pub use lol_html::html_content::Element as HtmlElement;
+
+/// 元素改写函数，可以修改元素的属性、在元素前后插入内容或移除元素
+///
+/// 第二个参数是元素所在页面相对输出目录的路径，如 `posts/hello/index.html`。
+pub type ElementHandler = Arc<dyn Fn(&mut HtmlElement<'_, '_>, &str) -> Result<()> + Send + Sync>;
+
+/// 整页改写函数，参数是页面内容和页面路径，返回改写后的页面
+///
+/// 插件的改写规则通过它在插件自己的库中执行，见 [`Plugin::rewrite_html`](crate::plugins::Plugin::rewrite_html)。
+pub type PageRewriter = Arc<dyn Fn(&str, &str) -> Result<String> + Send + Sync>;
+
+/// 按 CSS 选择器匹配元素的改写规则，由插件通过 `Plugin::html_rewriters` 提供
+///
+/// ```ignore
+/// HtmlRewriter::new("img", |element, _page| {
+///     element.set_attribute("loading", "lazy")?;
+///     Ok(())
+/// })
+/// ```
+#[derive(Clone)]
+pub struct HtmlRewriter {
+    /// CSS 选择器，如 `img`、`a[href^="http"]`
+    pub selector: String,
+    pub handler: ElementHandler,
+}
+
+impl HtmlRewriter {
+    pub fn new(selector: &str, handler: impl Fn(&mut HtmlElement<'_, '_>, &str) -> Result<()> + Send + Sync + 'static) -> Self {
+        Self {
+            selector: selector.to_string(),
+            handler: Arc::new(handler),
+        }
+    }
+}
+
+/// 页面写入前的处理：先执行元素改写规则，再依次执行整页改写，最后按配置压缩 HTML
+pub struct HtmlPostProcessor {
+    config: HtmlConfig,
+    rewriters: Vec<(Selector, ElementHandler)>,
+    pages: Vec<PageRewriter>,
+}
+
+impl HtmlPostProcessor {
+    /// 创建处理器，选择器无效的改写规则会被忽略
+    pub fn new(config: HtmlConfig, rewriters: Vec<HtmlRewriter>) -> Self {
+        let rewriters = rewriters.into_iter()
+            .filter_map(|rewriter| match rewriter.selector.parse::<Selector>() {
+                Ok(selector) => Some((selector, rewriter.handler)),
+                Err(e) => {
+                    warn!("忽略无效的 HTML 改写选择器 '{}': {}", rewriter.selector, e);
+                    None
+                }
+            })
+            .collect();
+        
+        Self { config, rewriters, pages: Vec::new() }
+    }
+    
+    /// 在元素改写规则之后按顺序执行的整页改写
+    pub fn with_page_rewriters(mut self, pages: Vec<PageRewriter>) -> Self {
+        self.pages = pages;
+        self
+    }
+    
+    /// 是否需要处理页面，既没有改写规则也不压缩时直接写入
+    pub fn is_enabled(&self) -> bool {
+        self.config.minify || !self.rewriters.is_empty() || !self.pages.is_empty()
+    }
+    
+    /// 处理一个 HTML 页面，`page` 是页面相对输出目录的路径
+    pub fn process(&self, html: &str, page: &str) -> Result<String> {
+        let mut html = if self.rewriters.is_empty() {
+            html.to_string()
+        } else {
+            self.rewrite(html, page)?
+        };
+        for rewriter in &self.pages {
+            html = rewriter(&html, page)?;
+        }
+        
+        if !self.config.minify {
+            return Ok(html);
+        }
+        
+        Ok(String::from_utf8_lossy(&minify_html::minify(html.as_bytes(), &self.minify_cfg())).into_owned())
+    }
+    
+    fn rewrite(&self, html: &str, page: &str) -> Result<String> {
+        let element_content_handlers = self.rewriters.iter()
+            .map(|(selector, handler)| {
+                let handler = handler.clone();
+                let element_handler = move |element: &mut HtmlElement<'_, '_>| -> HandlerResult {
+                    handler(element, page).map_err(Into::into)
+                };
+                (Cow::Borrowed(selector), ElementContentHandlers::default().element(element_handler))
+            })
+            .collect();
+        
+        let settings = RewriteStrSettings {
+            element_content_handlers,
+            ..RewriteStrSettings::new()
+        };
+        lol_html::rewrite_str(html, settings).map_err(|e| anyhow!("改写 HTML 失败: {}", e))
+    }
+    
+    /// `<pre>`、`<textarea>` 中的空白由 minify-html 保留
+    fn minify_cfg(&self) -> minify_html::Cfg {
+        minify_html::Cfg {
+            minify_css: self.config.minify_css,
+            minify_js: self.config.minify_js,
+            keep_comments: self.config.keep_comments,
+            keep_closing_tags: true,
+            keep_html_and_head_opening_tags: true,
+            do_not_minify_doctype: true,
+            ensure_spec_compliant_unquoted_attribute_values: true,
+            keep_spaces_between_attributes: true,
+            ..minify_html::Cfg::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_html_post_processor() {
+        let html = "<!DOCTYPE html>\n<html>\n  <head>\n    <style> body { color : red ; } </style>\n  </head>\n  <body>\n    <!-- note -->\n    <p>  Hello  </p>\n    <pre>  keep\n    this  </pre>\n    <img src=\"a.png\">\n  </body>\n</html>\n";
+        
+        let lazy = HtmlRewriter::new("img", |element, _page| {
+            element.set_attribute("loading", "lazy")?;
+            Ok(())
+        });
+        let invalid = HtmlRewriter::new("img[", |_, _| Ok(()));
+        let processor = HtmlPostProcessor::new(HtmlConfig { minify: true, ..HtmlConfig::default() }, vec![lazy, invalid]);
+        assert!(processor.is_enabled());
+        
+        let output = processor.process(html, "index.html").unwrap();
+        assert!(output.contains("<pre>  keep\n    this  </pre>"));
+        assert!(output.contains("loading=lazy"));
+        assert!(output.contains("body{color:red}"));
+        assert!(!output.contains("note"));
+        assert!(output.len() < html.len());
+        
+        let failing = HtmlRewriter::new("p", |_, _| Err(anyhow!("boom")));
+        let processor = HtmlPostProcessor::new(HtmlConfig::default(), vec![failing]);
+        assert!(processor.process(html, "index.html").is_err());
+        
+        assert!(!HtmlPostProcessor::new(HtmlConfig::default(), Vec::new()).is_enabled());
+    }
+    
+    #[test]
+    fn test_page_rewriters() {
+        let lazy = HtmlRewriter::new("img", |element, _page| {
+            element.set_attribute("loading", "lazy")?;
+            Ok(())
+        });
+        let footer: PageRewriter = Arc::new(|html, page| Ok(html.replace("</body>", &format!("<footer>{}</footer></body>", page))));
+        let processor = HtmlPostProcessor::new(HtmlConfig::default(), vec![lazy]).with_page_rewriters(vec![footer]);
+        
+        assert_eq!(
+            processor.process("<body><img></body>", "a/index.html").unwrap(),
+            "<body><img loading=\"lazy\"><footer>a/index.html</footer></body>"
+        );
+        
+        let failing: PageRewriter = Arc::new(|_, _| Err(anyhow!("boom")));
+        let processor = HtmlPostProcessor::new(HtmlConfig::default(), Vec::new()).with_page_rewriters(vec![failing]);
+        assert!(processor.is_enabled());
+        assert!(processor.process("<body></body>", "index.html").is_err());
+    }
+    
+    #[test]
+    fn test_rewriter_page() {
+        let marker = HtmlRewriter::new("body", |element, page| {
+            if page != "search/index.html" {
+                element.set_attribute("data-page", page)?;
+            }
+            Ok(())
+        });
+        let processor = HtmlPostProcessor::new(HtmlConfig::default(), vec![marker]);
+        
+        let html = "<html><body></body></html>";
+        assert_eq!(processor.process(html, "posts/a/index.html").unwrap(), "<html><body data-page=\"posts/a/index.html\"></body></html>");
+        assert_eq!(processor.process(html, "search/index.html").unwrap(), html);
+    }
+}
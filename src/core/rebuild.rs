@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// 监视模式下收集到的文件变化，用于决定需要重新生成的内容
+#[derive(Debug, Default, Clone)]
+pub struct ChangeSet {
+    /// 站点或主题配置文件是否发生变化
+    pub config: bool,
+    /// 新增或修改的文章源文件
+    pub posts: BTreeSet<PathBuf>,
+    /// 删除的文章源文件
+    pub removed_posts: BTreeSet<PathBuf>,
+    /// 修改的模板（相对于主题 layout 目录）
+    pub templates: BTreeSet<String>,
+    /// 新增或修改的主题资源（相对于主题 source 目录）
+    pub assets: BTreeSet<PathBuf>,
+    /// 删除的主题资源（相对于主题 source 目录）
+    pub removed_assets: BTreeSet<PathBuf>,
+    /// 无法归类的变化
+    pub others: BTreeSet<PathBuf>,
+}
+
+impl ChangeSet {
+    /// 创建空的变化集合
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个变化的路径，根据其所在目录归类
+    pub fn record(&mut self, path: &Path, source_dir: &Path, theme_dir: &Path) {
+        let removed = !path.exists();
+
+        // 文章源文件
+        if path.starts_with(source_dir.join("_posts"))
+            && path.extension().and_then(|s| s.to_str()) == Some("md")
+        {
+            if removed {
+                self.removed_posts.insert(path.to_path_buf());
+            } else {
+                self.posts.insert(path.to_path_buf());
+            }
+            return;
+        }
+
+        // 主题模板
+        if let Ok(rel_path) = path.strip_prefix(theme_dir.join("layout")) {
+            if !path.is_dir() {
+                self.templates.insert(rel_path.to_string_lossy().replace('\\', "/"));
+                return;
+            }
+        }
+
+        // 主题资源
+        if let Ok(rel_path) = path.strip_prefix(theme_dir.join("source")) {
+            if removed {
+                self.removed_assets.insert(rel_path.to_path_buf());
+                return;
+            }
+            if path.is_file() {
+                self.assets.insert(rel_path.to_path_buf());
+                return;
+            }
+        }
+
+        self.others.insert(path.to_path_buf());
+    }
+
+    /// 记录站点级模板覆盖目录中的变化，路径不在这些目录中时返回 `false`
+    pub fn record_layout_override(&mut self, path: &Path, layout_dirs: &[PathBuf]) -> bool {
+        for dir in layout_dirs {
+            if let Ok(rel_path) = path.strip_prefix(dir) {
+                if !path.is_dir() && !rel_path.as_os_str().is_empty() {
+                    self.templates.insert(rel_path.to_string_lossy().replace('\\', "/"));
+                }
+                return true;
+            }
+        }
+        false
+    }
+    
+    /// 是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        !self.config
+            && self.posts.is_empty()
+            && self.removed_posts.is_empty()
+            && self.templates.is_empty()
+            && self.assets.is_empty()
+            && self.removed_assets.is_empty()
+            && self.others.is_empty()
+    }
+
+    /// 是否需要完整重新生成
+    pub fn requires_full_rebuild(&self) -> bool {
+        self.config
+            || !self.others.is_empty()
+            || self.templates.iter().any(|t| !Self::is_targeted_template(t))
+    }
+
+    /// 判断模板是否可以按需重新渲染
+    ///
+    /// 顶层页面模板只影响使用它的页面；`layout.html` 和子目录中的局部模板
+    /// 可能被任意页面引用，需要完整重新生成。
+    fn is_targeted_template(template: &str) -> bool {
+        !template.contains('/') && template != "layout.html" && template.ends_with(".html")
+    }
+
+    /// 变化摘要，用于日志输出
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.config {
+            parts.push("配置".to_string());
+        }
+        if !self.posts.is_empty() {
+            parts.push(format!("文章 {} 篇", self.posts.len()));
+        }
+        if !self.templates.is_empty() {
+            let templates: Vec<&str> = self.templates.iter().map(String::as_str).collect();
+            parts.push(format!("模板 {}", templates.join(", ")));
+        }
+        if !self.assets.is_empty() {
+            parts.push(format!("资源 {} 个", self.assets.len()));
+        }
+        let removed = self.removed_posts.len() + self.removed_assets.len();
+        if removed > 0 {
+            parts.push(format!("删除 {} 个", removed));
+        }
+        if !self.others.is_empty() {
+            parts.push(format!("其他 {} 个", self.others.len()));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_classifies_paths() {
+        let source_dir = PathBuf::from("/site/source");
+        let theme_dir = PathBuf::from("/site/themes/default");
+        let mut changes = ChangeSet::new();
+
+        changes.record(&source_dir.join("_posts/missing.md"), &source_dir, &theme_dir);
+        changes.record(&theme_dir.join("layout/post.html"), &source_dir, &theme_dir);
+        changes.record(&theme_dir.join("source/css/missing.css"), &source_dir, &theme_dir);
+
+        assert!(changes.removed_posts.contains(&source_dir.join("_posts/missing.md")));
+        assert!(changes.templates.contains("post.html"));
+        assert!(changes.removed_assets.contains(&PathBuf::from("css/missing.css")));
+        assert!(!changes.requires_full_rebuild());
+
+        changes.record(&theme_dir.join("layout/layout.html"), &source_dir, &theme_dir);
+        assert!(changes.requires_full_rebuild());
+        
+        let mut changes = ChangeSet::new();
+        let layout_dirs = vec![source_dir.join("_layouts")];
+        assert!(changes.record_layout_override(&source_dir.join("_layouts/photo.html"), &layout_dirs));
+        assert!(!changes.record_layout_override(&source_dir.join("_posts/a.md"), &layout_dirs));
+        assert!(changes.templates.contains("photo.html"));
+        assert!(!changes.requires_full_rebuild());
+    }
+}
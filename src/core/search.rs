@@ -1,10 +1,29 @@
+use crate::models::config::SearchConfig;
 use crate::models::Post;
+use crate::theme::helpers::{strip_html, truncate};
+use crate::utils::url::UrlHelper;
 use anyhow::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 use tracing::info;
 
+/// 倒排索引格式的版本，索引结构变化时递增
+pub const INDEX_VERSION: u32 = 1;
+
+/// 没有摘要时从内容截取的长度
+const EXCERPT_LENGTH: usize = 150;
+
+/// 搜索页面相对于站点根目录的路径
+pub const PAGE_PATH: &str = "search/index.html";
+
+/// 搜索脚本相对于站点根目录的路径
+pub const SCRIPT_PATH: &str = "search/search.js";
+
+/// 浏览器端的搜索脚本，读取本模块生成的索引
+pub const SCRIPT: &str = include_str!("../../embed/search/search.js");
+
 /// 搜索索引项
 #[derive(Debug, Serialize)]
 pub struct SearchIndexItem {
@@ -22,63 +41,532 @@ pub struct SearchIndexItem {
     pub tags: Vec<String>,
 }
 
+/// 词条对应的文章序号和得分
+pub type Postings = Vec<(usize, f32)>;
+
+/// 倒排索引中的文章
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchDocument {
+    pub title: String,
+    /// 文章相对于站点根目录的路径
+    pub path: String,
+    /// 文章链接
+    pub url: String,
+    pub date: String,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    /// 去除 HTML 后的摘要
+    pub excerpt: String,
+}
+
+/// 倒排索引，词条对应文章序号和得分，按得分降序排列
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    pub documents: Vec<SearchDocument>,
+    pub terms: BTreeMap<String, Postings>,
+    /// 各文章去除 HTML 后的内容，用于生成结果摘要，不写入索引文件
+    pub texts: Vec<String>,
+}
+
+impl SearchIndex {
+    /// 检索包含查询中所有词条的文章，返回文章序号和得分，按得分降序排列
+    pub fn search(&self, query: &str) -> Vec<(usize, f32)> {
+        let mut tokens = tokenize(query);
+        tokens.sort();
+        tokens.dedup();
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut matches: HashMap<usize, (usize, f32)> = HashMap::new();
+        for token in &tokens {
+            for (doc, score) in self.terms.get(token).into_iter().flatten() {
+                let entry = matches.entry(*doc).or_default();
+                entry.0 += 1;
+                entry.1 += score;
+            }
+        }
+        
+        let mut results: Vec<(usize, f32)> = matches.into_iter()
+            .filter(|(_, (count, _))| *count == tokens.len())
+            .map(|(doc, (_, score))| (doc, score))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results
+    }
+}
+
+/// 倒排索引的清单文件，分片按需加载
+#[derive(Serialize)]
+struct SearchManifest<'a> {
+    version: u32,
+    index: &'static str,
+    documents: &'a [SearchDocument],
+    /// 分片键（词条首字符的十六进制码点）对应的分片路径
+    shards: BTreeMap<String, String>,
+}
+
 /// 搜索索引生成器
-pub struct SearchIndexGenerator {
-    /// 是否使用全文
-    use_full_content: bool,
+pub struct SearchIndexGenerator<'a> {
+    config: &'a SearchConfig,
+    urls: &'a UrlHelper,
 }
 
-impl SearchIndexGenerator {
+impl<'a> SearchIndexGenerator<'a> {
     /// 创建新的搜索索引生成器
-    pub fn new(use_full_content: bool) -> Self {
-        Self { use_full_content }
+    pub fn new(config: &'a SearchConfig, urls: &'a UrlHelper) -> Self {
+        Self { config, urls }
     }
     
-    /// 生成搜索索引
+    /// 生成搜索索引和搜索脚本
+    ///
+    /// `index` 为 `inverted` 时，`path` 为清单文件，分片写入同一目录下的 `<文件名>.<分片键>.json`。
     pub fn generate(&self, posts: &[Post], output_dir: &Path) -> Result<()> {
         info!("Generating search index...");
         
-        let mut index = Vec::new();
+        let posts = self.select(posts);
+        let path = self.config.path.trim_start_matches('/');
+        let output_path = output_dir.join(path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
         
-        for post in posts {
-            let content = if self.use_full_content {
-                post.content.clone()
-            } else {
-                post.excerpt.clone().unwrap_or_else(|| {
-                    // 如果没有摘要，使用内容的前150个字符
-                    let content = &post.content;
-                    if content.len() > 150 {
-                        if let Some(idx) = content.char_indices().nth(150).map(|(i, _)| i) {
-                            format!("{}...", &content[..idx])
-                        } else {
-                            content.clone()
-                        }
-                    } else {
-                        content.clone()
-                    }
-                })
-            };
-            
-            // 创建索引项
-            let item = SearchIndexItem {
-                title: post.title.clone(),
-                path: post.path.clone(),
-                content,
-                date: post.date.format("%Y-%m-%d").to_string(),
-                categories: post.categories.clone(),
-                tags: post.tags.clone(),
-            };
-            
-            index.push(item);
+        match self.config.index.as_str() {
+            "inverted" => self.write_inverted(&self.build(&posts), path, output_dir)?,
+            _ => {
+                let index: Vec<SearchIndexItem> = posts.iter().map(|post| SearchIndexItem {
+                    title: post.title.clone(),
+                    path: post.path.clone(),
+                    content: self.content(post),
+                    date: post.date.format("%Y-%m-%d").to_string(),
+                    categories: post.categories.clone(),
+                    tags: post.tags.clone(),
+                }).collect();
+                fs::write(output_path, serde_json::to_string(&index)?)?;
+            }
         }
         
-        // 将索引写入文件
-        let search_dir = output_dir.join("search");
-        fs::create_dir_all(&search_dir)?;
-        let index_json = serde_json::to_string(&index)?;
-        fs::write(search_dir.join("search.json"), index_json)?;
+        let script_path = output_dir.join(SCRIPT_PATH);
+        if let Some(parent) = script_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(script_path, SCRIPT)?;
         
         info!("Search index generated successfully");
         Ok(())
     }
-} 
\ No newline at end of file
+    
+    /// 按 `field` 筛选文章并构建倒排索引
+    pub fn index(&self, posts: &[Post]) -> SearchIndex {
+        self.build(&self.select(posts))
+    }
+    
+    /// 构建倒排索引
+    ///
+    /// 词条在标题、标签和分类、内容中出现时分别按 `weights` 计分，内容中的词频取对数，最后乘以逆文档频率。
+    pub fn build(&self, posts: &[&Post]) -> SearchIndex {
+        let weights = &self.config.weights;
+        let mut terms: BTreeMap<String, Postings> = BTreeMap::new();
+        let mut documents = Vec::with_capacity(posts.len());
+        let mut texts = Vec::with_capacity(posts.len());
+        
+        for (id, post) in posts.iter().enumerate() {
+            let mut scores: HashMap<String, f32> = HashMap::new();
+            for token in tokenize(&post.title) {
+                *scores.entry(token).or_default() += weights.title;
+            }
+            for name in post.categories.iter().chain(&post.tags) {
+                for token in tokenize(name) {
+                    *scores.entry(token).or_default() += weights.tags;
+                }
+            }
+            let text = unescape_html(&strip_html(&self.content(post)));
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for token in tokenize(&text) {
+                *counts.entry(token).or_default() += 1;
+            }
+            for (token, count) in counts {
+                *scores.entry(token).or_default() += weights.content * (1.0 + (count as f32).ln());
+            }
+            
+            for (term, score) in scores {
+                terms.entry(term).or_default().push((id, score));
+            }
+            documents.push(self.document(post));
+            texts.push(text);
+        }
+        
+        let total = posts.len() as f32;
+        for postings in terms.values_mut() {
+            let idf = (1.0 + total / postings.len() as f32).ln();
+            for (_, score) in postings.iter_mut() {
+                *score = (*score * idf * 1000.0).round() / 1000.0;
+            }
+            postings.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        }
+        
+        SearchIndex { documents, terms, texts }
+    }
+    
+    fn write_inverted(&self, index: &SearchIndex, path: &str, output_dir: &Path) -> Result<()> {
+        let stem = path.strip_suffix(".json").unwrap_or(path);
+        
+        // 清理上次生成的分片
+        let output_path = output_dir.join(path);
+        if let (Some(dir), Some(name)) = (output_path.parent(), Path::new(stem).file_name().and_then(|name| name.to_str())) {
+            let prefix = format!("{}.", name);
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if file_name.starts_with(&prefix) && file_name.ends_with(".json") {
+                    fs::remove_file(entry.path())?;
+                }
+            }
+        }
+        
+        let mut shards: BTreeMap<String, BTreeMap<&str, &Postings>> = BTreeMap::new();
+        for (term, postings) in &index.terms {
+            shards.entry(shard_key(term)).or_default().insert(term, postings);
+        }
+        
+        let mut shard_paths = BTreeMap::new();
+        for (key, terms) in shards {
+            let shard_path = format!("{}.{}.json", stem, key);
+            fs::write(output_dir.join(&shard_path), serde_json::to_string(&terms)?)?;
+            shard_paths.insert(key, shard_path);
+        }
+        
+        let manifest = SearchManifest {
+            version: INDEX_VERSION,
+            index: "inverted",
+            documents: &index.documents,
+            shards: shard_paths,
+        };
+        fs::write(output_path, serde_json::to_string(&manifest)?)?;
+        Ok(())
+    }
+    
+    /// 按 `field` 筛选文章
+    fn select<'p>(&self, posts: &'p [Post]) -> Vec<&'p Post> {
+        posts.iter()
+            .filter(|post| match self.config.field.as_str() {
+                "all" => true,
+                "page" => post.layout == "page",
+                _ => post.layout != "page",
+            })
+            .collect()
+    }
+    
+    /// 按 `content` 和 `format` 选择索引的内容
+    fn content(&self, post: &Post) -> String {
+        if !self.config.content {
+            return excerpt(post);
+        }
+        match self.config.format.as_str() {
+            "html" => post.rendered_content.clone().unwrap_or_else(|| post.content.clone()),
+            "excerpt" => excerpt(post),
+            "more" => post.content.split_once("<!-- more -->")
+                .map(|(_, more)| more.trim_start().to_string())
+                .unwrap_or_else(|| post.content.clone()),
+            _ => post.content.clone(),
+        }
+    }
+    
+    fn document(&self, post: &Post) -> SearchDocument {
+        SearchDocument {
+            title: post.title.clone(),
+            path: post.path.clone(),
+            url: self.urls.url_for_page(&post.path),
+            date: post.date.format("%Y-%m-%d").to_string(),
+            categories: post.categories.clone(),
+            tags: post.tags.clone(),
+            excerpt: truncate(&strip_html(&excerpt(post)), EXCERPT_LENGTH),
+        }
+    }
+}
+
+/// 引用搜索脚本的 script 标签，索引地址和界面选项通过 data 属性传入
+pub fn script_tag(config: &SearchConfig, urls: &UrlHelper) -> String {
+    format!(
+        r#"<script src="{}" data-index="{}" data-root="{}" data-limit="{}" data-live="{}" data-highlight="{}" data-preview-length="{}" defer></script>"#,
+        urls.url_for(SCRIPT_PATH, None),
+        urls.url_for(&config.path, None),
+        urls.url_for("", None),
+        config.ui.limit,
+        config.ui.live,
+        config.ui.highlight,
+        config.ui.preview_length,
+    )
+}
+
+/// 截取 `text` 中第一个匹配查询的位置附近约 `length` 个字符，转义 HTML 并用 `<mark>` 标出匹配的词
+///
+/// 英文单词按词干匹配，如查询 `searching` 会标出 `searches`；中日韩文字按查询中的词条匹配。
+pub fn snippet(text: &str, query: &str, length: usize) -> String {
+    let terms = tokenize(query);
+    let mut ranges = Vec::new();
+    
+    // 英文和数字按单词比较词干
+    let mut start = None;
+    for (idx, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        let in_word = c.is_alphanumeric() && !is_cjk(c);
+        match (start, in_word) {
+            (None, true) => start = Some(idx),
+            (Some(begin), false) => {
+                let word = text[begin..idx].to_lowercase();
+                if terms.contains(&stem(&word)) {
+                    ranges.push((begin, idx));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    for term in terms.iter().filter(|term| term.chars().any(is_cjk)) {
+        ranges.extend(text.match_indices(term.as_str()).map(|(idx, matched)| (idx, idx + matched.len())));
+    }
+    ranges.sort();
+    
+    // 从第一个匹配之前的几个字符开始截取
+    let first = ranges.first().map(|(begin, _)| *begin).unwrap_or(0);
+    let lead = text[..first].chars().rev().take(length / 4).map(char::len_utf8).sum::<usize>();
+    let window_start = first - lead;
+    let window_end = text[window_start..].char_indices().nth(length).map(|(idx, _)| window_start + idx).unwrap_or(text.len());
+    
+    let mut output = String::new();
+    if window_start > 0 {
+        output.push_str("...");
+    }
+    let mut pos = window_start;
+    for (begin, end) in ranges {
+        if begin < pos || end > window_end {
+            continue;
+        }
+        output.push_str(&escape_html(&text[pos..begin]));
+        output.push_str("<mark>");
+        output.push_str(&escape_html(&text[begin..end]));
+        output.push_str("</mark>");
+        pos = end;
+    }
+    output.push_str(&escape_html(&text[pos..window_end]));
+    if window_end < text.len() {
+        output.push_str("...");
+    }
+    output
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// 还原渲染后内容中的常见字符实体，避免 `&quot;` 等被当作词条索引
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// 文章摘要，没有摘要时使用内容的前 150 个字符
+fn excerpt(post: &Post) -> String {
+    post.excerpt.clone()
+        .filter(|excerpt| !excerpt.is_empty())
+        .unwrap_or_else(|| truncate(&post.content, EXCERPT_LENGTH))
+}
+
+/// 词条所在的分片，为首字符的十六进制码点
+pub fn shard_key(term: &str) -> String {
+    term.chars().next().map(|c| format!("{:x}", c as u32)).unwrap_or_default()
+}
+
+/// 分词
+///
+/// 英文和数字按单词切分并转为小写，英文单词提取词干；中日韩文字没有分隔符，切分为相邻两个字组成的词条。
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+    
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if is_cjk(c) {
+            push_word(&mut tokens, &mut word);
+            cjk.push(c);
+        } else if c.is_alphanumeric() {
+            push_cjk(&mut tokens, &mut cjk);
+            word.push(c);
+        } else {
+            push_word(&mut tokens, &mut word);
+            push_cjk(&mut tokens, &mut cjk);
+        }
+    }
+    push_word(&mut tokens, &mut word);
+    push_cjk(&mut tokens, &mut cjk);
+    tokens
+}
+
+/// 单个字母不作为词条
+fn push_word(tokens: &mut Vec<String>, word: &mut String) {
+    if word.chars().count() > 1 || word.chars().any(|c| c.is_numeric()) {
+        tokens.push(stem(word));
+    }
+    word.clear();
+}
+
+fn push_cjk(tokens: &mut Vec<String>, run: &mut Vec<char>) {
+    match run.len() {
+        0 => {},
+        1 => tokens.push(run[0].to_string()),
+        _ => tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>())),
+    }
+    run.clear();
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF |   // CJK统一汉字
+        0x3400..=0x4DBF |   // CJK扩展A
+        0x20000..=0x2A6DF | // CJK扩展B
+        0xF900..=0xFAFF |   // CJK兼容汉字
+        0x3040..=0x30FF |   // 平假名、片假名
+        0xAC00..=0xD7AF     // 韩文音节
+    )
+}
+
+/// 轻量的英文词干提取
+///
+/// 只去掉常见的复数、时态和进行时词尾，如 `searching`、`searches`、`searched` 都得到 `search`。
+/// 浏览器端的搜索脚本需要使用相同的规则处理查询。
+pub fn stem(word: &str) -> String {
+    if word.len() <= 3 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+    
+    let stemmed = if let Some(base) = word.strip_suffix("ies").filter(|base| base.len() >= 2) {
+        format!("{}y", base)
+    } else if let Some(base) = word.strip_suffix("sses") {
+        format!("{}ss", base)
+    } else if word.ends_with("ss") || word.ends_with("us") || word.ends_with("is") {
+        word.to_string()
+    } else if let Some(base) = word.strip_suffix("ing").filter(|base| base.len() >= 3) {
+        undouble(base)
+    } else if let Some(base) = word.strip_suffix("ed").filter(|base| base.len() >= 3) {
+        undouble(base)
+    } else if let Some(base) = word.strip_suffix("es").filter(|base| ["sh", "ch", "x", "z", "o"].iter().any(|end| base.ends_with(end))) {
+        base.to_string()
+    } else if let Some(base) = word.strip_suffix('s') {
+        base.to_string()
+    } else {
+        word.to_string()
+    };
+    
+    // `make`、`making` 都得到 `mak`
+    match stemmed.strip_suffix('e') {
+        Some(base) if base.len() >= 3 && !base.ends_with('e') => base.to_string(),
+        _ => stemmed,
+    }
+}
+
+/// 去掉重复的结尾辅音，如 `runn` -> `run`，`l`、`s`、`z` 除外
+fn undouble(base: &str) -> String {
+    let bytes = base.as_bytes();
+    let n = bytes.len();
+    if n >= 2 && bytes[n - 1] == bytes[n - 2] && !b"aeiouylsz".contains(&bytes[n - 1]) {
+        base[..n - 1].to_string()
+    } else {
+        base.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::Config;
+    
+    fn post(title: &str, content: &str, tags: &[&str]) -> Post {
+        Post {
+            title: title.to_string(),
+            content: content.to_string(),
+            rendered_content: Some(format!("<p>{}</p>", content)),
+            path: format!("posts/{}.html", title.to_lowercase().replace(' ', "-")),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Post::default()
+        }
+    }
+    
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Searching <b>Rust</b> files, v2"), vec!["search", "rust", "fil", "v2"]);
+        assert_eq!(tokenize("静态网站 generator"), vec!["静态", "态网", "网站", "generator"]);
+        assert_eq!(tokenize("搜索"), vec!["搜索"]);
+        assert_eq!(tokenize("中"), vec!["中"]);
+        for word in ["searches", "searched", "searching", "search"] {
+            assert_eq!(stem(word), "search");
+        }
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("stories"), "story");
+        assert_eq!(stem("boxes"), "box");
+        assert_eq!(stem("making"), stem("make"));
+        assert_eq!(stem("class"), "class");
+    }
+    
+    fn posts() -> [Post; 3] {
+        [
+            post("Rust Search", "全文搜索的实现", &["rust"]),
+            post("Hello", "Searching with rust is fast", &[]),
+            post("About", "静态网站", &[]),
+        ]
+    }
+    
+    fn inverted_config() -> SearchConfig {
+        SearchConfig { index: "inverted".to_string(), content: true, ..SearchConfig::default() }
+    }
+    
+    #[test]
+    fn test_inverted_index() {
+        let config = inverted_config();
+        let urls = UrlHelper::from_config(&Config::default());
+        let posts = posts();
+        let refs: Vec<&Post> = posts.iter().collect();
+        let index = SearchIndexGenerator::new(&config, &urls).build(&refs);
+        
+        let results = index.search("rust searches");
+        assert_eq!(results.iter().map(|(doc, _)| *doc).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(results[0].1 > results[1].1);
+        assert_eq!(index.search("搜索")[0].0, 0);
+        assert!(index.search("rust 网站").is_empty());
+        assert_eq!(index.documents[2].url, "/posts/about.html");
+        assert_eq!(index.texts[1], "Searching with rust is fast");
+    }
+    
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("Searching with rust is fast", "rust searches", 100), "<mark>Searching</mark> with <mark>rust</mark> is fast");
+        assert_eq!(snippet("很长的前文，然后是全文搜索的实现 & more", "搜索", 12), "...是全文<mark>搜索</mark>的实现 &amp; m...");
+        assert_eq!(snippet("no match here", "rust", 5), "no ma...");
+    }
+    
+    #[test]
+    fn test_generate_index_files() {
+        let config = inverted_config();
+        let urls = UrlHelper::from_config(&Config::default());
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path();
+        fs::create_dir_all(output_dir.join("search")).unwrap();
+        fs::write(output_dir.join("search/search.stale.json"), "{}").unwrap();
+        SearchIndexGenerator::new(&config, &urls).generate(&posts(), output_dir).unwrap();
+        
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join("search/search.json")).unwrap()).unwrap();
+        assert_eq!(manifest["version"], INDEX_VERSION);
+        assert_eq!(manifest["documents"][1]["title"], "Hello");
+        assert_eq!(manifest["shards"]["72"], "search/search.72.json");
+        let shard: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join("search/search.72.json")).unwrap()).unwrap();
+        assert_eq!(shard["rust"][0][0], 0);
+        assert!(output_dir.join(format!("search/search.{}.json", shard_key("搜索"))).exists());
+        assert!(!output_dir.join("search/search.stale.json").exists());
+        assert!(output_dir.join(SCRIPT_PATH).exists());
+        assert!(script_tag(&config, &urls).contains(r#"data-index="/search/search.json" data-root="/" data-limit="10""#));
+    }
+}
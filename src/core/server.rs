@@ -1,15 +1,29 @@
 use anyhow::Result;
 use axum::{
+    extract::{Query, State},
     http::StatusCode,
-    routing::get_service,
-    Router,
+    routing::{get, get_service},
+    Json, Router,
 };
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::broadcast;
 use tower_http::services::ServeDir;
 use tracing::info;
 
+use crate::core::search::{snippet, SearchIndex, SearchIndexGenerator};
+use crate::models::config::Config;
+use crate::models::Post;
+use crate::utils::url::UrlHelper;
+
+/// 搜索结果摘要的长度
+const SNIPPET_LENGTH: usize = 160;
+
 /// HTTP 服务器
 pub struct Server {
     /// 站点目录
@@ -18,6 +32,8 @@ pub struct Server {
     port: u16,
     /// 文件更改通知通道
     tx: broadcast::Sender<()>,
+    /// 开发接口
+    api: Option<DevApi>,
 }
 
 impl Server {
@@ -28,9 +44,16 @@ impl Server {
             public_dir,
             port,
             tx,
+            api: None,
         }
     }
     
+    /// 启用 `/__api/` 下的开发接口
+    pub fn with_api(mut self, api: DevApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    
     /// 文件更改事件发送器
     pub fn get_sender(&self) -> broadcast::Sender<()> {
         self.tx.clone()
@@ -47,8 +70,12 @@ impl Server {
             });
         
         // 创建路由
-        let app = Router::new()
-            .fallback_service(serve_dir);
+        let mut app = Router::new();
+        if let Some(api) = self.api {
+            info!("已启用开发接口 /__api/search、/__api/posts、/__api/tags");
+            app = app.merge(api.router());
+        }
+        let app = app.fallback_service(serve_dir);
         
         // 启动HTTP服务器
         let addr: SocketAddr = format!("0.0.0.0:{}", self.port).parse()?;
@@ -60,4 +87,169 @@ impl Server {
         
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// 开发服务器的 JSON 接口，供编辑器等工具在写作时查询站点内容
+///
+/// 接口只由本地服务器提供，不会写入 `public/`。文章列表和配置与引擎共享，监视模式下重新生成或重新加载配置后立即生效。
+/// 搜索索引在首次查询时构建并缓存，引擎递增 `revision` 后才重新构建。
+#[derive(Clone)]
+pub struct DevApi {
+    posts: Arc<RwLock<Vec<Post>>>,
+    config: Arc<RwLock<Config>>,
+    revision: Arc<AtomicU64>,
+    index: Arc<Mutex<Option<CachedIndex>>>,
+}
+
+/// 缓存的搜索索引及构建时的版本号
+type CachedIndex = (u64, Arc<SearchIndex>);
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    q: String,
+    limit: Option<usize>,
+}
+
+impl DevApi {
+    pub fn new(posts: Arc<RwLock<Vec<Post>>>, config: Arc<RwLock<Config>>, revision: Arc<AtomicU64>) -> Self {
+        Self { posts, config, revision, index: Arc::default() }
+    }
+    
+    fn router(self) -> Router {
+        Router::new()
+            .route("/__api/search", get(search))
+            .route("/__api/posts", get(posts))
+            .route("/__api/tags", get(tags))
+            .with_state(Arc::new(self))
+    }
+    
+    /// 按搜索索引的规则检索文章，结果按得分降序排列并带有标出匹配词的摘要
+    fn search(&self, query: &str, limit: Option<usize>) -> Value {
+        let config = self.config.read().unwrap().search.clone().unwrap_or_default();
+        let index = self.search_index();
+        let matches = index.search(query);
+        
+        let results: Vec<Value> = matches.iter()
+            .take(limit.unwrap_or(config.ui.limit))
+            .map(|(doc, score)| {
+                let mut result = serde_json::to_value(&index.documents[*doc]).unwrap_or_default();
+                result["score"] = json!(score);
+                result["snippet"] = json!(snippet(&index.texts[*doc], query, SNIPPET_LENGTH));
+                result
+            })
+            .collect();
+        
+        json!({ "query": query, "total": matches.len(), "results": results })
+    }
+    
+    /// 返回缓存的搜索索引，文章或配置变化后重新构建
+    ///
+    /// 先读取版本号再复制文章列表，构建期间发生的变化会让下次查询再次重建。构建在文章锁之外进行。
+    fn search_index(&self) -> Arc<SearchIndex> {
+        let mut cached = self.index.lock().unwrap();
+        let revision = self.revision.load(Ordering::SeqCst);
+        if let Some((built, index)) = cached.as_ref() {
+            if *built == revision {
+                return index.clone();
+            }
+        }
+        
+        let config = self.config.read().unwrap().clone();
+        let posts = self.posts.read().unwrap().clone();
+        let search_config = config.search.clone().unwrap_or_default();
+        let urls = UrlHelper::from_config(&config);
+        let index = Arc::new(SearchIndexGenerator::new(&search_config, &urls).index(&posts));
+        *cached = Some((revision, index.clone()));
+        index
+    }
+    
+    /// 全部文章，按发布时间倒序排列
+    fn posts(&self) -> Value {
+        let urls = UrlHelper::from_config(&self.config.read().unwrap());
+        let mut posts: Vec<Post> = self.posts.read().unwrap().clone();
+        posts.sort_by_key(|post| std::cmp::Reverse(post.date));
+        
+        Value::Array(posts.iter().map(|post| json!({
+            "title": post.title,
+            "path": post.path,
+            "url": urls.url_for_page(&post.path),
+            "source": post.source,
+            "date": post.date.to_rfc3339(),
+            "updated": post.updated.map(|updated| updated.to_rfc3339()),
+            "layout": post.layout,
+            "categories": post.categories,
+            "tags": post.tags,
+        })).collect())
+    }
+    
+    /// 全部标签及其文章数，按名称排序
+    fn tags(&self) -> Value {
+        let urls = UrlHelper::from_config(&self.config.read().unwrap());
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for post in self.posts.read().unwrap().iter() {
+            for tag in &post.tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        
+        Value::Array(counts.into_iter().map(|(name, count)| json!({
+            "name": name,
+            "count": count,
+            "url": urls.url_for_tag(&name),
+        })).collect())
+    }
+}
+
+async fn search(State(api): State<Arc<DevApi>>, Query(params): Query<SearchParams>) -> Json<Value> {
+    Json(api.search(&params.q, params.limit))
+}
+
+async fn posts(State(api): State<Arc<DevApi>>) -> Json<Value> {
+    Json(api.posts())
+}
+
+async fn tags(State(api): State<Arc<DevApi>>) -> Json<Value> {
+    Json(api.tags())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    
+    fn post(title: &str, content: &str) -> Post {
+        Post {
+            title: title.to_string(),
+            date: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            rendered_content: Some(format!("<p>{}</p>", content)),
+            path: format!("posts/{}/index.html", title.to_lowercase()),
+            ..Post::default()
+        }
+    }
+    
+    #[test]
+    fn test_dev_api_follows_changes() {
+        let posts = Arc::new(RwLock::new(vec![post("Rust", "rust notes")]));
+        let config = Arc::new(RwLock::new(Config::default()));
+        let revision = Arc::new(AtomicU64::new(0));
+        let api = DevApi::new(posts.clone(), config.clone(), revision.clone());
+        
+        // 未变化时复用缓存的索引
+        let index = api.search_index();
+        assert!(Arc::ptr_eq(&index, &api.search_index()));
+        assert_eq!(api.search("tera", None)["total"], 0);
+        
+        // 文章变化并递增版本号后重新构建
+        posts.write().unwrap().push(post("Tera", "tera templates"));
+        revision.fetch_add(1, Ordering::SeqCst);
+        assert!(!Arc::ptr_eq(&index, &api.search_index()));
+        assert_eq!(api.search("tera", None)["total"], 1);
+        
+        // 重新加载配置后链接使用新的根路径
+        config.write().unwrap().root = Some("/blog/".to_string());
+        revision.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(api.posts()[0]["url"], "/blog/posts/rust/");
+        assert_eq!(api.search("rust", None)["results"][0]["url"], "/blog/posts/rust/");
+    }
+}
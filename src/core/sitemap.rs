@@ -0,0 +1,188 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+use crate::models::config::{RobotsConfig, SitemapConfig, SitemapRule};
+use crate::utils::url::UrlHelper;
+
+/// 单个站点地图文件最多包含的链接数
+pub const MAX_URLS_PER_FILE: usize = 50_000;
+
+/// 站点地图中的页面
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapEntry {
+    /// 页面相对于站点根目录的路径
+    pub path: String,
+    /// 最后修改时间
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// 站点地图生成器
+pub struct SitemapGenerator<'a> {
+    config: &'a SitemapConfig,
+    urls: &'a UrlHelper,
+}
+
+impl<'a> SitemapGenerator<'a> {
+    pub fn new(config: &'a SitemapConfig, urls: &'a UrlHelper) -> Self {
+        Self { config, urls }
+    }
+    
+    /// 生成站点地图文件，返回输出路径和内容
+    ///
+    /// 链接超过 `MAX_URLS_PER_FILE` 时拆分为 `sitemap-1.xml`、`sitemap-2.xml` 等，`path` 为引用它们的站点地图索引。
+    pub fn render(&self, entries: &[SitemapEntry]) -> Result<Vec<(String, String)>> {
+        if entries.len() <= MAX_URLS_PER_FILE {
+            return Ok(vec![(self.config.path.clone(), self.render_urlset(entries)?)]);
+        }
+        
+        let (stem, ext) = self.config.path.rsplit_once('.').unwrap_or((&self.config.path, "xml"));
+        let mut files = Vec::new();
+        let mut index = String::with_capacity(4096);
+        writeln!(index, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(index, r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#)?;
+        
+        for (i, chunk) in entries.chunks(MAX_URLS_PER_FILE).enumerate() {
+            let path = format!("{}-{}.{}", stem, i + 1, ext);
+            writeln!(index, "  <sitemap>")?;
+            writeln!(index, "    <loc>{}</loc>", escape(&self.urls.full_url_for(&path)))?;
+            if let Some(lastmod) = chunk.iter().filter_map(|entry| entry.lastmod).max() {
+                writeln!(index, "    <lastmod>{}</lastmod>", lastmod.to_rfc3339())?;
+            }
+            writeln!(index, "  </sitemap>")?;
+            files.push((path, self.render_urlset(chunk)?));
+        }
+        
+        writeln!(index, "</sitemapindex>")?;
+        files.push((self.config.path.clone(), index));
+        Ok(files)
+    }
+    
+    fn render_urlset(&self, entries: &[SitemapEntry]) -> Result<String> {
+        let mut xml = String::with_capacity(256 * entries.len() + 128);
+        writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(xml, r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#)?;
+        
+        for entry in entries {
+            // 目录页面使用以 `/` 结尾的链接
+            let path = entry.path.strip_suffix("index.html").unwrap_or(&entry.path);
+            writeln!(xml, "  <url>")?;
+            writeln!(xml, "    <loc>{}</loc>", escape(&self.urls.full_url_for(path)))?;
+            if let Some(lastmod) = entry.lastmod {
+                writeln!(xml, "    <lastmod>{}</lastmod>", lastmod.to_rfc3339())?;
+            }
+            if let Some(rule) = self.rule_for(&entry.path) {
+                if let Some(changefreq) = &rule.changefreq {
+                    writeln!(xml, "    <changefreq>{}</changefreq>", escape(changefreq))?;
+                }
+                if let Some(priority) = rule.priority {
+                    writeln!(xml, "    <priority>{:.1}</priority>", priority.clamp(0.0, 1.0))?;
+                }
+            }
+            writeln!(xml, "  </url>")?;
+        }
+        
+        writeln!(xml, "</urlset>")?;
+        Ok(xml)
+    }
+    
+    fn rule_for(&self, path: &str) -> Option<&SitemapRule> {
+        self.config.rules.iter().find(|rule| matches_pattern(&rule.pattern, path))
+    }
+}
+
+/// 生成 robots.txt，`sitemap` 为站点地图的路径
+pub fn robots_txt(config: &RobotsConfig, urls: &UrlHelper, sitemap: Option<&str>) -> String {
+    let mut content = format!("User-agent: {}\n", config.user_agent);
+    for path in &config.allow {
+        content.push_str(&format!("Allow: {}\n", path));
+    }
+    for path in &config.disallow {
+        content.push_str(&format!("Disallow: {}\n", path));
+    }
+    if config.allow.is_empty() && config.disallow.is_empty() {
+        content.push_str("Disallow:\n");
+    }
+    if let Some(sitemap) = sitemap {
+        content.push_str(&format!("\nSitemap: {}\n", urls.full_url_for(sitemap)));
+    }
+    content
+}
+
+/// 路径是否匹配模式，`*` 匹配任意字符（包括 `/`），模式开头的 `/` 会被忽略
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches('/');
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // 没有通配符时需要完全匹配
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::Config;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_sitemap() {
+        let urls = UrlHelper::from_config(&Config {
+            url: Some("https://example.com".to_string()),
+            ..Config::default()
+        });
+        let config = SitemapConfig {
+            rules: vec![
+                SitemapRule { pattern: "posts/*".to_string(), changefreq: Some("monthly".to_string()), priority: Some(0.8) },
+                SitemapRule { pattern: "*".to_string(), changefreq: None, priority: Some(0.5) },
+            ],
+            ..SitemapConfig::default()
+        };
+        let date = Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap();
+        let entries = vec![
+            SitemapEntry { path: "index.html".to_string(), lastmod: None },
+            SitemapEntry { path: "posts/a&b.html".to_string(), lastmod: Some(date) },
+        ];
+        
+        let files = SitemapGenerator::new(&config, &urls).render(&entries).unwrap();
+        assert_eq!(files.len(), 1);
+        let (path, xml) = &files[0];
+        assert_eq!(path, "sitemap.xml");
+        assert!(xml.contains("<loc>https://example.com/</loc>\n    <priority>0.5</priority>"));
+        assert!(xml.contains("<loc>https://example.com/posts/a&amp;b.html</loc>\n    <lastmod>2024-05-01T08:00:00+00:00</lastmod>\n    <changefreq>monthly</changefreq>\n    <priority>0.8</priority>"));
+        
+        let many: Vec<_> = (0..MAX_URLS_PER_FILE + 1).map(|i| SitemapEntry { path: format!("p/{}.html", i), lastmod: None }).collect();
+        let files = SitemapGenerator::new(&config, &urls).render(&many).unwrap();
+        let names: Vec<_> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(names, vec!["sitemap-1.xml", "sitemap-2.xml", "sitemap.xml"]);
+        assert!(files[2].1.contains("<loc>https://example.com/sitemap-2.xml</loc>"));
+        
+        let robots = robots_txt(&RobotsConfig { disallow: vec!["/drafts/".to_string()], ..RobotsConfig::default() }, &urls, Some("sitemap.xml"));
+        assert_eq!(robots, "User-agent: *\nDisallow: /drafts/\n\nSitemap: https://example.com/sitemap.xml\n");
+        
+        assert!(matches_pattern("/tags/*/index.html", "tags/rust/index.html"));
+        assert!(!matches_pattern("tags/*", "posts/a.html"));
+        assert!(!matches_pattern("about", "about/index.html"));
+    }
+}
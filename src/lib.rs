@@ -7,4 +7,6 @@ pub mod utils;
 // Re-export commonly used types and traits
 pub use crate::plugins::{Plugin, PluginContext, PluginHook};
 pub use crate::models::{Post, Page, Category, Tag};
-pub use crate::theme::renderer::ThemeRenderer; 
\ No newline at end of file
+pub use crate::theme::renderer::ThemeRenderer;
+// 插件改写 HTML 时插入内容使用
+pub use lol_html::html_content::ContentType as HtmlContentType; 
\ No newline at end of file
@@ -40,6 +40,10 @@ pub struct Config {
     pub time_format: Option<String>,
     pub per_page: Option<i32>,
     pub pagination_dir: Option<String>,
+    pub index_generator: Option<GeneratorConfig>,
+    pub category_generator: Option<GeneratorConfig>,
+    pub tag_generator: Option<GeneratorConfig>,
+    pub archive_generator: Option<GeneratorConfig>,
     pub theme: Option<String>,
     pub theme_config: Option<serde_yaml::Value>,
     pub deploy: Option<DeployConfig>,
@@ -48,6 +52,24 @@ pub struct Config {
     pub search: Option<SearchConfig>,
     pub plugins: Option<Vec<String>>,
     pub comments: Option<CommentsConfig>,
+    pub assets: Option<AssetConfig>,
+    pub html: Option<HtmlConfig>,
+    pub images: Option<ImageConfig>,
+    pub sitemap: Option<SitemapConfig>,
+    pub robots: Option<RobotsConfig>,
+}
+
+/// 列表页面生成配置（首页、分类、标签、归档）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GeneratorConfig {
+    /// 每页文章数，覆盖全局 `per_page`，为 0 时不分页
+    pub per_page: Option<usize>,
+    /// 是否生成按年归档页面（仅归档）
+    pub yearly: Option<bool>,
+    /// 是否生成按月归档页面（仅归档）
+    pub monthly: Option<bool>,
+    /// 是否生成按日归档页面（仅归档）
+    pub daily: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,26 +94,174 @@ pub struct DeployConfig {
     pub message: Option<String>,
 }
 
+/// 订阅源配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FeedConfig {
     pub enable: bool,
+    /// 生成 `rss.xml`
     pub rss: bool,
+    /// 生成 `atom.xml`
     pub atom: bool,
+    /// 生成 JSON Feed 1.1 格式的 `feed.json`，默认关闭
+    pub json: bool,
+    /// 订阅源所在的目录，默认为站点根目录
     pub path: Option<String>,
+    /// 包含的文章数，为 0 时包含全部文章
     pub limit: usize,
+    /// `html`（默认）包含渲染后的完整正文，`excerpt` 只包含摘要
     pub content_type: String,
+    /// 文章排序字段：`date`、`updated` 或 `title`，前缀 `-` 表示降序
     pub order_by: String,
     pub icon: Option<String>,
     pub logo: Option<String>,
+    /// 为每个分类生成订阅源，如 `categories/<分类>/atom.xml`
+    pub categories: bool,
+    /// 为每个标签生成订阅源，如 `tags/<标签>/atom.xml`
+    pub tags: bool,
+    /// 播客订阅源，包含指定分类中带有 `audio` 的文章
+    pub podcast: Option<PodcastConfig>,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            rss: true,
+            atom: true,
+            json: false,
+            path: None,
+            limit: 20,
+            content_type: "html".to_string(),
+            order_by: "-date".to_string(),
+            icon: None,
+            logo: None,
+            categories: false,
+            tags: false,
+            podcast: None,
+        }
+    }
+}
+
+/// 播客订阅源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PodcastConfig {
+    /// 播客文章所在的分类
+    pub category: String,
+    /// 输出路径
+    pub path: String,
+    /// 播客标题，默认为站点标题
+    pub title: Option<String>,
+    /// 播客简介，默认为站点描述
+    pub description: Option<String>,
+    /// 默认为站点作者
+    pub author: Option<String>,
+    pub owner_name: Option<String>,
+    pub owner_email: Option<String>,
+    /// 封面图片，Apple Podcasts 要求至少 1400×1400
+    pub image: Option<String>,
+    /// iTunes 分类，如 `Technology`
+    pub itunes_category: Option<String>,
+    pub itunes_subcategory: Option<String>,
+    /// 是否包含不适合未成年人的内容，文章可以通过 front matter 中的 `explicit` 覆盖
+    pub explicit: bool,
+    /// `podcast:guid`，播客的全局唯一标识
+    pub guid: Option<String>,
+    /// `podcast:locked`，禁止其他平台导入
+    pub locked: Option<bool>,
+}
+
+impl Default for PodcastConfig {
+    fn default() -> Self {
+        Self {
+            category: String::new(),
+            path: "podcast.xml".to_string(),
+            title: None,
+            description: None,
+            author: None,
+            owner_name: None,
+            owner_email: None,
+            image: None,
+            itunes_category: None,
+            itunes_subcategory: None,
+            explicit: false,
+            guid: None,
+            locked: None,
+        }
+    }
 }
 
+/// 搜索索引配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchConfig {
     pub enable: bool,
+    /// 索引文件的输出路径
     pub path: String,
+    /// 索引的文章：`post`、`page`（`layout: page` 的文章）或 `all`
     pub field: String,
+    /// 是否使用全文，否则只使用摘要
     pub content: bool,
+    /// 全文的格式：`raw`（Markdown）、`html`、`excerpt` 或 `more`（摘要之后的部分）
     pub format: String,
+    /// 索引类型：`list` 输出文章列表，`inverted` 输出按词条前缀分片的倒排索引
+    pub index: String,
+    /// 倒排索引中各字段的权重
+    pub weights: SearchWeights,
+    /// 搜索脚本的界面选项
+    pub ui: SearchUiConfig,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            path: "search/search.json".to_string(),
+            field: "post".to_string(),
+            content: false,
+            format: "raw".to_string(),
+            index: "list".to_string(),
+            weights: SearchWeights::default(),
+            ui: SearchUiConfig::default(),
+        }
+    }
+}
+
+/// 搜索字段权重
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchWeights {
+    pub title: f32,
+    /// 标签和分类
+    pub tags: f32,
+    pub content: f32,
+}
+
+impl Default for SearchWeights {
+    fn default() -> Self {
+        Self { title: 10.0, tags: 5.0, content: 1.0 }
+    }
+}
+
+/// 搜索脚本的界面选项，通过 script 标签的 data 属性传给 `search.js`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchUiConfig {
+    /// 最多显示的结果数
+    pub limit: usize,
+    /// 输入时实时搜索，否则按回车搜索
+    pub live: bool,
+    /// 用 `<mark>` 标出匹配的词
+    pub highlight: bool,
+    /// 结果摘要的长度
+    pub preview_length: usize,
+}
+
+impl Default for SearchUiConfig {
+    fn default() -> Self {
+        Self { limit: 10, live: true, highlight: true, preview_length: 200 }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +272,144 @@ pub struct CommentsConfig {
     pub category_id: String,
 }
 
+/// 主题静态资源处理配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetConfig {
+    /// 编译 `.scss`、`.sass` 文件为 CSS，以 `_` 开头的文件只作为被引用的局部文件
+    pub sass: bool,
+    /// 压缩 CSS
+    pub minify_css: bool,
+    /// 压缩 JS，已压缩的 `.min.js` 不再处理
+    pub minify_js: bool,
+    /// 在文件名中加入内容哈希，如 `style.3fa9c1.css`
+    pub fingerprint: bool,
+    /// 加入哈希的文件扩展名
+    pub fingerprint_extensions: Vec<String>,
+    /// 资源清单的输出路径，记录原文件名到带哈希文件名的映射，开启 `fingerprint` 时生成
+    pub manifest: String,
+}
+
+impl Default for AssetConfig {
+    fn default() -> Self {
+        Self {
+            sass: true,
+            minify_css: false,
+            minify_js: false,
+            fingerprint: false,
+            fingerprint_extensions: vec!["css".to_string(), "js".to_string()],
+            manifest: "assets-manifest.json".to_string(),
+        }
+    }
+}
+
+/// 生成页面的后处理配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HtmlConfig {
+    /// 压缩生成的 HTML，`<pre>` 和 `<textarea>` 中的空白保持不变
+    pub minify: bool,
+    /// 压缩页面内联的 `<style>` 和 `style` 属性
+    pub minify_css: bool,
+    /// 压缩页面内联的 `<script>`
+    pub minify_js: bool,
+    /// 压缩时保留 HTML 注释
+    pub keep_comments: bool,
+}
+
+impl Default for HtmlConfig {
+    fn default() -> Self {
+        Self {
+            minify: false,
+            minify_css: true,
+            minify_js: true,
+            keep_comments: false,
+        }
+    }
+}
+
+/// 文章图片处理配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageConfig {
+    /// 处理文章引用的图片，默认关闭，关闭时图片原样复制
+    pub enable: bool,
+    /// 生成的宽度，大于等于原图宽度的不生成
+    pub widths: Vec<u32>,
+    /// 额外生成的格式，目前支持 `webp`（无损编码）
+    pub formats: Vec<String>,
+    /// JPEG 的压缩质量（1-100）
+    pub quality: u8,
+    /// `<img>` 的 `sizes` 属性
+    pub sizes: String,
+    /// 处理结果的缓存目录，相对于站点目录
+    pub cache_dir: String,
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            widths: vec![480, 960, 1440],
+            formats: vec!["webp".to_string()],
+            quality: 80,
+            sizes: "100vw".to_string(),
+            cache_dir: ".cache/images".to_string(),
+        }
+    }
+}
+
+/// 站点地图配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SitemapConfig {
+    pub enable: bool,
+    /// 输出路径，超过 50000 个链接时为站点地图索引
+    pub path: String,
+    /// 按路径设置 `changefreq` 和 `priority`，使用第一条匹配的规则
+    pub rules: Vec<SitemapRule>,
+}
+
+impl Default for SitemapConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            path: "sitemap.xml".to_string(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// 站点地图规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SitemapRule {
+    /// 页面路径（相对于站点根目录）的匹配模式，`*` 匹配任意字符，如 `posts/*`
+    pub pattern: String,
+    pub changefreq: Option<String>,
+    pub priority: Option<f32>,
+}
+
+/// robots.txt 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RobotsConfig {
+    pub enable: bool,
+    pub user_agent: String,
+    pub allow: Vec<String>,
+    pub disallow: Vec<String>,
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            user_agent: "*".to_string(),
+            allow: Vec::new(),
+            disallow: Vec::new(),
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -139,6 +447,10 @@ impl Default for Config {
             time_format: None,
             per_page: None,
             pagination_dir: None,
+            index_generator: None,
+            category_generator: None,
+            tag_generator: None,
+            archive_generator: None,
             theme: Some("default".to_string()),
             theme_config: None,
             deploy: None,
@@ -147,6 +459,11 @@ impl Default for Config {
             search: None,
             plugins: None,
             comments: None,
+            assets: None,
+            html: None,
+            images: None,
+            sitemap: None,
+            robots: None,
         }
     }
 }
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 图片的一个尺寸或格式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageVariant {
+    /// 相对于站点根目录的路径，如 `images/photo-480w.webp`
+    pub path: String,
+    pub width: u32,
+    /// 文件格式（扩展名）
+    pub format: String,
+}
+
+/// 处理后的图片
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessedImage {
+    /// 原图相对于站点根目录的路径
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// 生成的尺寸和格式，按宽度从小到大排列
+    pub variants: Vec<ImageVariant>,
+}
+
+impl ProcessedImage {
+    /// 原图的格式（扩展名）
+    pub fn format(&self) -> String {
+        normalize_format(extension(&self.path))
+    }
+    
+    /// 指定格式的 `srcset`，原格式的列表包含原图
+    pub fn srcset(&self, format: &str, url_for: impl Fn(&str) -> String) -> String {
+        let mut candidates: Vec<(u32, &str)> = self.variants.iter()
+            .filter(|variant| variant.format == format)
+            .map(|variant| (variant.width, variant.path.as_str()))
+            .collect();
+        if format == self.format() {
+            candidates.push((self.width, self.path.as_str()));
+        }
+        candidates.sort_by_key(|(width, _)| *width);
+        candidates.dedup_by_key(|(width, _)| *width);
+        
+        candidates.iter()
+            .map(|(width, path)| format!("{} {}w", url_for(path), width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+    
+    /// 原格式中宽度不小于 `width` 的最小图片，没有时使用原图
+    pub fn closest(&self, width: u32) -> &str {
+        let format = self.format();
+        self.variants.iter()
+            .filter(|variant| variant.format == format && variant.width >= width)
+            .min_by_key(|variant| variant.width)
+            .map(|variant| variant.path.as_str())
+            .unwrap_or(&self.path)
+    }
+    
+    /// 除原格式外生成的格式，如 `webp`，按配置中的顺序排列
+    ///
+    /// 各尺寸的副本依次排列，同一格式在列表中并不相邻。
+    pub fn extra_formats(&self) -> Vec<&str> {
+        let format = self.format();
+        let mut formats: Vec<&str> = Vec::new();
+        for variant in &self.variants {
+            if variant.format != format && !formats.contains(&variant.format.as_str()) {
+                formats.push(&variant.format);
+            }
+        }
+        formats
+    }
+}
+
+/// 处理过的图片，按原图路径索引，所有副本共享
+#[derive(Debug, Clone, Default)]
+pub struct ImageManifest(Arc<RwLock<HashMap<String, ProcessedImage>>>);
+
+impl ImageManifest {
+    /// 查找原图对应的处理结果
+    pub fn get(&self, path: &str) -> Option<ProcessedImage> {
+        self.0.read().unwrap().get(path.trim_start_matches('/')).cloned()
+    }
+    
+    /// 合并本次处理的图片，已有的记录会被替换
+    pub fn extend(&self, images: impl IntoIterator<Item = ProcessedImage>) {
+        let mut manifest = self.0.write().unwrap();
+        for image in images {
+            manifest.insert(image.path.clone(), image);
+        }
+    }
+}
+
+/// 统一格式名，`jpeg` 记为 `jpg`
+pub fn normalize_format(format: &str) -> String {
+    match format.to_ascii_lowercase().as_str() {
+        "jpeg" => "jpg".to_string(),
+        format => format.to_string(),
+    }
+}
+
+fn extension(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path).rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_extra_formats() {
+        let variant = |width: u32, format: &str| ImageVariant {
+            path: format!("images/photo-{}w.{}", width, format),
+            width,
+            format: format.to_string(),
+        };
+        
+        // formats: [webp, png] 时每个尺寸依次生成 jpg、webp、png
+        let image = ProcessedImage {
+            path: "images/photo.jpg".to_string(),
+            width: 1000,
+            height: 500,
+            variants: vec![
+                variant(480, "jpg"), variant(480, "webp"), variant(480, "png"),
+                variant(1000, "webp"), variant(1000, "png"),
+            ],
+        };
+        assert_eq!(image.extra_formats(), vec!["webp", "png"]);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod config;
+pub mod image;
 pub mod types;
 
 pub use config::Config;
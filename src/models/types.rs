@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// 博客文章的基本结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Post {
     /// 文章标题
     pub title: String,
@@ -14,7 +14,7 @@ pub struct Post {
     pub updated: Option<DateTime<Utc>>,
     /// 是否允许评论
     pub comments: bool,
-    /// 使用的布局
+    /// 使用的布局，`false` 表示不使用模板
     pub layout: String,
     /// 文章内容（原始Markdown）
     pub content: String,
@@ -38,6 +38,16 @@ pub struct Post {
     pub front_matter: HashMap<String, serde_yaml::Value>,
 }
 
+impl Post {
+    /// 不使用模板时的布局名（front matter 中的 `layout: false`）
+    pub const NO_LAYOUT: &'static str = "false";
+    
+    /// 是否使用模板渲染，`layout: false` 的文章直接输出渲染后的内容
+    pub fn uses_layout(&self) -> bool {
+        self.layout != Self::NO_LAYOUT
+    }
+}
+
 /// 页面结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Page {
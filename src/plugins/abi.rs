@@ -0,0 +1,659 @@
+//! 动态插件的二进制接口
+//!
+//! 插件库导出两个符号：
+//!
+//! - `rust_hexo_plugin_abi_version`：返回插件构建时的 [`ABI_VERSION`]，是一个只返回 C 字符串的
+//!   `extern "C"` 函数，宿主在使用插件的任何其他部分之前先调用它并用 semver 检查兼容性；
+//! - `rust_hexo_plugin_vtable`：`#[repr(C)]` 的 [`PluginVTable`]，全部由 `extern "C"` 函数组成。
+//!
+//! 跨越动态库边界的只有不透明的实例指针、字节缓冲区和状态码。字符串以 UTF-8 字节传递，上下文、
+//! 钩子、模板函数的参数等结构化数据序列化为 JSON。插件实例和插件返回的缓冲区都交回插件自己的库
+//! 释放，插件中的 panic 在边界内捕获并转为错误，因此插件不需要与宿主使用同一版本的编译器构建。
+//!
+//! HTML 改写规则在插件一侧执行：宿主把整个页面和页面路径交给插件，插件按自己的
+//! [`Plugin::html_rewriters`] 改写后返回。
+//!
+//! 插件作者不需要手写这些符号，使用 [`declare_plugin!`](crate::declare_plugin) 即可：
+//!
+//! ```ignore
+//! rust_hexo::declare_plugin!(SearchPlugin, SearchPlugin::new);
+//! ```
+
+use std::collections::HashMap;
+use std::ffi::{c_void, CStr};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use libloading::Library;
+use semver::{Version, VersionReq};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use super::{ContentType, Plugin, PluginContext, PluginError, PluginHook, ResourceLocation};
+use crate::core::postprocess::{HtmlPostProcessor, HtmlRewriter};
+use crate::models::config::HtmlConfig;
+use crate::theme::engine::TemplateFunction;
+
+/// 插件 ABI 版本
+///
+/// 函数表或数据格式发生不兼容变化时提升主版本号，只在函数表末尾增加函数时提升次版本号。
+/// 宿主接受主版本号相同且不高于自身的插件。
+pub const ABI_VERSION: &CStr = c"2.0.0";
+
+/// 导出 ABI 版本的函数名
+pub const ABI_VERSION_SYMBOL: &[u8] = b"rust_hexo_plugin_abi_version\0";
+
+/// 导出函数表的静态变量名
+pub const VTABLE_SYMBOL: &[u8] = b"rust_hexo_plugin_vtable\0";
+
+/// 调用成功，输出缓冲区中是结果
+pub const STATUS_OK: i32 = 0;
+/// 调用失败，输出缓冲区中是 UTF-8 编码的错误信息
+pub const STATUS_ERROR: i32 = 1;
+/// 插件发生 panic，输出缓冲区为空
+pub const STATUS_PANIC: i32 = 2;
+
+/// 调用方借出的 UTF-8 字节，只在一次调用期间有效
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FfiStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl FfiStr {
+    pub fn new(text: &str) -> Self {
+        Self { ptr: text.as_ptr(), len: text.len() }
+    }
+    
+    /// 读取借出的字符串
+    ///
+    /// # Safety
+    ///
+    /// `ptr` 必须在调用期间指向 `len` 个可读的字节。
+    pub unsafe fn as_str<'a>(self) -> Result<&'a str> {
+        if self.ptr.is_null() {
+            return Ok("");
+        }
+        std::str::from_utf8(std::slice::from_raw_parts(self.ptr, self.len))
+            .map_err(|e| anyhow!("参数不是有效的 UTF-8: {}", e))
+    }
+}
+
+/// 插件分配的缓冲区，宿主读取后交回插件的 `free_buffer` 释放
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiBuffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl FfiBuffer {
+    /// 空缓冲区，作为输出参数的初始值
+    pub const fn empty() -> Self {
+        Self { ptr: std::ptr::null_mut(), len: 0, capacity: 0 }
+    }
+    
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let mut bytes = std::mem::ManuallyDrop::new(bytes);
+        Self { ptr: bytes.as_mut_ptr(), len: bytes.len(), capacity: bytes.capacity() }
+    }
+}
+
+/// 插件的基本信息，由 `info` 以 JSON 返回
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+}
+
+/// 插件函数表，由 [`declare_plugin!`](crate::declare_plugin) 生成并以 `rust_hexo_plugin_vtable` 导出
+///
+/// 除 `create`、`destroy` 和 `free_buffer` 外，函数的最后一个参数都是输出缓冲区，返回值是
+/// [`STATUS_OK`] 等状态码。
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PluginVTable {
+    /// 创建插件实例，构造时 panic 则返回空指针
+    pub create: unsafe extern "C" fn() -> *mut c_void,
+    /// 释放由 `create` 创建的实例
+    pub destroy: unsafe extern "C" fn(*mut c_void),
+    /// 释放插件返回的缓冲区
+    pub free_buffer: unsafe extern "C" fn(FfiBuffer),
+    /// 插件信息，输出 JSON 格式的 [`PluginInfo`]
+    pub info: unsafe extern "C" fn(*const c_void, *mut FfiBuffer) -> i32,
+    /// 初始化插件，参数是 JSON 格式的 [`PluginContext`]
+    pub init: unsafe extern "C" fn(*mut c_void, FfiStr, *mut FfiBuffer) -> i32,
+    /// 执行钩子，参数是 JSON 格式的 [`PluginHook`]
+    pub execute_hook: unsafe extern "C" fn(*const c_void, FfiStr, *mut FfiBuffer) -> i32,
+    /// 处理内容，参数是内容和 JSON 格式的 [`ContentType`]，输出处理后的内容
+    pub process_content: unsafe extern "C" fn(*const c_void, FfiStr, FfiStr, *mut FfiBuffer) -> i32,
+    /// 插件资源，输出 JSON 格式的资源和位置列表
+    pub resources: unsafe extern "C" fn(*const c_void, *mut FfiBuffer) -> i32,
+    /// 模板函数，输出 JSON 格式的函数名列表
+    pub template_functions: unsafe extern "C" fn(*const c_void, *mut FfiBuffer) -> i32,
+    /// 调用模板函数，参数是函数名和 JSON 格式的参数表，输出 JSON 格式的返回值
+    pub call_function: unsafe extern "C" fn(*const c_void, FfiStr, FfiStr, *mut FfiBuffer) -> i32,
+    /// HTML 改写规则，输出 JSON 格式的选择器列表
+    pub html_rewriters: unsafe extern "C" fn(*const c_void, *mut FfiBuffer) -> i32,
+    /// 按插件的改写规则处理整个页面，参数是页面内容和页面相对输出目录的路径
+    pub rewrite_html: unsafe extern "C" fn(*const c_void, FfiStr, FfiStr, *mut FfiBuffer) -> i32,
+    /// 清理资源
+    pub cleanup: unsafe extern "C" fn(*const c_void, *mut FfiBuffer) -> i32,
+}
+
+/// 检查插件的 ABI 版本是否能被当前宿主使用
+pub fn check_abi_version(version: &str) -> Result<(), PluginError> {
+    let host = Version::parse(ABI_VERSION.to_str().unwrap_or_default()).expect("ABI_VERSION 必须是合法的 semver 版本");
+    let version = Version::parse(version).map_err(|e| PluginError::VersionError {
+        message: format!("插件的 ABI 版本 {} 无法解析: {}", version, e),
+    })?;
+    
+    let requirement = VersionReq::parse(&format!(">={}.0.0, <={}", host.major, host)).expect("ABI 版本要求格式错误");
+    if !requirement.matches(&version) {
+        return Err(PluginError::VersionError {
+            message: format!("插件的 ABI 版本 {} 与宿主的 {} 不兼容，请基于当前版本的 rust-hexo 重新编译插件", version, host),
+        });
+    }
+    Ok(())
+}
+
+/// 插件一侧的实例，缓存插件提供的模板函数和改写规则
+struct GuestPlugin {
+    plugin: Box<dyn Plugin>,
+    functions: HashMap<String, TemplateFunction>,
+    selectors: Vec<String>,
+    rewriter: Option<HtmlPostProcessor>,
+}
+
+impl GuestPlugin {
+    fn new(plugin: Box<dyn Plugin>) -> Self {
+        let mut guest = Self {
+            plugin,
+            functions: HashMap::new(),
+            selectors: Vec::new(),
+            rewriter: None,
+        };
+        guest.refresh();
+        guest
+    }
+    
+    /// 插件提供的模板函数和改写规则可能取决于初始化时读取的配置
+    fn refresh(&mut self) {
+        self.functions = self.plugin.get_template_functions();
+        let rewriters: Vec<HtmlRewriter> = self.plugin.html_rewriters();
+        self.selectors = rewriters.iter().map(|rewriter| rewriter.selector.clone()).collect();
+        self.rewriter = (!rewriters.is_empty()).then(|| HtmlPostProcessor::new(HtmlConfig::default(), rewriters));
+    }
+}
+
+impl PluginVTable {
+    /// 由 [`declare_plugin!`](crate::declare_plugin) 调用，除 `create` 外的函数都由 rust-hexo 提供
+    #[doc(hidden)]
+    #[allow(dead_code)] // 只在插件库中展开，可执行文件本身不会调用
+    pub const fn new(create: unsafe extern "C" fn() -> *mut c_void) -> Self {
+        Self {
+            create,
+            destroy: guest::destroy,
+            free_buffer: guest::free_buffer,
+            info: guest::info,
+            init: guest::init,
+            execute_hook: guest::execute_hook,
+            process_content: guest::process_content,
+            resources: guest::resources,
+            template_functions: guest::template_functions,
+            call_function: guest::call_function,
+            html_rewriters: guest::html_rewriters,
+            rewrite_html: guest::rewrite_html,
+            cleanup: guest::cleanup,
+        }
+    }
+}
+
+/// 由 [`declare_plugin!`](crate::declare_plugin) 调用，创建插件实例并转为不透明指针
+#[doc(hidden)]
+#[allow(dead_code)] // 只在插件库中展开，可执行文件本身不会调用
+pub fn create_plugin(constructor: fn() -> Box<dyn Plugin>) -> *mut c_void {
+    match std::panic::catch_unwind(|| GuestPlugin::new(constructor())) {
+        Ok(guest) => Box::into_raw(Box::new(guest)) as *mut c_void,
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// 函数表中由插件库执行的函数，全部在边界内捕获 panic
+#[allow(dead_code)] // 只在插件库中展开，可执行文件本身不会调用
+mod guest {
+    use super::*;
+    
+    /// 执行调用，把结果或错误信息写入输出缓冲区
+    fn call(out: *mut FfiBuffer, call: impl FnOnce() -> Result<Vec<u8>>) -> i32 {
+        let (status, bytes) = match std::panic::catch_unwind(AssertUnwindSafe(call)) {
+            Ok(Ok(bytes)) => (STATUS_OK, bytes),
+            Ok(Err(e)) => (STATUS_ERROR, format!("{:#}", e).into_bytes()),
+            Err(_) => (STATUS_PANIC, Vec::new()),
+        };
+        if !out.is_null() {
+            unsafe { out.write(FfiBuffer::from_vec(bytes)) };
+        }
+        status
+    }
+    
+    unsafe fn guest<'a>(instance: *const c_void) -> &'a GuestPlugin {
+        &*(instance as *const GuestPlugin)
+    }
+    
+    fn strings(values: &[String]) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(values)?)
+    }
+    
+    pub(super) unsafe extern "C" fn destroy(instance: *mut c_void) {
+        if !instance.is_null() {
+            let _ = std::panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(instance as *mut GuestPlugin))));
+        }
+    }
+    
+    pub(super) unsafe extern "C" fn free_buffer(buffer: FfiBuffer) {
+        if !buffer.ptr.is_null() {
+            drop(Vec::from_raw_parts(buffer.ptr, buffer.len, buffer.capacity));
+        }
+    }
+    
+    pub(super) unsafe extern "C" fn info(instance: *const c_void, out: *mut FfiBuffer) -> i32 {
+        let plugin = &guest(instance).plugin;
+        call(out, || Ok(serde_json::to_vec(&PluginInfo {
+            name: plugin.name().to_string(),
+            version: plugin.version().to_string(),
+            description: plugin.description().to_string(),
+        })?))
+    }
+    
+    pub(super) unsafe extern "C" fn init(instance: *mut c_void, context: FfiStr, out: *mut FfiBuffer) -> i32 {
+        let guest = &mut *(instance as *mut GuestPlugin);
+        call(out, || {
+            let context: PluginContext = serde_json::from_str(context.as_str()?)?;
+            guest.plugin.init(&context)?;
+            guest.refresh();
+            Ok(Vec::new())
+        })
+    }
+    
+    pub(super) unsafe extern "C" fn execute_hook(instance: *const c_void, hook: FfiStr, out: *mut FfiBuffer) -> i32 {
+        let plugin = &guest(instance).plugin;
+        call(out, || {
+            let hook: PluginHook = serde_json::from_str(hook.as_str()?)?;
+            plugin.execute_hook(&hook)?;
+            Ok(Vec::new())
+        })
+    }
+    
+    pub(super) unsafe extern "C" fn process_content(instance: *const c_void, content: FfiStr, content_type: FfiStr, out: *mut FfiBuffer) -> i32 {
+        let plugin = &guest(instance).plugin;
+        call(out, || {
+            let content_type: ContentType = serde_json::from_str(content_type.as_str()?)?;
+            Ok(plugin.process_content(content.as_str()?, content_type)?.into_bytes())
+        })
+    }
+    
+    pub(super) unsafe extern "C" fn resources(instance: *const c_void, out: *mut FfiBuffer) -> i32 {
+        let plugin = &guest(instance).plugin;
+        call(out, || Ok(serde_json::to_vec(&plugin.get_resources())?))
+    }
+    
+    pub(super) unsafe extern "C" fn template_functions(instance: *const c_void, out: *mut FfiBuffer) -> i32 {
+        let guest = guest(instance);
+        call(out, || {
+            let mut names: Vec<String> = guest.functions.keys().cloned().collect();
+            names.sort();
+            strings(&names)
+        })
+    }
+    
+    pub(super) unsafe extern "C" fn call_function(instance: *const c_void, name: FfiStr, args: FfiStr, out: *mut FfiBuffer) -> i32 {
+        let guest = guest(instance);
+        call(out, || {
+            let name = name.as_str()?;
+            let function = guest.functions.get(name).ok_or_else(|| anyhow!("插件没有提供模板函数 {}", name))?;
+            let args: HashMap<String, Value> = serde_json::from_str(args.as_str()?)?;
+            Ok(serde_json::to_vec(&function(&args)?)?)
+        })
+    }
+    
+    pub(super) unsafe extern "C" fn html_rewriters(instance: *const c_void, out: *mut FfiBuffer) -> i32 {
+        let guest = guest(instance);
+        call(out, || strings(&guest.selectors))
+    }
+    
+    pub(super) unsafe extern "C" fn rewrite_html(instance: *const c_void, html: FfiStr, page: FfiStr, out: *mut FfiBuffer) -> i32 {
+        let guest = guest(instance);
+        call(out, || {
+            let html = html.as_str()?;
+            match &guest.rewriter {
+                Some(rewriter) => Ok(rewriter.process(html, page.as_str()?)?.into_bytes()),
+                None => Ok(html.as_bytes().to_vec()),
+            }
+        })
+    }
+    
+    pub(super) unsafe extern "C" fn cleanup(instance: *const c_void, out: *mut FfiBuffer) -> i32 {
+        let plugin = &guest(instance).plugin;
+        call(out, || {
+            plugin.cleanup()?;
+            Ok(Vec::new())
+        })
+    }
+}
+
+/// 宿主持有的插件实例，释放时先交回插件销毁实例，再卸载动态库
+struct Instance {
+    ptr: *mut c_void,
+    vtable: PluginVTable,
+    _library: Option<Library>,
+}
+
+// 插件实现了 `Send + Sync`，实例指针只通过函数表访问
+unsafe impl Send for Instance {}
+unsafe impl Sync for Instance {}
+
+impl Instance {
+    /// 调用函数表中的函数，读取输出缓冲区后交回插件释放
+    fn call(&self, call: impl FnOnce(*mut FfiBuffer) -> i32) -> Result<String> {
+        let mut out = FfiBuffer::empty();
+        let status = call(&mut out);
+        let bytes = if out.ptr.is_null() {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(out.ptr, out.len) }.to_vec()
+        };
+        unsafe { (self.vtable.free_buffer)(out) };
+        
+        let text = String::from_utf8(bytes).map_err(|e| anyhow!("插件返回的内容不是有效的 UTF-8: {}", e))?;
+        match status {
+            STATUS_OK => Ok(text),
+            STATUS_ERROR => Err(anyhow!(text)),
+            STATUS_PANIC => Err(anyhow!("插件发生 panic")),
+            status => Err(anyhow!("插件返回了未知的状态码 {}", status)),
+        }
+    }
+    
+    fn call_json<T: DeserializeOwned>(&self, call: impl FnOnce(*mut FfiBuffer) -> i32) -> Result<T> {
+        let text = self.call(call)?;
+        serde_json::from_str(&text).map_err(|e| anyhow!("插件返回的 JSON 格式错误: {}", e))
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        unsafe { (self.vtable.destroy)(self.ptr) }
+    }
+}
+
+/// 宿主一侧的动态插件，通过函数表调用插件库中的实例
+pub(crate) struct DylibPlugin {
+    /// 模板函数也持有实例，保证调用期间插件库不会被卸载
+    instance: Arc<Instance>,
+    info: PluginInfo,
+    /// 初始化后插件提供的改写规则的选择器
+    selectors: Vec<String>,
+}
+
+impl DylibPlugin {
+    /// 通过函数表创建插件实例，插件释放时才卸载动态库
+    ///
+    /// # Safety
+    ///
+    /// `vtable` 必须来自 `library` 导出的 `rust_hexo_plugin_vtable`，且 ABI 版本已通过
+    /// [`check_abi_version`]。
+    pub(crate) unsafe fn new(vtable: PluginVTable, library: Option<Library>) -> Result<Self, PluginError> {
+        let ptr = (vtable.create)();
+        if ptr.is_null() {
+            return Err(PluginError::LoadError {
+                message: "插件构造函数发生 panic".to_string(),
+            });
+        }
+        let instance = Arc::new(Instance { ptr, vtable, _library: library });
+        
+        let info: PluginInfo = instance.call_json(|out| (vtable.info)(ptr, out)).map_err(|e| PluginError::LoadError {
+            message: format!("无法读取插件信息: {}", e),
+        })?;
+        let selectors = instance.call_json(|out| (vtable.html_rewriters)(ptr, out)).unwrap_or_default();
+        Ok(Self { instance, info, selectors })
+    }
+}
+
+impl Plugin for DylibPlugin {
+    fn name(&self) -> &str {
+        &self.info.name
+    }
+    
+    fn version(&self) -> &str {
+        &self.info.version
+    }
+    
+    fn description(&self) -> &str {
+        &self.info.description
+    }
+    
+    fn init(&mut self, context: &PluginContext) -> Result<()> {
+        let context = serde_json::to_string(context)?;
+        let instance = Arc::get_mut(&mut self.instance).ok_or_else(|| anyhow!("插件正在使用中，无法初始化"))?;
+        let (ptr, vtable) = (instance.ptr, instance.vtable);
+        instance.call(|out| unsafe { (vtable.init)(ptr, FfiStr::new(&context), out) })?;
+        
+        self.selectors = self.instance.call_json(|out| unsafe { (vtable.html_rewriters)(ptr, out) })?;
+        Ok(())
+    }
+    
+    fn execute_hook(&self, hook: &PluginHook) -> Result<()> {
+        let hook = serde_json::to_string(hook)?;
+        let Instance { ptr, vtable, .. } = *self.instance;
+        self.instance.call(|out| unsafe { (vtable.execute_hook)(ptr, FfiStr::new(&hook), out) })?;
+        Ok(())
+    }
+    
+    fn process_content(&self, content: &str, content_type: ContentType) -> Result<String> {
+        let content_type = serde_json::to_string(&content_type)?;
+        let Instance { ptr, vtable, .. } = *self.instance;
+        self.instance.call(|out| unsafe { (vtable.process_content)(ptr, FfiStr::new(content), FfiStr::new(&content_type), out) })
+    }
+    
+    fn get_resources(&self) -> Vec<(String, ResourceLocation)> {
+        let Instance { ptr, vtable, .. } = *self.instance;
+        self.instance.call_json(|out| unsafe { (vtable.resources)(ptr, out) }).unwrap_or_else(|e| {
+            warn!("无法获取插件 {} 的资源: {}", self.info.name, e);
+            Vec::new()
+        })
+    }
+    
+    fn get_template_functions(&self) -> HashMap<String, TemplateFunction> {
+        let Instance { ptr, vtable, .. } = *self.instance;
+        let names: Vec<String> = self.instance.call_json(|out| unsafe { (vtable.template_functions)(ptr, out) }).unwrap_or_else(|e| {
+            warn!("无法获取插件 {} 的模板函数: {}", self.info.name, e);
+            Vec::new()
+        });
+        
+        names.into_iter()
+            .map(|name| {
+                let instance = self.instance.clone();
+                let function_name = name.clone();
+                let function: TemplateFunction = Arc::new(move |args: &HashMap<String, Value>| {
+                    let args = serde_json::to_string(args)?;
+                    let Instance { ptr, vtable, .. } = *instance;
+                    instance.call_json(|out| unsafe { (vtable.call_function)(ptr, FfiStr::new(&function_name), FfiStr::new(&args), out) })
+                });
+                (name, function)
+            })
+            .collect()
+    }
+    
+    fn has_html_rewriters(&self) -> bool {
+        !self.selectors.is_empty()
+    }
+    
+    fn rewrite_html(&self, html: &str, page: &str) -> Result<String> {
+        let Instance { ptr, vtable, .. } = *self.instance;
+        self.instance.call(|out| unsafe { (vtable.rewrite_html)(ptr, FfiStr::new(html), FfiStr::new(page), out) })
+    }
+    
+    fn cleanup(&self) -> Result<()> {
+        let Instance { ptr, vtable, .. } = *self.instance;
+        self.instance.call(|out| unsafe { (vtable.cleanup)(ptr, out) })?;
+        Ok(())
+    }
+}
+
+/// 声明动态插件，导出宿主加载插件所需的符号
+///
+/// 第一个参数是插件类型，第二个参数是返回该类型实例的构造函数：
+///
+/// ```ignore
+/// rust_hexo::declare_plugin!(SearchPlugin, SearchPlugin::new);
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($plugin:ty, $constructor:path) => {
+        #[no_mangle]
+        pub extern "C" fn rust_hexo_plugin_abi_version() -> *const ::std::os::raw::c_char {
+            $crate::plugins::abi::ABI_VERSION.as_ptr()
+        }
+        
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static rust_hexo_plugin_vtable: $crate::plugins::abi::PluginVTable = $crate::plugins::abi::PluginVTable::new({
+            extern "C" fn create() -> *mut ::std::ffi::c_void {
+                $crate::plugins::abi::create_plugin(|| {
+                    let plugin: $plugin = $constructor();
+                    ::std::boxed::Box::new(plugin)
+                })
+            }
+            create
+        });
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lol_html::html_content::ContentType as HtmlContentType;
+    
+    /// 测试用插件，经过函数表调用，与动态库中的插件走同样的路径
+    struct EchoPlugin {
+        prefix: String,
+    }
+    
+    impl Plugin for EchoPlugin {
+        fn name(&self) -> &str {
+            "echo"
+        }
+        
+        fn version(&self) -> &str {
+            "0.1.0"
+        }
+        
+        fn description(&self) -> &str {
+            "Echo plugin"
+        }
+        
+        fn init(&mut self, context: &PluginContext) -> Result<()> {
+            self.prefix = context.config.title.clone();
+            Ok(())
+        }
+        
+        fn execute_hook(&self, hook: &PluginHook) -> Result<()> {
+            match hook {
+                PluginHook::Clean => Err(anyhow!("不能清理")),
+                PluginHook::NewPost => panic!("boom"),
+                _ => Ok(()),
+            }
+        }
+        
+        fn process_content(&self, content: &str, content_type: ContentType) -> Result<String> {
+            Ok(format!("{}:{:?}:{}", self.prefix, content_type, content))
+        }
+        
+        fn get_resources(&self) -> Vec<(String, ResourceLocation)> {
+            vec![("<script></script>".to_string(), ResourceLocation::Footer)]
+        }
+        
+        fn get_template_functions(&self) -> HashMap<String, TemplateFunction> {
+            let prefix = self.prefix.clone();
+            let function: TemplateFunction = Arc::new(move |args: &HashMap<String, Value>| {
+                Ok(Value::String(format!("{}{}", prefix, args.get("name").and_then(Value::as_str).unwrap_or_default())))
+            });
+            HashMap::from([("greet".to_string(), function)])
+        }
+        
+        fn html_rewriters(&self) -> Vec<HtmlRewriter> {
+            if self.prefix.is_empty() {
+                return Vec::new();
+            }
+            vec![HtmlRewriter::new("body", |element, page| {
+                element.append(page, HtmlContentType::Text);
+                Ok(())
+            })]
+        }
+        
+        fn cleanup(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+    
+    extern "C" fn create() -> *mut c_void {
+        create_plugin(|| Box::new(EchoPlugin { prefix: String::new() }))
+    }
+    
+    static VTABLE: PluginVTable = PluginVTable::new(create);
+    
+    #[test]
+    fn test_check_abi_version() {
+        assert!(check_abi_version("2.0.0").is_ok());
+        assert!(matches!(check_abi_version("1.0.0"), Err(PluginError::VersionError { .. })));
+        assert!(matches!(check_abi_version("3.0.0"), Err(PluginError::VersionError { .. })));
+        assert!(matches!(check_abi_version("2.99.0"), Err(PluginError::VersionError { .. })));
+        assert!(matches!(check_abi_version("not a version"), Err(PluginError::VersionError { .. })));
+    }
+    
+    #[test]
+    fn test_dylib_plugin() {
+        let mut plugin = unsafe { DylibPlugin::new(VTABLE, None) }.unwrap();
+        assert_eq!(plugin.name(), "echo");
+        assert!(!plugin.has_html_rewriters());
+        
+        let mut context = PluginContext::default();
+        context.config.title = "site".to_string();
+        plugin.init(&context).unwrap();
+        
+        assert_eq!(plugin.process_content("你好", ContentType::Markdown).unwrap(), "site:Markdown:你好");
+        assert_eq!(plugin.get_resources(), vec![("<script></script>".to_string(), ResourceLocation::Footer)]);
+        
+        let functions = plugin.get_template_functions();
+        let args = HashMap::from([("name".to_string(), Value::String("!".to_string()))]);
+        assert_eq!(functions["greet"](&args).unwrap(), Value::String("site!".to_string()));
+        
+        // 改写规则在初始化后才出现，并在插件一侧执行
+        assert!(plugin.has_html_rewriters());
+        assert_eq!(plugin.rewrite_html("<body></body>", "a/index.html").unwrap(), "<body>a/index.html</body>");
+        
+        // 错误和 panic 都不会越过边界
+        assert!(plugin.execute_hook(&PluginHook::Init).is_ok());
+        assert_eq!(plugin.execute_hook(&PluginHook::Clean).unwrap_err().to_string(), "不能清理");
+        assert_eq!(plugin.execute_hook(&PluginHook::NewPost).unwrap_err().to_string(), "插件发生 panic");
+        
+        // 模板函数持有实例，插件释放后仍可调用
+        drop(plugin);
+        assert!(functions["greet"](&args).is_ok());
+    }
+    
+    #[test]
+    fn test_create_panic() {
+        extern "C" fn create() -> *mut c_void {
+            create_plugin(|| panic!("boom"))
+        }
+        
+        let vtable = PluginVTable::new(create);
+        assert!(matches!(unsafe { DylibPlugin::new(vtable, None) }, Err(PluginError::LoadError { .. })));
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::error::Error;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// 插件错误类型
@@ -69,7 +70,7 @@ pub enum PluginError {
 }
 
 /// 插件钩子类型
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PluginHook {
     /// 初始化
     Init,
@@ -0,0 +1,254 @@
+//! 插件清单与执行顺序
+//!
+//! 插件可以放在 `plugins/<名称>/` 目录中，与动态库一起提供 `plugin.toml`：
+//!
+//! ```toml
+//! name = "math"
+//! version = "0.1.0"
+//! rust_hexo = ">=0.1.0"       # 要求的 rust-hexo 版本
+//! depends_on = ["syntax-highlight"]
+//! after = ["word-count"]
+//! before = []
+//! priority = 10
+//! ```
+//!
+//! 所有钩子和内容处理都按 [`resolve_order`] 得到的顺序执行。
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::Path;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use super::PluginError;
+
+/// 清单文件名
+pub const MANIFEST_FILE: &str = "plugin.toml";
+
+/// 默认优先级，与 Hexo 过滤器相同
+pub const DEFAULT_PRIORITY: i32 = 10;
+
+/// 插件清单
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PluginManifest {
+    /// 插件名称，必须与插件自身报告的名称一致
+    pub name: String,
+    /// 插件版本
+    pub version: String,
+    /// 要求的 rust-hexo 版本，semver 版本要求格式
+    pub rust_hexo: Option<String>,
+    /// 动态库文件名，相对于清单所在目录，不填时使用目录中唯一的动态库
+    pub library: Option<String>,
+    /// 依赖的插件，先于本插件执行；依赖缺失或初始化失败时本插件不会被启用
+    pub depends_on: Vec<String>,
+    /// 本插件应先于这些插件执行，插件不存在时忽略
+    pub before: Vec<String>,
+    /// 本插件应晚于这些插件执行，插件不存在时忽略
+    pub after: Vec<String>,
+    /// 优先级，没有先后约束的插件按优先级从小到大执行，相同时按名称排序
+    pub priority: i32,
+}
+
+impl Default for PluginManifest {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            version: String::new(),
+            rust_hexo: None,
+            library: None,
+            depends_on: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            priority: DEFAULT_PRIORITY,
+        }
+    }
+}
+
+impl PluginManifest {
+    /// 为没有清单的插件生成默认清单
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            ..Self::default()
+        }
+    }
+    
+    /// 读取清单文件
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        let content = std::fs::read_to_string(path).map_err(|e| PluginError::LoadError {
+            message: format!("无法读取插件清单 {}: {}", path.display(), e),
+        })?;
+        let manifest: Self = toml::from_str(&content).map_err(|e| PluginError::ConfigError {
+            message: format!("插件清单 {} 格式错误: {}", path.display(), e),
+        })?;
+        
+        if manifest.name.is_empty() {
+            return Err(PluginError::ConfigError {
+                message: format!("插件清单 {} 缺少 name", path.display()),
+            });
+        }
+        Ok(manifest)
+    }
+    
+    /// 检查当前 rust-hexo 版本是否满足清单的要求
+    pub fn check_host_version(&self, host_version: &str) -> Result<(), PluginError> {
+        let Some(requirement) = &self.rust_hexo else {
+            return Ok(());
+        };
+        
+        let parsed = VersionReq::parse(requirement).map_err(|e| PluginError::ConfigError {
+            message: format!("插件 {} 的 rust_hexo 版本要求 {} 无法解析: {}", self.name, requirement, e),
+        })?;
+        let host = Version::parse(host_version).map_err(|e| PluginError::VersionError {
+            message: format!("rust-hexo 版本 {} 无法解析: {}", host_version, e),
+        })?;
+        
+        if !parsed.matches(&host) {
+            return Err(PluginError::VersionError {
+                message: format!("插件 {} 要求 rust-hexo {}，当前版本为 {}", self.name, requirement, host),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 按依赖和先后约束计算插件的执行顺序，返回清单的下标
+///
+/// 约束之间存在环时返回 [`PluginError::DependencyError`]，并列出环中的插件。
+pub fn resolve_order(manifests: &[PluginManifest]) -> Result<Vec<usize>, PluginError> {
+    let mut graph: DiGraph<usize, ()> = DiGraph::new();
+    let nodes: Vec<NodeIndex> = (0..manifests.len()).map(|index| graph.add_node(index)).collect();
+    let by_name: HashMap<&str, NodeIndex> = manifests.iter()
+        .zip(&nodes)
+        .map(|(manifest, node)| (manifest.name.as_str(), *node))
+        .collect();
+    
+    // 边由先执行的插件指向后执行的插件
+    for (manifest, &node) in manifests.iter().zip(&nodes) {
+        for name in manifest.depends_on.iter().chain(&manifest.after) {
+            if let Some(&first) = by_name.get(name.as_str()) {
+                graph.update_edge(first, node, ());
+            }
+        }
+        for name in &manifest.before {
+            if let Some(&then) = by_name.get(name.as_str()) {
+                graph.update_edge(node, then, ());
+            }
+        }
+    }
+    
+    let mut cycles: Vec<Vec<&str>> = tarjan_scc(&graph).into_iter()
+        .filter(|component| component.len() > 1 || graph.contains_edge(component[0], component[0]))
+        .map(|component| {
+            let mut names: Vec<&str> = component.iter().map(|node| manifests[graph[*node]].name.as_str()).collect();
+            names.sort_unstable();
+            names
+        })
+        .collect();
+    if !cycles.is_empty() {
+        cycles.sort();
+        return Err(PluginError::DependencyError {
+            message: format!(
+                "插件之间存在循环依赖: {}",
+                cycles.iter().map(|names| names.join(" <-> ")).collect::<Vec<_>>().join("; ")
+            ),
+        });
+    }
+    
+    // 拓扑排序，可以执行的插件中优先级小的先执行，相同时按名称排序，保证每次顺序一致
+    let key = |node: NodeIndex| {
+        let manifest = &manifests[graph[node]];
+        Reverse((manifest.priority, manifest.name.as_str(), node.index()))
+    };
+    let mut incoming: Vec<usize> = nodes.iter()
+        .map(|node| graph.neighbors_directed(*node, petgraph::Direction::Incoming).count())
+        .collect();
+    let mut ready: BinaryHeap<_> = nodes.iter()
+        .filter(|node| incoming[node.index()] == 0)
+        .map(|node| key(*node))
+        .collect();
+    
+    let mut order = Vec::with_capacity(manifests.len());
+    while let Some(Reverse((_, _, index))) = ready.pop() {
+        let node = NodeIndex::new(index);
+        order.push(graph[node]);
+        for next in graph.neighbors_directed(node, petgraph::Direction::Outgoing) {
+            incoming[next.index()] -= 1;
+            if incoming[next.index()] == 0 {
+                ready.push(key(next));
+            }
+        }
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn manifest(name: &str, priority: i32, depends_on: &[&str], before: &[&str], after: &[&str]) -> PluginManifest {
+        PluginManifest {
+            name: name.to_string(),
+            depends_on: depends_on.iter().map(|name| name.to_string()).collect(),
+            before: before.iter().map(|name| name.to_string()).collect(),
+            after: after.iter().map(|name| name.to_string()).collect(),
+            priority,
+            ..PluginManifest::default()
+        }
+    }
+    
+    fn names(manifests: &[PluginManifest]) -> Vec<&str> {
+        resolve_order(manifests).unwrap().into_iter().map(|index| manifests[index].name.as_str()).collect()
+    }
+    
+    #[test]
+    fn test_resolve_order() {
+        let manifests = vec![
+            manifest("math", 10, &[], &[], &["syntax-highlight"]),
+            manifest("word-count", 10, &[], &[], &[]),
+            manifest("syntax-highlight", 20, &[], &[], &[]),
+            manifest("comments", 5, &["search"], &[], &[]),
+            manifest("search", 10, &[], &["word-count"], &["missing"]),
+        ];
+        assert_eq!(names(&manifests), vec!["search", "comments", "word-count", "syntax-highlight", "math"]);
+        
+        // 顺序与清单的先后无关
+        let mut reversed = manifests.clone();
+        reversed.reverse();
+        assert_eq!(names(&reversed), names(&manifests));
+    }
+    
+    #[test]
+    fn test_resolve_order_cycle() {
+        let manifests = vec![
+            manifest("a", 10, &["b"], &[], &[]),
+            manifest("b", 10, &[], &[], &["c"]),
+            manifest("c", 10, &[], &[], &["a"]),
+            manifest("d", 10, &[], &["d"], &[]),
+            manifest("e", 10, &[], &[], &[]),
+        ];
+        match resolve_order(&manifests) {
+            Err(PluginError::DependencyError { message }) => {
+                assert_eq!(message, "插件之间存在循环依赖: a <-> b <-> c; d");
+            }
+            other => panic!("应当报告循环依赖: {:?}", other.map(|_| ())),
+        }
+    }
+    
+    #[test]
+    fn test_manifest() {
+        let manifest: PluginManifest = toml::from_str(r#"
+            name = "math"
+            version = "0.1.0"
+            rust_hexo = ">=0.1, <2"
+            depends_on = ["syntax-highlight"]
+        "#).unwrap();
+        assert_eq!(manifest.priority, DEFAULT_PRIORITY);
+        assert!(manifest.check_host_version("0.3.0").is_ok());
+        assert!(matches!(manifest.check_host_version("2.0.0"), Err(PluginError::VersionError { .. })));
+    }
+}
@@ -6,15 +6,20 @@ use std::fs;
 use anyhow::{anyhow, Result};
 use libloading::{Library, Symbol};
 use tracing::{info, warn, error, debug};
-use serde::Serialize;
-use tera;
+use serde::{Deserialize, Serialize};
+use crate::theme::engine::TemplateFunction;
+use crate::core::postprocess::{HtmlPostProcessor, HtmlRewriter, PageRewriter};
+use crate::models::config::HtmlConfig;
 
 // 重新导出子模块
 mod error;
+pub mod abi;
+pub mod manifest;
 pub use error::*;
+pub use manifest::PluginManifest;
 
 // 定义内容类型枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ContentType {
     Markdown,
     HTML,
@@ -29,14 +34,14 @@ pub enum ContentType {
 pub use error::PluginHook;
 
 // 定义资源位置
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResourceLocation {
     Head,
     Footer,
 }
 
 /// 插件上下文，提供给插件使用的环境信息
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PluginContext {
     /// 基础目录
     pub base_dir: PathBuf,
@@ -108,15 +113,35 @@ pub trait Plugin: Send + Sync {
         Vec::new() // 默认实现返回空列表
     }
     
-    /// 获取插件提供的模板函数
-    fn get_template_functions(&self) -> HashMap<String, Box<dyn Fn(&HashMap<String, serde_json::Value>) -> Result<serde_json::Value, tera::Error> + Send + Sync>> {
+    /// 获取插件提供的模板函数，这些函数可用于任何模板引擎
+    fn get_template_functions(&self) -> HashMap<String, TemplateFunction> {
         HashMap::new() // 默认实现返回空列表
     }
     
+    /// 获取插件提供的 HTML 改写规则，在每个页面写入前按选择器处理匹配的元素
+    fn html_rewriters(&self) -> Vec<HtmlRewriter> {
+        Vec::new() // 默认实现返回空列表
+    }
+    
+    /// 是否提供 HTML 改写规则
+    fn has_html_rewriters(&self) -> bool {
+        !self.html_rewriters().is_empty()
+    }
+    
+    /// 按 `html_rewriters` 的规则改写整个页面，`page` 是页面相对输出目录的路径
+    ///
+    /// 宿主通过它执行插件的改写规则，动态插件的规则在插件自己的库中运行，插件一般不需要实现。
+    fn rewrite_html(&self, html: &str, page: &str) -> Result<String> {
+        HtmlPostProcessor::new(HtmlConfig::default(), self.html_rewriters()).process(html, page)
+    }
+    
     /// 清理资源
     fn cleanup(&self) -> Result<()>;
 }
 
+/// 已加载的插件及其名称，按执行顺序排列
+pub type LoadedPlugins = Vec<(String, Box<dyn Plugin>)>;
+
 /// 插件管理器，负责加载和管理插件
 pub struct PluginManager {
     /// 基础目录
@@ -125,12 +150,10 @@ pub struct PluginManager {
     pub plugins_dir: PathBuf,
     /// 插件上下文
     pub context: Arc<RwLock<PluginContext>>,
-    /// 已加载的插件
-    pub plugins: Arc<RwLock<HashMap<String, Box<dyn Plugin>>>>,
-    /// 已加载的库
-    pub libraries: Arc<RwLock<Vec<Library>>>,
+    /// 已加载的插件，按执行顺序排列
+    pub plugins: Arc<RwLock<LoadedPlugins>>,
     /// 文件监视器
-    pub watcher: Option<Box<dyn std::any::Any + Send>>,
+    pub watcher: Option<Box<dyn std::any::Any + Send + Sync>>,
     /// 是否正在监视
     pub is_watching: Arc<RwLock<bool>>,
     /// 是否已初始化
@@ -144,7 +167,6 @@ impl Clone for PluginManager {
             plugins_dir: self.plugins_dir.clone(),
             context: self.context.clone(),
             plugins: self.plugins.clone(),
-            libraries: self.libraries.clone(),
             watcher: None, // 不克隆监视器
             is_watching: self.is_watching.clone(),
             initialized: self.initialized,
@@ -162,8 +184,7 @@ impl PluginManager {
             base_dir: base_dir.clone(),
             plugins_dir,
             context: Arc::new(RwLock::new(context)),
-            plugins: Arc::new(RwLock::new(HashMap::new())),
-            libraries: Arc::new(RwLock::new(Vec::new())),
+            plugins: Arc::new(RwLock::new(Vec::new())),
             watcher: None,
             is_watching: Arc::new(RwLock::new(false)),
             initialized: false,
@@ -224,174 +245,211 @@ impl PluginManager {
             }
         };
 
-        info!("库文件 {} 已加载，尝试解析 create_plugin 符号...", lib_path.display());
+        info!("库文件 {} 已加载，检查插件 ABI 版本...", lib_path.display());
 
-        // 尝试获取创建插件的函数，使用更谨慎的方式
-        let constructor: Symbol<unsafe fn() -> Box<dyn Plugin>> = match std::panic::catch_unwind(|| {
-            unsafe {
-                lib.get(b"create_plugin")
-            }
-        }) {
-            Ok(symbol_result) => {
-                match symbol_result {
-                    Ok(symbol) => symbol,
-                    Err(e) => {
-                        return Err(anyhow!(PluginError::LoadError {
-                            message: format!("找不到 create_plugin 函数: {}", e),
-                        }));
-                    }
-                }
-            },
+        // 先只调用返回版本字符串的函数，确认兼容后才读取函数表
+        let abi_version: Symbol<unsafe extern "C" fn() -> *const std::os::raw::c_char> = match unsafe { lib.get(abi::ABI_VERSION_SYMBOL) } {
+            Ok(symbol) => symbol,
             Err(_) => {
-                return Err(anyhow!(PluginError::LoadError {
-                    message: format!("解析 create_plugin 符号时发生严重错误: {}", lib_path.display())
+                return Err(anyhow!(PluginError::VersionError {
+                    message: format!("{} 没有导出 ABI 版本，可能基于旧的 create_plugin 接口构建，请使用 declare_plugin! 重新编译", lib_path.display()),
                 }));
             }
         };
+        let version = unsafe { std::ffi::CStr::from_ptr(abi_version()) }.to_string_lossy().into_owned();
+        abi::check_abi_version(&version)?;
 
-        info!("成功解析 create_plugin 符号，正在创建插件实例...");
-
-        // 尝试创建插件实例
-        let plugin_result = std::panic::catch_unwind(|| {
-            unsafe { constructor() }
-        });
-
-        let mut plugin = match plugin_result {
-            Ok(plugin) => plugin,
-            Err(_) => {
+        let vtable: abi::PluginVTable = match unsafe { lib.get::<*const abi::PluginVTable>(abi::VTABLE_SYMBOL) } {
+            Ok(symbol) => unsafe { **symbol },
+            Err(e) => {
                 return Err(anyhow!(PluginError::LoadError {
-                    message: format!("调用 create_plugin 时发生严重错误: {}", lib_path.display())
+                    message: format!("找不到插件函数表 rust_hexo_plugin_vtable: {}", e),
                 }));
             }
         };
 
-        let context = self.context.read().unwrap();
+        info!("插件 ABI 版本 {} 兼容，正在创建插件实例...", version);
 
-        // 初始化插件
-        match plugin.init(&context) {
-            Ok(_) => {
-                // 保存库引用
-                self.libraries.write().unwrap().push(lib);
-                Ok(plugin)
-            },
-            Err(e) => {
-                error!("插件 {} 初始化失败: {}", plugin.name(), e);
-                Err(anyhow!(PluginError::InitError {
-                    plugin_name: plugin.name().to_string(),
-                    message: e.to_string(),
-                }))
+        // 插件实例由库自己创建和释放，库随最后一个引用实例的插件或模板函数一起卸载
+        let plugin: Box<dyn Plugin> = Box::new(unsafe { abi::DylibPlugin::new(vtable, Some(lib))? });
+        Ok(plugin)
+    }
+    
+    /// 初始化插件
+    fn init_plugin(&self, plugin: &mut Box<dyn Plugin>) -> Result<()> {
+        let context = self.context.read().unwrap();
+        plugin.init(&context).map_err(|e| {
+            anyhow!(PluginError::InitError {
+                plugin_name: plugin.name().to_string(),
+                message: e.to_string(),
+            })
+        })
+    }
+    
+    /// 查找插件目录中的插件：直接放置的动态库，以及子目录中的动态库和 `plugin.toml` 清单
+    fn discover_plugins(&self) -> Result<Vec<(PathBuf, Option<PluginManifest>)>> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&self.plugins_dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        entries.sort();
+        
+        let mut found = Vec::new();
+        for path in entries {
+            if path.is_file() {
+                if is_plugin_library(&path) {
+                    found.push((path, None));
+                }
+                continue;
+            }
+            
+            let mut libraries: Vec<PathBuf> = std::fs::read_dir(&path)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| is_plugin_library(path))
+                .collect();
+            libraries.sort();
+            
+            // 没有清单的目录中的动态库按直接放置的插件处理
+            let manifest_path = path.join(manifest::MANIFEST_FILE);
+            if !manifest_path.is_file() {
+                found.extend(libraries.into_iter().map(|library| (library, None)));
+                continue;
             }
+            let manifest = PluginManifest::load(&manifest_path)?;
+            let library = match &manifest.library {
+                Some(library) => path.join(library),
+                None => {
+                    if libraries.len() != 1 {
+                        return Err(anyhow!(PluginError::LoadError {
+                            message: format!("{} 中应当有且只有一个插件动态库，或在清单中指定 library", path.display()),
+                        }));
+                    }
+                    libraries.remove(0)
+                }
+            };
+            found.push((library, Some(manifest)));
         }
+        Ok(found)
     }
     
     /// 加载所有插件
+    ///
+    /// 先加载全部动态库，再按清单中的依赖和先后约束排序，依次初始化。
+    /// 插件之间存在循环依赖时返回 [`PluginError::DependencyError`]。
     pub fn load_plugins(&mut self) -> Result<()> {
         // 确保插件目录存在
-        info!("开始加载插件，目录: {}", self.plugins_dir.display());
         if !self.plugins_dir.exists() {
             info!("插件目录不存在，创建目录: {}", self.plugins_dir.display());
             std::fs::create_dir_all(&self.plugins_dir)?;
             return Ok(());
         }
-
+        
         info!("开始加载插件，目录: {}", self.plugins_dir.display());
-        let mut loaded_count = 0;
-        let mut failed_count = 0;
         let mut failed_plugins = Vec::new();
-
-        // 安全读取目录
-        let entries = match std::fs::read_dir(&self.plugins_dir) {
-            Ok(entries) => entries,
+        
+        let candidates = match self.discover_plugins() {
+            Ok(candidates) => candidates,
             Err(e) => {
                 warn!("读取插件目录失败: {} - {}", self.plugins_dir.display(), e);
                 return Ok(());
             }
         };
-
-        // 遍历每个条目
-        for entry_result in entries {
-            let entry = match entry_result {
-                Ok(entry) => entry,
-                Err(e) => {
-                    warn!("读取目录条目失败: {}", e);
-                    failed_count += 1;
+        
+        let mut loaded: Vec<(PluginManifest, Box<dyn Plugin>)> = Vec::new();
+        for (path, manifest) in candidates {
+            // 提取文件名，用于调试
+            let file_name = path.file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_else(|| String::from("unknown"));
+            
+            if let Some(manifest) = &manifest {
+                if let Err(e) = manifest.check_host_version(env!("CARGO_PKG_VERSION")) {
+                    error!("跳过插件 {}: {}", file_name, e);
+                    failed_plugins.push(format!("{}: {}", file_name, e));
                     continue;
                 }
-            };
+            }
             
-            let path = entry.path();
+            info!("尝试加载插件: {}", path.display());
             
-            if path.is_file() {
-                // 检查是否是动态链接库
-                if let Some(ext) = path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    let is_plugin_lib = (cfg!(target_os = "windows") && ext_str == "dll") ||
-                                        (cfg!(target_os = "macos") && ext_str == "dylib") ||
-                                        (cfg!(target_os = "linux") && ext_str == "so");
+            // 使用 std::panic::catch_unwind 防止整个进程崩溃
+            let load_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                self.load_plugin_from_dylib(&path)
+            }));
+            
+            let plugin = match load_result {
+                Ok(Ok(plugin)) => plugin,
+                Ok(Err(e)) => {
+                    error!("加载插件失败: {} - {}", path.display(), e);
+                    failed_plugins.push(format!("{}: {}", file_name, e));
+                    continue;
+                },
+                Err(panic_err) => {
+                    let panic_msg = match panic_err.downcast_ref::<&str>() {
+                        Some(s) => *s,
+                        None => match panic_err.downcast_ref::<String>() {
+                            Some(s) => s.as_str(),
+                            None => "未知错误（可能是内存访问或ABI不兼容）",
+                        },
+                    };
                     
-                    if is_plugin_lib {
-                        info!("尝试加载插件: {}", path.display());
-                        
-                        // 提取文件名，用于调试
-                        let file_name = path.file_name()
-                                            .map(|n| n.to_string_lossy().to_string())
-                                            .unwrap_or_else(|| String::from("unknown"));
-                        
-                        info!("插件文件名: {}", file_name);
-                        
-                        // 使用 std::panic::catch_unwind 防止整个进程崩溃
-                        let load_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            self.load_plugin_from_dylib(&path)
-                        }));
-                        
-                        match load_result {
-                            Ok(plugin_result) => {
-                                match plugin_result {
-                            Ok(plugin) => {
-                                let name = plugin.name().to_string();
-                                info!("成功加载插件: {} v{} (文件: {})", name, plugin.version(), file_name);
-                                
-                                // 插件命名规范化 - 确保与配置文件中的名称匹配
-                                let config_name = if name.contains('_') {
-                                    name.replace('_', "-")
-                                } else {
-                                    name.clone()
-                                };
-                                
-                                info!("插件配置名: {}", config_name);
-                                self.plugins.write().unwrap().insert(config_name, plugin);
-                                loaded_count += 1;
-                            },
-                            Err(e) => {
-                                error!("加载插件失败: {} - {}", path.display(), e);
-                                        failed_plugins.push(format!("{}: {}", file_name, e));
-                                        failed_count += 1;
-                                    }
-                                }
-                            },
-                            Err(panic_err) => {
-                                let panic_msg = match panic_err.downcast_ref::<&str>() {
-                                    Some(s) => *s,
-                                    None => match panic_err.downcast_ref::<String>() {
-                                        Some(s) => s.as_str(),
-                                        None => "未知错误（可能是内存访问或ABI不兼容）",
-                                    },
-                                };
-                                
-                                error!("加载插件时发生严重错误: {} - {}", path.display(), panic_msg);
-                                failed_plugins.push(format!("{}: 严重错误 - {}", file_name, panic_msg));
-                                failed_count += 1;
-                            }
-                        }
-                    }
+                    error!("加载插件时发生严重错误: {} - {}", path.display(), panic_msg);
+                    failed_plugins.push(format!("{}: 严重错误 - {}", file_name, panic_msg));
+                    continue;
                 }
+            };
+            
+            // 插件命名规范化 - 确保与配置文件中的名称匹配
+            let name = plugin.name().replace('_', "-");
+            let manifest = manifest.unwrap_or_else(|| PluginManifest::new(&name, plugin.version()));
+            if manifest.name != name {
+                error!("插件 {} 的清单名称 {} 与插件名称不一致", file_name, manifest.name);
+                failed_plugins.push(format!("{}: 清单名称 {} 与插件名称 {} 不一致", file_name, manifest.name, name));
+                continue;
             }
+            if loaded.iter().any(|(loaded, _)| loaded.name == name) {
+                warn!("插件 {} 重复，忽略 {}", name, file_name);
+                failed_plugins.push(format!("{}: 插件 {} 重复", file_name, name));
+                continue;
+            }
+            
+            info!("成功加载插件: {} v{} (文件: {})", name, plugin.version(), file_name);
+            loaded.push((manifest, plugin));
         }
-
+        
+        // 按依赖关系排序
+        let manifests: Vec<PluginManifest> = loaded.iter().map(|(manifest, _)| manifest.clone()).collect();
+        let order = manifest::resolve_order(&manifests)?;
+        let mut slots: Vec<Option<(PluginManifest, Box<dyn Plugin>)>> = loaded.into_iter().map(Some).collect();
+        
+        // 依次初始化，依赖缺失或初始化失败的插件不会启用，依赖它的插件也一样
+        let mut plugins: LoadedPlugins = Vec::new();
+        for index in order {
+            let (manifest, mut plugin) = slots[index].take().expect("每个插件只排序一次");
+            
+            let missing: Vec<&str> = manifest.depends_on.iter()
+                .filter(|dependency| !plugins.iter().any(|(name, _)| name == *dependency))
+                .map(|dependency| dependency.as_str())
+                .collect();
+            if !missing.is_empty() {
+                let e = PluginError::DependencyError {
+                    message: format!("插件 {} 依赖的 {} 不可用", manifest.name, missing.join(", ")),
+                };
+                error!("{}", e);
+                failed_plugins.push(format!("{}: {}", manifest.name, e));
+                continue;
+            }
+            
+            if let Err(e) = self.init_plugin(&mut plugin) {
+                error!("插件 {} 初始化失败: {}", manifest.name, e);
+                failed_plugins.push(format!("{}: {}", manifest.name, e));
+                continue;
+            }
+            plugins.push((manifest.name, plugin));
+        }
+        
         // 打印已加载的插件列表
-        let plugins = self.plugins.read().unwrap();
         if !plugins.is_empty() {
-            info!("已加载的插件列表:");
+            info!("已加载的插件列表（按执行顺序）:");
             for (name, plugin) in plugins.iter() {
                 info!("  - {} v{}", name, plugin.version());
             }
@@ -405,7 +463,8 @@ impl PluginManager {
             }
         }
         
-        info!("插件加载完成 - 成功: {}, 失败: {}", loaded_count, failed_count);
+        info!("插件加载完成 - 成功: {}, 失败: {}", plugins.len(), failed_plugins.len());
+        *self.plugins.write().unwrap() = plugins;
         Ok(())
     }
 
@@ -519,8 +578,12 @@ impl PluginManager {
         Ok(())
     }
     
-    /// 注册到主题渲染器
-    pub fn register_to_theme_renderer(&self, _renderer: &mut crate::theme::renderer::ThemeRenderer) -> Result<()> {
+    /// 注册到主题渲染器：注册插件提供的模板函数，并让渲染器能够获取插件资源
+    pub fn register_to_theme_renderer(&self, renderer: &mut crate::theme::renderer::ThemeRenderer) -> Result<()> {
+        for (name, function) in self.template_functions() {
+            renderer.register_function(&name, function);
+        }
+        renderer.set_plugin_manager(self.clone());
         Ok(())
     }
     
@@ -535,114 +598,67 @@ impl PluginManager {
         *self.is_watching.write().unwrap() = false;
     }
     
-    /// 注册所有插件提供的模板函数到Tera实例
-    pub fn register_template_functions(&self, tera: &mut tera::Tera) -> Result<()> {
+    /// 收集所有插件提供的模板函数，插件的函数不依赖具体的模板引擎
+    pub fn template_functions(&self) -> Vec<(String, TemplateFunction)> {
         let plugins = self.plugins.read().unwrap();
+        let mut functions = Vec::new();
         
         for (name, plugin) in plugins.iter() {
             debug!("注册插件 {} 提供的模板函数", name);
-            
-            // 获取插件提供的模板函数
-            let template_functions = plugin.get_template_functions();
-            
-            // 注册每个函数到Tera实例
-            for (func_name, func) in template_functions {
+            for (func_name, func) in plugin.get_template_functions() {
                 debug!("注册模板函数: {}", func_name);
                 
-                // 在闭包外部克隆 func_name，这样可以在闭包中使用克隆版本
-                let func_name_clone = func_name.clone();
-                
-                // 创建一个闭包包装函数，将 serde_json::Value 转换为 tera::Value
-                let wrapped_func = move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
-                    // 将 tera::Value 转换为 serde_json::Value
-                    let converted_args: HashMap<String, serde_json::Value> = args.iter()
-                        .map(|(k, v)| {
-                            let json_value = match v {
-                                tera::Value::Null => serde_json::Value::Null,
-                                tera::Value::Bool(b) => serde_json::Value::Bool(*b),
-                                tera::Value::Number(n) => serde_json::Value::Number(n.clone()),
-                                tera::Value::String(s) => serde_json::Value::String(s.clone()),
-                                tera::Value::Array(a) => {
-                                    // 递归转换数组元素
-                                    let json_array: Vec<serde_json::Value> = a.iter()
-                                        .map(|item| match item {
-                                            tera::Value::String(s) => serde_json::Value::String(s.clone()),
-                                            tera::Value::Number(n) => serde_json::Value::Number(n.clone()),
-                                            tera::Value::Bool(b) => serde_json::Value::Bool(*b),
-                                            _ => serde_json::Value::Null,
-                                        })
-                                        .collect();
-                                    serde_json::Value::Array(json_array)
-                                },
-                                tera::Value::Object(o) => {
-                                    // 递归转换对象属性
-                                    let mut json_object = serde_json::Map::new();
-                                    for (key, val) in o {
-                                        let json_val = match val {
-                                            tera::Value::String(s) => serde_json::Value::String(s.clone()),
-                                            tera::Value::Number(n) => serde_json::Value::Number(n.clone()),
-                                            tera::Value::Bool(b) => serde_json::Value::Bool(*b),
-                                            _ => serde_json::Value::Null,
-                                        };
-                                        json_object.insert(key.clone(), json_val);
-                                    }
-                                    serde_json::Value::Object(json_object)
-                                },
-                            };
-                            (k.clone(), json_value)
-                        })
-                        .collect();
-                    
-                    // 调用插件提供的函数
-                    match func(&converted_args) {
-                        Ok(result) => {
-                            // 将 serde_json::Value 转换回 tera::Value
-                            match result {
-                                serde_json::Value::Null => Ok(tera::Value::Null),
-                                serde_json::Value::Bool(b) => Ok(tera::Value::Bool(b)),
-                                serde_json::Value::Number(n) => Ok(tera::Value::Number(n)),
-                                serde_json::Value::String(s) => Ok(tera::Value::String(s)),
-                                serde_json::Value::Array(a) => {
-                                    // 递归转换数组
-                                    let tera_array: Vec<tera::Value> = a.iter()
-                                        .map(|item| match item {
-                                            serde_json::Value::String(s) => tera::Value::String(s.clone()),
-                                            serde_json::Value::Number(n) => tera::Value::Number(n.clone()),
-                                            serde_json::Value::Bool(b) => tera::Value::Bool(*b),
-                                            _ => tera::Value::Null,
-                                        })
-                                        .collect();
-                                    Ok(tera::Value::Array(tera_array))
-                                },
-                                serde_json::Value::Object(o) => {
-                                    // 递归转换对象
-                                    let mut tera_object = serde_json::Map::new();
-                                    for (key, val) in o {
-                                        let tera_val = match val {
-                                            serde_json::Value::String(s) => tera::Value::String(s),
-                                            serde_json::Value::Number(n) => tera::Value::Number(n),
-                                            serde_json::Value::Bool(b) => tera::Value::Bool(b),
-                                            _ => tera::Value::Null,
-                                        };
-                                        tera_object.insert(key, tera_val);
-                                    }
-                                    Ok(tera::Value::Object(tera_object))
-                                },
-                            }
-                        },
-                        Err(e) => Err(tera::Error::msg(format!("插件函数 '{}' 执行失败: {}", func_name_clone, e))),
-                    }
-                };
-                
-                // 注册函数到Tera
-                tera.register_function(&func_name, Box::new(wrapped_func));
+                // 在错误信息中标明出错的插件函数
+                let plugin_func_name = func_name.clone();
+                let wrapped: TemplateFunction = Arc::new(move |args: &HashMap<String, serde_json::Value>| {
+                    func(args).map_err(|e| anyhow!("插件函数 '{}' 执行失败: {}", plugin_func_name, e))
+                });
+                functions.push((func_name, wrapped));
             }
         }
         
-        Ok(())
+        functions
+    }
+    
+    /// 按执行顺序为提供 HTML 改写规则的插件各生成一个整页改写函数
+    pub fn page_rewriters(&self) -> Vec<PageRewriter> {
+        let plugins = self.plugins.read().unwrap();
+        let mut rewriters = Vec::new();
+        
+        for (name, plugin) in plugins.iter() {
+            if !plugin.has_html_rewriters() {
+                continue;
+            }
+            debug!("注册插件 {} 的 HTML 改写规则", name);
+            
+            // 在错误信息中标明出错的插件
+            let all_plugins = self.plugins.clone();
+            let plugin_name = name.clone();
+            let rewriter: PageRewriter = Arc::new(move |html: &str, page: &str| {
+                let plugins = all_plugins.read().unwrap();
+                match plugins.iter().find(|(name, _)| *name == plugin_name) {
+                    Some((_, plugin)) => plugin.rewrite_html(html, page)
+                        .map_err(|e| anyhow!("插件 {} 改写 HTML 失败: {}", plugin_name, e)),
+                    None => Ok(html.to_string()),
+                }
+            });
+            rewriters.push(rewriter);
+        }
+        
+        rewriters
     }
 }
 
+/// 是否是当前平台的动态链接库
+fn is_plugin_library(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| {
+        let ext_str = ext.to_string_lossy().to_lowercase();
+        (cfg!(target_os = "windows") && ext_str == "dll") ||
+            (cfg!(target_os = "macos") && ext_str == "dylib") ||
+            (cfg!(target_os = "linux") && ext_str == "so")
+    })
+}
+
 /// 插件克隆辅助结构体（只有基本信息）
 #[derive(Clone)]
 struct ClonedPlugin {
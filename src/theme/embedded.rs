@@ -0,0 +1,74 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// 内置主题的名称，站点配置的主题不存在时使用
+pub const EMBEDDED_THEME_NAME: &str = "default";
+
+/// 编译进程序的默认主题文件，路径相对于主题目录
+pub const FILES: &[(&str, &str)] = &[
+    ("layout/layout.html", include_str!("../../embed/theme/default/layout/layout.html")),
+    ("layout/index.html", include_str!("../../embed/theme/default/layout/index.html")),
+    ("layout/post.html", include_str!("../../embed/theme/default/layout/post.html")),
+    ("layout/category.html", include_str!("../../embed/theme/default/layout/category.html")),
+    ("layout/tag.html", include_str!("../../embed/theme/default/layout/tag.html")),
+    ("layout/categories.html", include_str!("../../embed/theme/default/layout/categories.html")),
+    ("layout/tags.html", include_str!("../../embed/theme/default/layout/tags.html")),
+    ("layout/archive.html", include_str!("../../embed/theme/default/layout/archive.html")),
+    ("source/css/style.css", include_str!("../../embed/theme/default/source/css/style.css")),
+    ("source/js/script.js", include_str!("../../embed/theme/default/source/js/script.js")),
+];
+
+/// 内置主题的模板，返回相对于 `layout/` 的模板名和内容
+pub fn layouts() -> impl Iterator<Item = (&'static str, &'static str)> {
+    files_in("layout/")
+}
+
+/// 内置主题的静态资源，返回相对于 `source/` 的路径和内容
+pub fn assets() -> impl Iterator<Item = (&'static str, &'static str)> {
+    files_in("source/")
+}
+
+fn files_in(prefix: &'static str) -> impl Iterator<Item = (&'static str, &'static str)> {
+    FILES.iter().filter_map(move |(path, content)| path.strip_prefix(prefix).map(|name| (name, *content)))
+}
+
+/// 将内置主题写入 `theme_dir`，返回写入的文件数
+///
+/// 目录已存在且不为空时，只有 `force` 为真才会覆盖其中的同名文件。
+pub fn eject(theme_dir: &Path, force: bool) -> Result<usize> {
+    let is_empty = theme_dir.read_dir().map(|mut entries| entries.next().is_none()).unwrap_or(true);
+    if !is_empty && !force {
+        return Err(anyhow!("主题目录已存在: {}，使用 --force 覆盖", theme_dir.display()));
+    }
+    
+    for (path, content) in FILES {
+        let dest_path = theme_dir.join(path);
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest_path, content)
+            .with_context(|| format!("写入主题文件失败: {}", dest_path.display()))?;
+    }
+    fs::create_dir_all(theme_dir.join("source").join("images"))?;
+    
+    Ok(FILES.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_eject() {
+        assert!(layouts().any(|(name, _)| name == "post.html"));
+        assert!(assets().any(|(path, _)| path == "css/style.css"));
+        
+        let dir = tempfile::tempdir().unwrap();
+        let theme_dir = dir.path().join("default");
+        assert_eq!(eject(&theme_dir, false).unwrap(), FILES.len());
+        assert!(theme_dir.join("layout/index.html").is_file());
+        assert!(eject(&theme_dir, false).is_err());
+        assert!(eject(&theme_dir, true).is_ok());
+    }
+}
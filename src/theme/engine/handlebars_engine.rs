@@ -0,0 +1,82 @@
+use anyhow::{Context as _, Result};
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, RenderContext, RenderError, RenderErrorReason, ScopedJson,
+};
+use std::collections::HashMap;
+
+use super::{error_message, TemplateContext, TemplateEngine, TemplateEngineKind, TemplateFilter, TemplateFunction};
+
+/// Handlebars 模板引擎
+///
+/// 模板名为去掉扩展名的相对路径（如 `post`、`_partial/header`），以便通过 `{{> _partial/header}}` 引用。
+/// 函数以命名参数调用（`{{url_for path="about/"}}`），过滤器以第一个参数为值（`{{markdown content}}`）。
+#[derive(Clone, Default)]
+pub struct HandlebarsEngine {
+    registry: Handlebars<'static>,
+}
+
+/// 将模板函数或过滤器包装为 Handlebars helper
+enum FunctionHelper {
+    Function(String, TemplateFunction),
+    Filter(String, TemplateFilter),
+}
+
+impl HelperDef for FunctionHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let args: HashMap<String, serde_json::Value> = h.hash().iter()
+            .map(|(key, value)| (key.to_string(), value.value().clone()))
+            .collect();
+        
+        let result = match self {
+            Self::Function(name, function) => function(&args).map_err(|e| error_message(name, e)),
+            Self::Filter(name, filter) => {
+                let value = h.param(0).map(|p| p.value().clone()).unwrap_or(serde_json::Value::Null);
+                filter(&value, &args).map_err(|e| error_message(name, e))
+            }
+        };
+        result.map(ScopedJson::Derived)
+            .map_err(|message| RenderErrorReason::Other(message).into())
+    }
+}
+
+impl TemplateEngine for HandlebarsEngine {
+    fn kind(&self) -> TemplateEngineKind {
+        TemplateEngineKind::Handlebars
+    }
+    
+    fn load_templates(&mut self, templates: Vec<(String, String)>) -> Result<()> {
+        self.registry.clear_templates();
+        for (name, source) in templates {
+            let name = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(&name).to_string();
+            self.registry.register_template_string(&name, source)
+                .with_context(|| format!("加载 Handlebars 模板 {} 失败", name))?;
+        }
+        Ok(())
+    }
+    
+    fn register_function(&mut self, name: &str, function: TemplateFunction) {
+        self.registry.register_helper(name, Box::new(FunctionHelper::Function(name.to_string(), function)));
+    }
+    
+    fn register_filter(&mut self, name: &str, filter: TemplateFilter) {
+        self.registry.register_helper(name, Box::new(FunctionHelper::Filter(name.to_string(), filter)));
+    }
+    
+    fn render(&self, name: &str, context: &TemplateContext) -> Result<String> {
+        Ok(self.registry.render(name, context.as_map())?)
+    }
+    
+    fn template_names(&self) -> Vec<String> {
+        self.registry.get_templates().keys().cloned().collect()
+    }
+    
+    fn box_clone(&self) -> Box<dyn TemplateEngine> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use minijinja::value::Kwargs;
+use minijinja::{Environment, Error, ErrorKind, Value};
+use std::collections::{BTreeSet, HashMap};
+
+use super::{error_message, TemplateContext, TemplateEngine, TemplateEngineKind, TemplateFilter, TemplateFunction};
+
+/// minijinja（Jinja2 语法）模板引擎，模板名为包含扩展名的相对路径，如 `post.j2`
+#[derive(Clone)]
+pub struct MiniJinjaEngine {
+    env: Environment<'static>,
+}
+
+impl Default for MiniJinjaEngine {
+    fn default() -> Self {
+        // `Environment::default()` 不包含内置过滤器和测试
+        Self { env: Environment::new() }
+    }
+}
+
+impl MiniJinjaEngine {
+    /// 将命名参数转换为 JSON
+    fn kwargs_to_json(kwargs: &Kwargs) -> Result<HashMap<String, serde_json::Value>, Error> {
+        kwargs.args()
+            .map(|key| {
+                let value: Value = kwargs.get(key)?;
+                let value = serde_json::to_value(&value)
+                    .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+                Ok((key.to_string(), value))
+            })
+            .collect()
+    }
+    
+    fn call_error(name: &str, error: anyhow::Error) -> Error {
+        Error::new(ErrorKind::InvalidOperation, error_message(name, error))
+    }
+}
+
+impl TemplateEngine for MiniJinjaEngine {
+    fn kind(&self) -> TemplateEngineKind {
+        TemplateEngineKind::MiniJinja
+    }
+    
+    fn load_templates(&mut self, templates: Vec<(String, String)>) -> Result<()> {
+        self.env.clear_templates();
+        for (name, source) in templates {
+            self.env.add_template_owned(name.clone(), source)
+                .with_context(|| format!("加载 minijinja 模板 {} 失败", name))?;
+        }
+        Ok(())
+    }
+    
+    fn register_function(&mut self, name: &str, function: TemplateFunction) {
+        let function_name = name.to_string();
+        self.env.add_function(name.to_string(), move |kwargs: Kwargs| -> Result<Value, Error> {
+            let args = Self::kwargs_to_json(&kwargs)?;
+            let result = function(&args).map_err(|e| Self::call_error(&function_name, e))?;
+            Ok(Value::from_serialize(&result))
+        });
+    }
+    
+    fn register_filter(&mut self, name: &str, filter: TemplateFilter) {
+        let filter_name = name.to_string();
+        self.env.add_filter(name.to_string(), move |value: Value, kwargs: Kwargs| -> Result<Value, Error> {
+            let value = serde_json::to_value(&value)
+                .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+            let args = Self::kwargs_to_json(&kwargs)?;
+            let result = filter(&value, &args).map_err(|e| Self::call_error(&filter_name, e))?;
+            Ok(Value::from_serialize(&result))
+        });
+    }
+    
+    fn render(&self, name: &str, context: &TemplateContext) -> Result<String> {
+        let template = self.env.get_template(name)?;
+        Ok(template.render(Value::from_serialize(context.as_map()))?)
+    }
+    
+    fn template_names(&self) -> Vec<String> {
+        self.env.templates().map(|(name, _)| name.to_string()).collect()
+    }
+    
+    fn box_clone(&self) -> Box<dyn TemplateEngine> {
+        Box::new(self.clone())
+    }
+    
+    fn referenced_variables(&self, name: &str) -> Option<BTreeSet<String>> {
+        let template = self.env.get_template(name).ok()?;
+        // 函数也是全局变量，需要排除
+        let globals: BTreeSet<&str> = self.env.globals().map(|(name, _)| name).collect();
+        Some(template.undeclared_variables(true).into_iter()
+            .filter(|variable| !globals.contains(variable.split('.').next().unwrap_or_default()))
+            .collect())
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use walkdir::WalkDir;
+
+pub mod handlebars_engine;
+pub mod minijinja_engine;
+pub mod tera_engine;
+
+pub use handlebars_engine::HandlebarsEngine;
+pub use minijinja_engine::MiniJinjaEngine;
+pub use tera_engine::TeraEngine;
+
+/// 与模板引擎无关的模板函数，参数为调用时传入的命名参数
+pub type TemplateFunction = Arc<dyn Fn(&HashMap<String, Value>) -> Result<Value> + Send + Sync>;
+
+/// 与模板引擎无关的过滤器，参数为被过滤的值和命名参数
+pub type TemplateFilter = Arc<dyn Fn(&Value, &HashMap<String, Value>) -> Result<Value> + Send + Sync>;
+
+/// 模板引擎
+///
+/// 主题渲染器通过该接口加载模板、注册函数和渲染页面，不依赖具体的模板引擎。
+pub trait TemplateEngine: Send + Sync {
+    /// 引擎类型
+    fn kind(&self) -> TemplateEngineKind;
+    
+    /// 替换全部模板，`templates` 中的每一项为模板相对于 `layout/` 的路径及其内容，
+    /// 已注册的函数和过滤器保持不变
+    fn load_templates(&mut self, templates: Vec<(String, String)>) -> Result<()>;
+    
+    /// 注册模板函数
+    fn register_function(&mut self, name: &str, function: TemplateFunction);
+    
+    /// 注册过滤器，不支持过滤器的引擎以第一个参数为值的函数形式提供
+    fn register_filter(&mut self, name: &str, filter: TemplateFilter);
+    
+    /// 渲染模板
+    fn render(&self, name: &str, context: &TemplateContext) -> Result<String>;
+    
+    /// 已加载的模板名
+    fn template_names(&self) -> Vec<String>;
+    
+    /// 复制引擎，包括已加载的模板和已注册的函数
+    fn box_clone(&self) -> Box<dyn TemplateEngine>;
+    
+    /// 模板从上下文中读取的变量，如 `page.title`，不包括模板内定义的局部变量；
+    /// 引擎不支持分析时返回 `None`
+    fn referenced_variables(&self, _name: &str) -> Option<BTreeSet<String>> {
+        None
+    }
+    
+    /// 查找模板名对应的已加载模板
+    ///
+    /// 程序中使用 `post.html` 这样的名称引用模板，其他引擎的主题中对应的模板可能是 `post.j2`、`post.html.j2` 或 `post`。
+    fn resolve(&self, name: &str) -> Option<String> {
+        let names = self.template_names();
+        let stem = name.strip_suffix(".html").unwrap_or(name);
+        
+        let mut candidates = vec![name.to_string(), stem.to_string()];
+        for ext in self.kind().extensions() {
+            candidates.push(format!("{}.{}", stem, ext));
+            candidates.push(format!("{}.{}", name, ext));
+        }
+        candidates.into_iter().find(|candidate| names.contains(candidate))
+    }
+}
+
+impl Clone for Box<dyn TemplateEngine> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// 支持的模板引擎
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TemplateEngineKind {
+    Tera,
+    MiniJinja,
+    Handlebars,
+}
+
+impl TemplateEngineKind {
+    /// 按优先级排列的所有引擎
+    pub const ALL: [TemplateEngineKind; 3] = [Self::Tera, Self::MiniJinja, Self::Handlebars];
+    
+    /// 引擎使用的模板文件扩展名
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Tera => &["html", "tera"],
+            Self::MiniJinja => &["j2", "jinja", "jinja2"],
+            Self::Handlebars => &["hbs", "handlebars"],
+        }
+    }
+    
+    /// 根据文件扩展名判断所属的引擎
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        Self::ALL.into_iter().find(|kind| kind.extensions().contains(&ext))
+    }
+    
+    /// 根据模板目录中各类模板文件的数量选择引擎，数量相同时按 `ALL` 的顺序优先
+    pub fn detect(layout_dirs: &[PathBuf]) -> Self {
+        let mut counts: HashMap<TemplateEngineKind, usize> = HashMap::new();
+        for dir in layout_dirs.iter().filter(|dir| dir.is_dir()) {
+            for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+                if let Some(kind) = Self::from_path(entry.path()) {
+                    *counts.entry(kind).or_default() += 1;
+                }
+            }
+        }
+        
+        Self::ALL.into_iter()
+            .rev()
+            .max_by_key(|kind| counts.get(kind).copied().unwrap_or(0))
+            .unwrap_or(Self::Tera)
+    }
+    
+    /// 创建引擎实例
+    pub fn create(self) -> Box<dyn TemplateEngine> {
+        match self {
+            Self::Tera => Box::new(TeraEngine::default()),
+            Self::MiniJinja => Box::new(MiniJinjaEngine::default()),
+            Self::Handlebars => Box::new(HandlebarsEngine::default()),
+        }
+    }
+}
+
+impl fmt::Display for TemplateEngineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Tera => "Tera",
+            Self::MiniJinja => "minijinja",
+            Self::Handlebars => "Handlebars",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 模板上下文，与具体的模板引擎无关
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext(Map<String, Value>);
+
+impl TemplateContext {
+    /// 创建空的上下文
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 插入变量，无法序列化的值记为 `null`
+    pub fn insert<T: Serialize + ?Sized>(&mut self, key: impl Into<String>, value: &T) {
+        self.0.insert(key.into(), serde_json::to_value(value).unwrap_or(Value::Null));
+    }
+    
+    /// 读取变量
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+    
+    /// 所有变量
+    pub fn as_map(&self) -> &Map<String, Value> {
+        &self.0
+    }
+}
+
+/// 将模板函数的错误转换为字符串，供各引擎包装为自己的错误类型
+fn error_message(name: &str, error: anyhow::Error) -> String {
+    format!("模板函数 {} 执行失败: {:#}", name, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    /// 同一套函数和过滤器在三种引擎中的渲染结果一致
+    #[test]
+    fn test_engines_share_functions() {
+        let templates = [
+            (TemplateEngineKind::Tera, "page.html", "{{ greet(name=user) }} {{ user | shout }}"),
+            (TemplateEngineKind::MiniJinja, "page.j2", "{{ greet(name=user) }} {{ user | shout | safe }}"),
+            (TemplateEngineKind::Handlebars, "page.hbs", "{{greet name=user}} {{shout user}}"),
+        ];
+        
+        let mut context = TemplateContext::new();
+        context.insert("user", "ada");
+        
+        for (kind, name, source) in templates {
+            let mut engine = kind.create();
+            engine.register_function("greet", Arc::new(|args| {
+                Ok(json!(format!("hi {}", args.get("name").and_then(Value::as_str).unwrap_or_default())))
+            }));
+            engine.register_filter("shout", Arc::new(|value, _| Ok(json!(value.as_str().unwrap_or_default().to_uppercase()))));
+            engine.load_templates(vec![(name.to_string(), source.to_string())]).unwrap();
+            
+            let template = engine.template_names().pop().unwrap();
+            assert_eq!(engine.render(&template, &context).unwrap(), "hi ada ADA", "{}", kind);
+        }
+        
+        assert_eq!(TemplateEngineKind::from_path(Path::new("layout/post.hbs")), Some(TemplateEngineKind::Handlebars));
+    }
+}
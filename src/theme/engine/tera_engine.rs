@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashSet};
+use tera::ast::{Expr, ExprVal, FunctionCall, Node};
+use tera::Tera;
+
+use super::{error_message, TemplateContext, TemplateEngine, TemplateEngineKind, TemplateFilter, TemplateFunction};
+
+/// Tera 模板引擎，模板名为包含扩展名的相对路径，如 `post.html`
+#[derive(Clone, Default)]
+pub struct TeraEngine {
+    tera: Tera,
+    /// 已注册的函数和过滤器，重新加载模板时注册到新的实例
+    functions: Vec<(String, TemplateFunction)>,
+    filters: Vec<(String, TemplateFilter)>,
+}
+
+impl TeraEngine {
+    fn add_function(tera: &mut Tera, name: &str, function: TemplateFunction) {
+        let name = name.to_string();
+        tera.register_function(&name.clone(), move |args: &std::collections::HashMap<String, tera::Value>| {
+            function(args).map_err(|e| tera::Error::msg(error_message(&name, e)))
+        });
+    }
+    
+    fn add_filter(tera: &mut Tera, name: &str, filter: TemplateFilter) {
+        let name = name.to_string();
+        tera.register_filter(&name.clone(), move |value: &tera::Value, args: &std::collections::HashMap<String, tera::Value>| {
+            filter(value, args).map_err(|e| tera::Error::msg(error_message(&name, e)))
+        });
+    }
+}
+
+impl TemplateEngine for TeraEngine {
+    fn kind(&self) -> TemplateEngineKind {
+        TemplateEngineKind::Tera
+    }
+    
+    fn load_templates(&mut self, templates: Vec<(String, String)>) -> Result<()> {
+        // Tera 无法移除已加载的模板，重新创建实例后再注册函数
+        let mut tera = Tera::default();
+        for (name, function) in &self.functions {
+            Self::add_function(&mut tera, name, function.clone());
+        }
+        for (name, filter) in &self.filters {
+            Self::add_filter(&mut tera, name, filter.clone());
+        }
+        tera.add_raw_templates(templates).context("加载 Tera 模板失败")?;
+        
+        self.tera = tera;
+        Ok(())
+    }
+    
+    fn register_function(&mut self, name: &str, function: TemplateFunction) {
+        Self::add_function(&mut self.tera, name, function.clone());
+        self.functions.push((name.to_string(), function));
+    }
+    
+    fn register_filter(&mut self, name: &str, filter: TemplateFilter) {
+        Self::add_filter(&mut self.tera, name, filter.clone());
+        self.filters.push((name.to_string(), filter));
+    }
+    
+    fn render(&self, name: &str, context: &TemplateContext) -> Result<String> {
+        let context = tera::Context::from_serialize(context.as_map())?;
+        Ok(self.tera.render(name, &context)?)
+    }
+    
+    fn template_names(&self) -> Vec<String> {
+        self.tera.get_template_names().map(String::from).collect()
+    }
+    
+    fn box_clone(&self) -> Box<dyn TemplateEngine> {
+        Box::new(self.clone())
+    }
+    
+    fn referenced_variables(&self, name: &str) -> Option<BTreeSet<String>> {
+        let template = self.tera.get_template(name).ok()?;
+        let mut collector = VariableCollector::default();
+        collector.nodes(&template.ast);
+        Some(collector.finish())
+    }
+}
+
+/// 收集 Tera 模板语法树中引用的上下文变量
+#[derive(Default)]
+struct VariableCollector {
+    variables: BTreeSet<String>,
+    /// 循环变量、`set` 和宏参数定义的局部变量
+    locals: HashSet<String>,
+}
+
+impl VariableCollector {
+    fn nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::VariableBlock(_, expr) => self.expr(expr),
+                Node::Set(_, set) => {
+                    self.locals.insert(set.key.clone());
+                    self.expr(&set.value);
+                }
+                Node::MacroDefinition(_, definition, _) => {
+                    self.locals.extend(definition.args.keys().cloned());
+                    definition.args.values().flatten().for_each(|expr| self.expr(expr));
+                    self.nodes(&definition.body);
+                }
+                Node::FilterSection(_, section, _) => {
+                    self.function_call(&section.filter);
+                    self.nodes(&section.body);
+                }
+                Node::Block(_, block, _) => self.nodes(&block.body),
+                Node::Forloop(_, forloop, _) => {
+                    self.locals.extend(forloop.key.iter().cloned());
+                    self.locals.insert(forloop.value.clone());
+                    self.expr(&forloop.container);
+                    self.nodes(&forloop.body);
+                    if let Some(body) = &forloop.empty_body {
+                        self.nodes(body);
+                    }
+                }
+                Node::If(condition, _) => {
+                    for (_, expr, body) in &condition.conditions {
+                        self.expr(expr);
+                        self.nodes(body);
+                    }
+                    if let Some((_, body)) = &condition.otherwise {
+                        self.nodes(body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    fn expr(&mut self, expr: &Expr) {
+        self.expr_val(&expr.val);
+        expr.filters.iter().for_each(|filter| self.function_call(filter));
+    }
+    
+    fn expr_val(&mut self, val: &ExprVal) {
+        match val {
+            ExprVal::Ident(ident) => self.ident(ident),
+            ExprVal::Math(math) => {
+                self.expr(&math.lhs);
+                self.expr(&math.rhs);
+            }
+            ExprVal::Logic(logic) => {
+                self.expr(&logic.lhs);
+                self.expr(&logic.rhs);
+            }
+            ExprVal::In(expr) => {
+                self.expr(&expr.lhs);
+                self.expr(&expr.rhs);
+            }
+            ExprVal::Test(test) => {
+                self.ident(&test.ident);
+                test.args.iter().for_each(|expr| self.expr(expr));
+            }
+            ExprVal::MacroCall(call) => call.args.values().for_each(|expr| self.expr(expr)),
+            ExprVal::FunctionCall(call) => self.function_call(call),
+            ExprVal::Array(items) => items.iter().for_each(|expr| self.expr(expr)),
+            ExprVal::StringConcat(concat) => concat.values.iter().for_each(|val| self.expr_val(val)),
+            _ => {}
+        }
+    }
+    
+    fn function_call(&mut self, call: &FunctionCall) {
+        call.args.values().for_each(|expr| self.expr(expr));
+    }
+    
+    /// 记录 `page.posts[0].title` 这样的变量路径中下标之前的部分
+    fn ident(&mut self, ident: &str) {
+        let path = ident.split('[').next().unwrap_or_default();
+        self.variables.insert(path.to_string());
+    }
+    
+    fn finish(self) -> BTreeSet<String> {
+        let locals = self.locals;
+        self.variables.into_iter()
+            .filter(|variable| {
+                let root = variable.split('.').next().unwrap_or_default();
+                !locals.contains(root) && root != "loop" && root != "__tera_context"
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,654 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use chrono::{Datelike, NaiveDate};
+use serde_json::Value;
+
+use crate::models::config::Config;
+use crate::models::types::Post;
+use crate::theme::engine::TemplateEngine;
+use crate::utils::url::{current_path, UrlHelper};
+
+/// 模板函数的参数
+type Args = HashMap<String, Value>;
+
+/// 辅助函数的实现
+type Helper = fn(&SiteHelpers, &Args) -> String;
+
+/// Hexo 风格的列表辅助函数
+///
+/// 函数名和参数与 Hexo 保持一致，返回 HTML 字符串，模板中需要配合 `safe` 过滤器使用，
+/// 例如 `{{ list_tags(show_count=false) | safe }}`。文章数据由引擎在每次生成前通过 `set_posts` 更新。
+#[derive(Clone)]
+pub struct SiteHelpers {
+    /// 站点中的所有文章
+    posts: Arc<RwLock<Vec<Post>>>,
+    /// 链接生成器
+    urls: UrlHelper,
+    /// 站点配置
+    config: Config,
+}
+
+/// 分类树中的节点
+struct CategoryNode {
+    name: String,
+    count: usize,
+    children: Vec<CategoryNode>,
+}
+
+impl SiteHelpers {
+    /// 根据站点配置创建
+    pub fn new(config: &Config) -> Self {
+        Self {
+            posts: Arc::new(RwLock::new(Vec::new())),
+            urls: UrlHelper::from_config(config),
+            config: config.clone(),
+        }
+    }
+    
+    /// 更新辅助函数使用的文章列表
+    pub fn set_posts(&self, posts: &[Post]) {
+        *self.posts.write().unwrap() = posts.to_vec();
+    }
+    
+    /// 注册 `list_categories`、`list_tags`、`tagcloud`、`list_archives`、`list_posts`、`paginator`、`open_graph`
+    pub fn register(&self, engine: &mut dyn TemplateEngine) {
+        let helpers: [(&str, Helper); 7] = [
+            ("list_categories", Self::list_categories),
+            ("list_tags", Self::list_tags),
+            ("tagcloud", Self::tagcloud),
+            ("list_archives", Self::list_archives),
+            ("list_posts", Self::list_posts),
+            ("paginator", Self::paginator),
+            ("open_graph", Self::open_graph),
+        ];
+        
+        for (name, helper) in helpers {
+            let site = self.clone();
+            engine.register_function(name, Arc::new(move |args: &Args| Ok(Value::String(helper(&site, args)))));
+        }
+    }
+    
+    /// 分类列表，按文章的分类层级嵌套
+    ///
+    /// 参数：`orderby`（name、count）、`order`、`show_count`、`style`（list、none）、`separator`、`depth`、`class`
+    pub fn list_categories(&self, args: &Args) -> String {
+        let posts = self.posts.read().unwrap();
+        let paths: Vec<&[String]> = posts.iter()
+            .map(|post| post.categories.as_slice())
+            .filter(|categories| !categories.is_empty())
+            .collect();
+        let mut tree = Self::category_tree(&paths);
+        
+        let orderby = str_arg(args, "orderby", "name");
+        let descending = is_descending(args, false);
+        Self::sort_categories(&mut tree, &orderby, descending);
+        
+        let class = str_arg(args, "class", "category");
+        let show_count = bool_arg(args, "show_count", true);
+        let depth = usize_arg(args, "depth", 0);
+        
+        if is_list_style(args) {
+            self.category_list(&tree, &class, show_count, depth, 1)
+        } else {
+            let separator = str_arg(args, "separator", ", ");
+            let mut links = Vec::new();
+            self.category_links(&tree, &class, show_count, depth, 1, &mut links);
+            links.join(&separator)
+        }
+    }
+    
+    /// 标签列表
+    ///
+    /// 参数：`orderby`（name、count）、`order`、`show_count`、`style`（list、none）、`separator`、`amount`、`class`
+    pub fn list_tags(&self, args: &Args) -> String {
+        let tags = self.sorted_tags(args, "name", false);
+        let class = str_arg(args, "class", "tag");
+        let show_count = bool_arg(args, "show_count", true);
+        
+        if is_list_style(args) {
+            let mut html = format!("<ul class=\"{}-list\" itemprop=\"keywords\">", class);
+            for (name, count) in &tags {
+                html.push_str(&format!(
+                    "<li class=\"{0}-list-item\"><a class=\"{0}-list-link\" href=\"{1}\" rel=\"tag\">{2}</a>",
+                    class, self.urls.url_for_tag(name), escape(name)
+                ));
+                if show_count {
+                    html.push_str(&format!("<span class=\"{}-list-count\">{}</span>", class, count));
+                }
+                html.push_str("</li>");
+            }
+            html.push_str("</ul>");
+            html
+        } else {
+            let separator = str_arg(args, "separator", ", ");
+            tags.iter()
+                .map(|(name, count)| {
+                    let count = if show_count { format!("<span class=\"{}-count\">{}</span>", class, count) } else { String::new() };
+                    format!("<a class=\"{}-link\" href=\"{}\" rel=\"tag\">{}{}</a>", class, self.urls.url_for_tag(name), escape(name), count)
+                })
+                .collect::<Vec<_>>()
+                .join(&separator)
+        }
+    }
+    
+    /// 标签云，字体大小按文章数在 `min_font` 和 `max_font` 之间分布
+    ///
+    /// 参数：`min_font`、`max_font`、`unit`、`amount`、`orderby`、`order`、`color`、`start_color`、`end_color`
+    pub fn tagcloud(&self, args: &Args) -> String {
+        let tags = self.sorted_tags(args, "name", false);
+        let min_font = f64_arg(args, "min_font", 10.0);
+        let max_font = f64_arg(args, "max_font", 20.0);
+        let unit = str_arg(args, "unit", "px");
+        
+        let colors = if bool_arg(args, "color", false) {
+            parse_color(&str_arg(args, "start_color", "")).zip(parse_color(&str_arg(args, "end_color", "")))
+        } else {
+            None
+        };
+        
+        // 按不同的文章数分级，文章数相同的标签大小相同
+        let mut counts: Vec<usize> = tags.iter().map(|(_, count)| *count).collect();
+        counts.sort_unstable();
+        counts.dedup();
+        let levels = counts.len().saturating_sub(1).max(1) as f64;
+        
+        tags.iter()
+            .map(|(name, count)| {
+                let ratio = counts.iter().position(|c| c == count).unwrap_or(0) as f64 / levels;
+                let size = min_font + (max_font - min_font) * ratio;
+                let mut style = format!("font-size: {}{};", (size * 100.0).round() / 100.0, unit);
+                if let Some((start, end)) = colors {
+                    style.push_str(&format!(" color: {}", mix_color(start, end, ratio)));
+                }
+                format!("<a href=\"{}\" style=\"{}\">{}</a>", self.urls.url_for_tag(name), style, escape(name))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    
+    /// 归档列表
+    ///
+    /// 参数：`type`（monthly、yearly）、`order`、`show_count`、`format`、`style`（list、none）、`separator`、`class`
+    pub fn list_archives(&self, args: &Args) -> String {
+        let yearly = str_arg(args, "type", "monthly") == "yearly";
+        let default_format = if yearly { "%Y" } else { "%B %Y" };
+        let format = str_arg(args, "format", default_format);
+        
+        let mut archives: BTreeMap<(i32, u32), usize> = BTreeMap::new();
+        for post in self.posts.read().unwrap().iter() {
+            let month = if yearly { 1 } else { post.date.month() };
+            *archives.entry((post.date.year(), month)).or_default() += 1;
+        }
+        
+        let mut archives: Vec<((i32, u32), usize)> = archives.into_iter().collect();
+        if is_descending(args, true) {
+            archives.reverse();
+        }
+        
+        let class = str_arg(args, "class", "archive");
+        let show_count = bool_arg(args, "show_count", true);
+        let items: Vec<(String, String, usize)> = archives.into_iter()
+            .map(|((year, month), count)| {
+                let path = if yearly { format!("archives/{}/", year) } else { format!("archives/{}/{:02}/", year, month) };
+                let name = NaiveDate::from_ymd_opt(year, month, 1)
+                    .map(|date| date.format(&format).to_string())
+                    .unwrap_or_else(|| year.to_string());
+                (self.urls.url_for(&path, None), name, count)
+            })
+            .collect();
+        
+        if is_list_style(args) {
+            let mut html = format!("<ul class=\"{}-list\">", class);
+            for (url, name, count) in &items {
+                html.push_str(&format!("<li class=\"{0}-list-item\"><a class=\"{0}-list-link\" href=\"{1}\">{2}</a>", class, url, escape(name)));
+                if show_count {
+                    html.push_str(&format!("<span class=\"{}-list-count\">{}</span>", class, count));
+                }
+                html.push_str("</li>");
+            }
+            html.push_str("</ul>");
+            html
+        } else {
+            let separator = str_arg(args, "separator", ", ");
+            items.iter()
+                .map(|(url, name, count)| {
+                    let count = if show_count { format!("<span class=\"{}-count\">{}</span>", class, count) } else { String::new() };
+                    format!("<a class=\"{}-link\" href=\"{}\">{}{}</a>", class, url, escape(name), count)
+                })
+                .collect::<Vec<_>>()
+                .join(&separator)
+        }
+    }
+    
+    /// 文章列表，默认为最近的 6 篇文章
+    ///
+    /// 参数：`amount`、`orderby`（date、title）、`order`、`tag`、`category`、`style`（list、none）、`separator`、`class`
+    pub fn list_posts(&self, args: &Args) -> String {
+        let posts = self.posts.read().unwrap();
+        let tag = args.get("tag").and_then(Value::as_str);
+        let category = args.get("category").and_then(Value::as_str);
+        
+        let mut selected: Vec<&Post> = posts.iter()
+            .filter(|post| tag.is_none_or(|tag| post.tags.iter().any(|t| t == tag)))
+            .filter(|post| category.is_none_or(|category| post.categories.iter().any(|c| c == category)))
+            .collect();
+        
+        if str_arg(args, "orderby", "date") == "title" {
+            selected.sort_by(|a, b| a.title.cmp(&b.title));
+        } else {
+            selected.sort_by_key(|post| post.date);
+        }
+        if is_descending(args, true) {
+            selected.reverse();
+        }
+        
+        let amount = usize_arg(args, "amount", 6);
+        if amount > 0 {
+            selected.truncate(amount);
+        }
+        
+        let class = str_arg(args, "class", "post");
+        if is_list_style(args) {
+            let mut html = format!("<ul class=\"{}-list\">", class);
+            for post in selected {
+                html.push_str(&format!(
+                    "<li class=\"{0}-list-item\"><a class=\"{0}-list-link\" href=\"{1}\">{2}</a></li>",
+                    class, self.urls.url_for_page(&post.path), escape(&post.title)
+                ));
+            }
+            html.push_str("</ul>");
+            html
+        } else {
+            let separator = str_arg(args, "separator", ", ");
+            selected.iter()
+                .map(|post| format!("<a class=\"{}-link\" href=\"{}\">{}</a>", class, self.urls.url_for_page(&post.path), escape(&post.title)))
+                .collect::<Vec<_>>()
+                .join(&separator)
+        }
+    }
+    
+    /// 分页导航，首尾各保留 `end_size` 页，当前页两侧保留 `mid_size` 页，其余以 `space` 省略
+    ///
+    /// 参数：`base`、`current`、`total`、`format`、`prev_text`、`next_text`、`space`、`prev_next`、`end_size`、`mid_size`、`show_all`，
+    /// 模板中通常传入 `paginator(base=page.base, current=page.current, total=page.total)`。
+    pub fn paginator(&self, args: &Args) -> String {
+        let total = usize_arg(args, "total", 1);
+        if total <= 1 {
+            return String::new();
+        }
+        
+        let current = usize_arg(args, "current", 1).clamp(1, total);
+        let base = str_arg(args, "base", "");
+        let pagination_dir = self.config.pagination_dir.as_deref().unwrap_or("page").trim_matches('/').to_string();
+        let format = str_arg(args, "format", &format!("{}/%d/", pagination_dir));
+        let link = |page: usize| {
+            let path = if page == 1 { base.clone() } else { format!("{}{}", base, format.replace("%d", &page.to_string())) };
+            self.urls.url_for(&path, None)
+        };
+        
+        let end_size = usize_arg(args, "end_size", 1);
+        let mid_size = usize_arg(args, "mid_size", 2);
+        let show_all = bool_arg(args, "show_all", false);
+        let prev_next = bool_arg(args, "prev_next", true);
+        let space = str_arg(args, "space", "&hellip;");
+        
+        let mut html = String::new();
+        if prev_next && current > 1 {
+            html.push_str(&format!("<a class=\"extend prev\" rel=\"prev\" href=\"{}\">{}</a>", link(current - 1), str_arg(args, "prev_text", "Prev")));
+        }
+        
+        let mut last_shown = 0;
+        for page in 1..=total {
+            let visible = show_all
+                || page <= end_size
+                || page + end_size > total
+                || (page + mid_size >= current && page <= current + mid_size);
+            if !visible {
+                continue;
+            }
+            if page > last_shown + 1 && !space.is_empty() {
+                html.push_str(&format!("<span class=\"space\">{}</span>", space));
+            }
+            if page == current {
+                html.push_str(&format!("<span class=\"page-number current\">{}</span>", page));
+            } else {
+                html.push_str(&format!("<a class=\"page-number\" href=\"{}\">{}</a>", link(page), page));
+            }
+            last_shown = page;
+        }
+        if last_shown < total && !space.is_empty() {
+            html.push_str(&format!("<span class=\"space\">{}</span>", space));
+        }
+        
+        if prev_next && current < total {
+            html.push_str(&format!("<a class=\"extend next\" rel=\"next\" href=\"{}\">{}</a>", link(current + 1), str_arg(args, "next_text", "Next")));
+        }
+        html
+    }
+    
+    /// Open Graph 和 Twitter Card 元数据
+    ///
+    /// 参数：`title`、`type`、`url`、`image`、`description`、`site_name`、`date`、`updated`、`tags`、
+    /// `twitter_card`、`twitter_id`、`twitter_site`；`url` 默认为当前页面，其余未指定时使用站点配置。
+    pub fn open_graph(&self, args: &Args) -> String {
+        let title = str_arg(args, "title", &self.config.title);
+        let og_type = str_arg(args, "type", "website");
+        let path = args.get("url").and_then(Value::as_str).map(str::to_string)
+            .or_else(|| current_path().map(|path| path.trim_end_matches("index.html").to_string()))
+            .unwrap_or_default();
+        let url = self.urls.full_url_for(&path);
+        let description = args.get("description").and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| self.config.description.clone())
+            .map(|description| truncate(&strip_html(&description), 200))
+            .unwrap_or_default();
+        let site_name = str_arg(args, "site_name", &self.config.title);
+        
+        let mut meta: Vec<(&str, &str, String)> = Vec::new();
+        if !description.is_empty() {
+            meta.push(("name", "description", description.clone()));
+        }
+        meta.push(("property", "og:type", og_type));
+        meta.push(("property", "og:title", title.clone()));
+        meta.push(("property", "og:url", url));
+        meta.push(("property", "og:site_name", site_name));
+        if !description.is_empty() {
+            meta.push(("property", "og:description", description.clone()));
+        }
+        if let Some(language) = &self.config.language {
+            meta.push(("property", "og:locale", language.replace('-', "_")));
+        }
+        
+        let images: Vec<String> = match args.get("image") {
+            Some(Value::String(image)) => vec![image.clone()],
+            Some(Value::Array(images)) => images.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        for image in &images {
+            meta.push(("property", "og:image", self.urls.full_url_for(image)));
+        }
+        
+        if let Some(date) = args.get("date").and_then(Value::as_str) {
+            meta.push(("property", "article:published_time", date.to_string()));
+        }
+        if let Some(updated) = args.get("updated").and_then(Value::as_str) {
+            meta.push(("property", "article:modified_time", updated.to_string()));
+        }
+        if let Some(author) = &self.config.author {
+            meta.push(("property", "article:author", author.clone()));
+        }
+        if let Some(Value::Array(tags)) = args.get("tags") {
+            for tag in tags.iter().filter_map(Value::as_str) {
+                meta.push(("property", "article:tag", tag.to_string()));
+            }
+        }
+        
+        meta.push(("name", "twitter:card", str_arg(args, "twitter_card", "summary")));
+        meta.push(("name", "twitter:title", title));
+        if !description.is_empty() {
+            meta.push(("name", "twitter:description", description));
+        }
+        if let Some(image) = images.first() {
+            meta.push(("name", "twitter:image", self.urls.full_url_for(image)));
+        }
+        if let Some(id) = args.get("twitter_id").and_then(Value::as_str) {
+            meta.push(("name", "twitter:creator", format!("@{}", id.trim_start_matches('@'))));
+        }
+        if let Some(site) = args.get("twitter_site").and_then(Value::as_str) {
+            meta.push(("name", "twitter:site", format!("@{}", site.trim_start_matches('@'))));
+        }
+        
+        meta.iter()
+            .map(|(attr, key, content)| format!("<meta {}=\"{}\" content=\"{}\">", attr, key, escape(content)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    
+    /// 按参数排序并截取的标签及其文章数
+    fn sorted_tags(&self, args: &Args, default_orderby: &str, default_descending: bool) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for post in self.posts.read().unwrap().iter() {
+            for tag in &post.tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        
+        let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+        if str_arg(args, "orderby", default_orderby) == "count" {
+            tags.sort_by_key(|(_, count)| *count);
+        }
+        if is_descending(args, default_descending) {
+            tags.reverse();
+        }
+        
+        let amount = usize_arg(args, "amount", 0);
+        if amount > 0 {
+            tags.truncate(amount);
+        }
+        tags
+    }
+    
+    /// 根据文章的分类层级构建分类树，`paths` 为每篇文章的分类列表
+    fn category_tree(paths: &[&[String]]) -> Vec<CategoryNode> {
+        let mut groups: BTreeMap<&str, Vec<&[String]>> = BTreeMap::new();
+        for path in paths {
+            if let Some((first, rest)) = path.split_first() {
+                groups.entry(first.as_str()).or_default().push(rest);
+            }
+        }
+        
+        groups.into_iter()
+            .map(|(name, rests)| {
+                let children: Vec<&[String]> = rests.iter().copied().filter(|rest| !rest.is_empty()).collect();
+                CategoryNode {
+                    name: name.to_string(),
+                    count: rests.len(),
+                    children: Self::category_tree(&children),
+                }
+            })
+            .collect()
+    }
+    
+    fn sort_categories(nodes: &mut [CategoryNode], orderby: &str, descending: bool) {
+        if orderby == "count" {
+            nodes.sort_by_key(|node| node.count);
+        }
+        if descending {
+            nodes.reverse();
+        }
+        for node in nodes {
+            Self::sort_categories(&mut node.children, orderby, descending);
+        }
+    }
+    
+    fn category_list(&self, nodes: &[CategoryNode], class: &str, show_count: bool, depth: usize, level: usize) -> String {
+        let list_class = if level == 1 { format!("{}-list", class) } else { format!("{}-list-child", class) };
+        let mut html = format!("<ul class=\"{}\">", list_class);
+        for node in nodes {
+            html.push_str(&format!(
+                "<li class=\"{0}-list-item\"><a class=\"{0}-list-link\" href=\"{1}\">{2}</a>",
+                class, self.urls.url_for_category(&node.name), escape(&node.name)
+            ));
+            if show_count {
+                html.push_str(&format!("<span class=\"{}-list-count\">{}</span>", class, node.count));
+            }
+            if !node.children.is_empty() && (depth == 0 || level < depth) {
+                html.push_str(&self.category_list(&node.children, class, show_count, depth, level + 1));
+            }
+            html.push_str("</li>");
+        }
+        html.push_str("</ul>");
+        html
+    }
+    
+    fn category_links(&self, nodes: &[CategoryNode], class: &str, show_count: bool, depth: usize, level: usize, links: &mut Vec<String>) {
+        for node in nodes {
+            let count = if show_count { format!("<span class=\"{}-count\">{}</span>", class, node.count) } else { String::new() };
+            links.push(format!("<a class=\"{}-link\" href=\"{}\">{}{}</a>", class, self.urls.url_for_category(&node.name), escape(&node.name), count));
+            if depth == 0 || level < depth {
+                self.category_links(&node.children, class, show_count, depth, level + 1, links);
+            }
+        }
+    }
+}
+
+/// 读取字符串参数，未指定时使用默认值
+fn str_arg(args: &Args, name: &str, default: &str) -> String {
+    match args.get(name) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => default.to_string(),
+    }
+}
+
+/// 读取布尔参数，未指定时使用默认值
+fn bool_arg(args: &Args, name: &str, default: bool) -> bool {
+    args.get(name).and_then(Value::as_bool).unwrap_or(default)
+}
+
+/// 读取非负整数参数，未指定时使用默认值
+fn usize_arg(args: &Args, name: &str, default: usize) -> usize {
+    args.get(name).and_then(Value::as_u64).map(|n| n as usize).unwrap_or(default)
+}
+
+/// 读取数字参数，未指定时使用默认值
+fn f64_arg(args: &Args, name: &str, default: f64) -> f64 {
+    args.get(name).and_then(Value::as_f64).unwrap_or(default)
+}
+
+/// `order` 参数是否为降序，与 Hexo 一致支持 `1`、`-1` 和 `asc`、`desc`
+fn is_descending(args: &Args, default: bool) -> bool {
+    match args.get("order") {
+        Some(Value::Number(n)) => n.as_i64().is_some_and(|n| n < 0),
+        Some(Value::String(s)) => s.eq_ignore_ascii_case("desc") || s == "-1",
+        _ => default,
+    }
+}
+
+/// `style` 参数是否为列表样式，`style=false` 或 `style="none"` 时输出以分隔符连接的链接
+fn is_list_style(args: &Args) -> bool {
+    match args.get("style") {
+        Some(Value::Bool(list)) => *list,
+        Some(Value::String(style)) => style == "list",
+        _ => true,
+    }
+}
+
+/// 解析 `#rgb` 或 `#rrggbb` 格式的颜色
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().trim_start_matches('#');
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    
+    let mut rgb = [0u8; 3];
+    for (i, channel) in rgb.iter_mut().enumerate() {
+        *channel = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(rgb)
+}
+
+/// 在两个颜色之间按比例插值
+fn mix_color(start: [u8; 3], end: [u8; 3], ratio: f64) -> String {
+    let channel = |i: usize| (start[i] as f64 + (end[i] as f64 - start[i] as f64) * ratio).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(0), channel(1), channel(2))
+}
+
+/// 转义 HTML 特殊字符，与 Tera 的 `escape_html` 不同，保留 `/` 以便输出可读的链接
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 去掉 HTML 标签
+pub(crate) fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 截取前 `max` 个字符
+pub(crate) fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}...", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+    
+    fn post(title: &str, date: (i32, u32, u32), categories: &[&str], tags: &[&str]) -> Post {
+        Post {
+            title: title.to_string(),
+            date: Utc.with_ymd_and_hms(date.0, date.1, date.2, 0, 0, 0).unwrap(),
+            path: format!("posts/{}/index.html", title),
+            categories: categories.iter().map(|s| s.to_string()).collect(),
+            tags: tags.iter().map(|s| s.to_string()).collect(),
+            ..Post::default()
+        }
+    }
+    
+    fn helpers() -> SiteHelpers {
+        let helpers = SiteHelpers::new(&Config::default());
+        helpers.set_posts(&[
+            post("a", (2023, 1, 5), &["dev", "rust"], &["rust", "cli"]),
+            post("b", (2023, 2, 1), &["dev"], &["rust"]),
+            post("c", (2024, 3, 1), &["life"], &[]),
+        ]);
+        helpers
+    }
+    
+    fn args(value: Value) -> Args {
+        serde_json::from_value(value).unwrap()
+    }
+    
+    #[test]
+    fn test_list_helpers() {
+        let site = helpers();
+        
+        let categories = site.list_categories(&Args::new());
+        assert!(categories.contains("<a class=\"category-list-link\" href=\"/categories/dev/\">dev</a><span class=\"category-list-count\">2</span><ul class=\"category-list-child\">"));
+        
+        let tags = site.list_tags(&args(json!({"orderby": "count", "order": -1, "style": "none", "show_count": false})));
+        assert_eq!(tags, "<a class=\"tag-link\" href=\"/tags/rust/\" rel=\"tag\">rust</a>, <a class=\"tag-link\" href=\"/tags/cli/\" rel=\"tag\">cli</a>");
+        
+        let cloud = site.tagcloud(&args(json!({"color": true, "start_color": "#000", "end_color": "#fff"})));
+        assert!(cloud.contains("style=\"font-size: 20px; color: #ffffff\">rust</a>"));
+        assert!(cloud.contains("style=\"font-size: 10px; color: #000000\">cli</a>"));
+        
+        let archives = site.list_archives(&args(json!({"type": "yearly"})));
+        assert!(archives.find("2024").unwrap() < archives.find("2023").unwrap());
+        
+        let posts = site.list_posts(&args(json!({"tag": "rust", "amount": 1})));
+        assert_eq!(posts, "<ul class=\"post-list\"><li class=\"post-list-item\"><a class=\"post-list-link\" href=\"/posts/b/\">b</a></li></ul>");
+    }
+    
+    #[test]
+    fn test_paginator_ellipsis() {
+        let site = helpers();
+        let html = site.paginator(&args(json!({"base": "tags/rust/", "current": 5, "total": 10})));
+        
+        assert!(html.starts_with("<a class=\"extend prev\" rel=\"prev\" href=\"/tags/rust/page/4/\">Prev</a><a class=\"page-number\" href=\"/tags/rust/\">1</a><span class=\"space\">&hellip;</span>"));
+        assert!(html.contains("<span class=\"page-number current\">5</span>"));
+        assert!(html.contains("<a class=\"page-number\" href=\"/tags/rust/page/7/\">7</a><span class=\"space\">&hellip;</span><a class=\"page-number\" href=\"/tags/rust/page/10/\">10</a>"));
+        assert!(!html.contains(">8<"));
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Context, Result};
+use serde_yaml::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 主题继承链的最大深度，防止配置错误导致过深的查找
+const MAX_DEPTH: usize = 8;
+
+/// 解析主题继承链，子主题在前、父主题在后
+///
+/// 主题在 `_config.yml` 中通过 `extends: <parent>` 声明父主题，
+/// 父主题位于与子主题相同的 `themes/` 目录下。
+pub fn resolve_theme_chain(theme_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut chain = vec![theme_dir.to_path_buf()];
+    
+    while let Some(parent) = parent_theme(chain.last().unwrap())? {
+        let themes_dir = theme_dir.parent().unwrap_or(theme_dir);
+        let parent_dir = themes_dir.join(&parent);
+        
+        if chain.contains(&parent_dir) {
+            return Err(anyhow!("主题继承存在循环: {} 继承 {}", chain.last().unwrap().display(), parent));
+        }
+        if !parent_dir.exists() {
+            return Err(anyhow!("父主题不存在: {}", parent_dir.display()));
+        }
+        if chain.len() >= MAX_DEPTH {
+            return Err(anyhow!("主题继承层级过深（最多 {} 层）", MAX_DEPTH));
+        }
+        
+        chain.push(parent_dir);
+    }
+    
+    Ok(chain)
+}
+
+/// 读取主题配置中声明的父主题名称
+fn parent_theme(theme_dir: &Path) -> Result<Option<String>> {
+    let config_path = theme_dir.join("_config.yml");
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    
+    let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("读取主题配置失败: {}", config_path.display()))?;
+    let config: Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("解析主题配置失败: {}", config_path.display()))?;
+    
+    Ok(config.get("extends")
+        .and_then(Value::as_str)
+        .map(|parent| parent.trim().to_string())
+        .filter(|parent| !parent.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_resolve_theme_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes_dir = dir.path();
+        for (name, config) in [("child", "extends: base\n"), ("base", "menu: {}\n"), ("loop", "extends: loop\n")] {
+            fs::create_dir_all(themes_dir.join(name)).unwrap();
+            fs::write(themes_dir.join(name).join("_config.yml"), config).unwrap();
+        }
+        
+        let chain = resolve_theme_chain(&themes_dir.join("child")).unwrap();
+        assert_eq!(chain, vec![themes_dir.join("child"), themes_dir.join("base")]);
+        assert!(resolve_theme_chain(&themes_dir.join("loop")).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{anyhow, Context, Result};
+use serde_yaml::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use crate::theme::engine::TemplateEngineKind;
+
+/// `theme new` 生成的主题骨架，包含生成器使用的所有模板，路径相对于主题目录
+const SKELETON_FILES: &[(&str, &str)] = &[
+    ("_config.yml", include_str!("../../embed/theme/skeleton/_config.yml")),
+    ("layout/layout.html", include_str!("../../embed/theme/skeleton/layout/layout.html")),
+    ("layout/_partial/post-list.html", include_str!("../../embed/theme/skeleton/layout/_partial/post-list.html")),
+    ("layout/index.html", include_str!("../../embed/theme/skeleton/layout/index.html")),
+    ("layout/post.html", include_str!("../../embed/theme/skeleton/layout/post.html")),
+    ("layout/category.html", include_str!("../../embed/theme/skeleton/layout/category.html")),
+    ("layout/tag.html", include_str!("../../embed/theme/skeleton/layout/tag.html")),
+    ("layout/archive.html", include_str!("../../embed/theme/skeleton/layout/archive.html")),
+    ("layout/categories.html", include_str!("../../embed/theme/skeleton/layout/categories.html")),
+    ("layout/tags.html", include_str!("../../embed/theme/skeleton/layout/tags.html")),
+    ("source/css/style.css", include_str!("../../embed/theme/skeleton/source/css/style.css")),
+];
+
+/// 压缩包主题支持的扩展名
+const ARCHIVE_EXTENSIONS: &[&str] = &[".tar.gz", ".tgz", ".tar.bz2", ".tar.xz", ".tar"];
+
+/// 已安装主题的信息
+#[derive(Debug, Clone)]
+pub struct ThemeInfo {
+    /// 主题名，即 `themes/` 下的目录名
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    /// 父主题
+    pub extends: Option<String>,
+    /// 模板使用的引擎
+    pub engine: TemplateEngineKind,
+}
+
+impl ThemeInfo {
+    /// 读取主题信息，优先使用主题 `_config.yml` 中的字段，其次是 `package.json`（Hexo 主题）
+    pub fn load(theme_dir: &Path) -> Result<Self> {
+        let name = theme_dir.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("无效的主题目录: {}", theme_dir.display()))?;
+        
+        let config: Value = match fs::read_to_string(theme_dir.join("_config.yml")) {
+            Ok(content) => serde_yaml::from_str(&content)
+                .with_context(|| format!("解析主题配置失败: {}", theme_dir.join("_config.yml").display()))?,
+            Err(_) => Value::Null,
+        };
+        let package: serde_json::Value = fs::read_to_string(theme_dir.join("package.json")).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        
+        let field = |key: &str| {
+            config.get(key).and_then(Value::as_str)
+                .or_else(|| package.get(key).and_then(|value| value.as_str().or_else(|| value.get("name")?.as_str())))
+                .map(str::to_string)
+                .filter(|value| !value.is_empty())
+        };
+        
+        Ok(Self {
+            name,
+            version: field("version"),
+            description: field("description"),
+            author: field("author"),
+            extends: config.get("extends").and_then(Value::as_str).map(str::to_string),
+            engine: TemplateEngineKind::detect(&[theme_dir.join("layout")]),
+        })
+    }
+}
+
+/// 列出 `themes_dir` 中的主题，按名称排序，包含 `layout/` 目录的子目录才视为主题
+pub fn list_themes(themes_dir: &Path) -> Result<Vec<ThemeInfo>> {
+    if !themes_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    
+    let mut themes = Vec::new();
+    for entry in fs::read_dir(themes_dir)? {
+        let path = entry?.path();
+        if !path.join("layout").is_dir() {
+            continue;
+        }
+        match ThemeInfo::load(&path) {
+            Ok(theme) => themes.push(theme),
+            Err(e) => warn!("读取主题信息失败: {}", e),
+        }
+    }
+    
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(themes)
+}
+
+/// 主题来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ThemeSource {
+    /// 本地目录，直接复制
+    Directory(PathBuf),
+    /// 压缩包，使用 `tar` 解压
+    Archive(PathBuf),
+    /// git 仓库，使用 `git clone` 获取，本地仓库目录也按 git 仓库处理
+    Git(String),
+}
+
+impl ThemeSource {
+    fn parse(source: &str) -> Result<Self> {
+        let path = Path::new(source);
+        if path.is_dir() {
+            return Ok(if path.join(".git").exists() {
+                Self::Git(source.to_string())
+            } else {
+                Self::Directory(path.to_path_buf())
+            });
+        }
+        if path.is_file() && archive_stem(source).is_some() {
+            return Ok(Self::Archive(path.to_path_buf()));
+        }
+        if source.contains("://") || source.starts_with("git@") || source.ends_with(".git") {
+            return Ok(Self::Git(source.to_string()));
+        }
+        Err(anyhow!("无法识别的主题来源: {}（支持本地目录、压缩包和 git 仓库地址）", source))
+    }
+    
+    /// 根据来源推断主题名，去掉 `.git`、压缩包扩展名和 `hexo-theme-` 前缀
+    fn default_name(source: &str) -> Option<String> {
+        let last = source.trim_end_matches(['/', '\\']).rsplit(['/', '\\', ':']).next()?;
+        let stem = archive_stem(last).or_else(|| last.strip_suffix(".git")).unwrap_or(last);
+        let name = ["rust-hexo-theme-", "hexo-theme-"].iter()
+            .find_map(|prefix| stem.strip_prefix(prefix))
+            .unwrap_or(stem);
+        (!name.is_empty()).then(|| name.to_string())
+    }
+}
+
+/// 去掉压缩包扩展名后的文件名，不是压缩包时返回 `None`
+fn archive_stem(file_name: &str) -> Option<&str> {
+    ARCHIVE_EXTENSIONS.iter().find_map(|ext| file_name.strip_suffix(ext))
+}
+
+/// 从本地目录、压缩包或 git 仓库安装主题到 `themes_dir/<name>`，返回主题目录
+///
+/// 未指定 `name` 时根据来源推断；目标目录已存在时只有 `force` 为真才会替换。新主题获取并确认有效后
+/// 才替换旧主题，因此安装失败时旧主题保持不变，来源也可以位于旧主题目录之中。
+pub fn install_theme(themes_dir: &Path, source: &str, name: Option<&str>, force: bool) -> Result<PathBuf> {
+    let theme_source = ThemeSource::parse(source)?;
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => ThemeSource::default_name(source)
+            .ok_or_else(|| anyhow!("无法从 {} 推断主题名，请使用 --name 指定", source))?,
+    };
+    check_theme_name(&name)?;
+    
+    let theme_dir = themes_dir.join(&name);
+    if theme_dir.exists() && !force {
+        return Err(anyhow!("主题 {} 已存在: {}，使用 --force 替换", name, theme_dir.display()));
+    }
+    fs::create_dir_all(themes_dir)?;
+    
+    // 先安装到临时目录，确认是有效的主题后再移动到目标位置
+    let staging_dir = themes_dir.join(format!(".{}.installing", name));
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    let result = fetch_theme(&theme_source, &staging_dir)
+        .and_then(|_| {
+            let root = theme_root(&staging_dir)
+                .ok_or_else(|| anyhow!("{} 不是有效的主题：缺少 layout 目录", source))?;
+            replace_dir(&root, &theme_dir)
+        });
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    result?;
+    
+    info!("已安装主题 {} 到 {}", name, theme_dir.display());
+    Ok(theme_dir)
+}
+
+/// 用 `source` 替换 `dest`，替换失败时恢复原来的 `dest`
+fn replace_dir(source: &Path, dest: &Path) -> Result<()> {
+    if !dest.exists() {
+        fs::rename(source, dest)?;
+        return Ok(());
+    }
+    
+    let name = dest.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let backup = dest.with_file_name(format!(".{}.old", name));
+    if backup.exists() {
+        fs::remove_dir_all(&backup)?;
+    }
+    fs::rename(dest, &backup)?;
+    if let Err(e) = fs::rename(source, dest) {
+        fs::rename(&backup, dest)?;
+        return Err(e.into());
+    }
+    fs::remove_dir_all(&backup)?;
+    Ok(())
+}
+
+/// 检查主题名，主题名用作 `themes/` 下的目录名，不能包含路径分隔符或 `..`
+fn check_theme_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(anyhow!("无效的主题名: {}", name));
+    }
+    Ok(())
+}
+
+/// 将主题来源的内容获取到 `dest`
+fn fetch_theme(source: &ThemeSource, dest: &Path) -> Result<()> {
+    match source {
+        ThemeSource::Directory(dir) => copy_dir(dir, dest),
+        ThemeSource::Archive(archive) => {
+            fs::create_dir_all(dest)?;
+            run(Command::new("tar").arg("-xf").arg(archive).arg("-C").arg(dest), "tar")
+        }
+        ThemeSource::Git(url) => {
+            let mut command = Command::new("git");
+            command.arg("clone");
+            // 本地仓库不支持浅克隆
+            if !Path::new(url).exists() {
+                command.args(["--depth", "1"]);
+            }
+            // `--` 之后的参数不会被当作选项，避免以 `-` 开头的来源被解析为 git 参数
+            run(command.arg("--").arg(url).arg(dest), "git")
+        }
+    }
+}
+
+/// 执行外部命令，失败时返回其错误输出
+fn run(command: &mut Command, program: &str) -> Result<()> {
+    let output = command.output()
+        .with_context(|| format!("无法执行 {}，请确认已安装", program))?;
+    if !output.status.success() {
+        return Err(anyhow!("{} 执行失败: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// 复制目录，跳过 `.git`
+fn copy_dir(source: &Path, dest: &Path) -> Result<()> {
+    for entry in WalkDir::new(source).into_iter().filter_entry(|e| e.file_name() != ".git") {
+        let entry = entry?;
+        let target = dest.join(entry.path().strip_prefix(source)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// 找到包含 `layout/` 的主题根目录，压缩包通常会多包一层目录
+fn theme_root(dir: &Path) -> Option<PathBuf> {
+    if dir.join("layout").is_dir() {
+        return Some(dir.to_path_buf());
+    }
+    
+    let entries: Vec<PathBuf> = fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    match entries.as_slice() {
+        [single] if single.join("layout").is_dir() => Some(single.clone()),
+        _ => None,
+    }
+}
+
+/// 在 `themes_dir/<name>` 创建新主题的骨架，包含生成器使用的所有模板，返回主题目录
+pub fn new_theme(themes_dir: &Path, name: &str) -> Result<PathBuf> {
+    check_theme_name(name)?;
+    let theme_dir = themes_dir.join(name);
+    if theme_dir.exists() {
+        return Err(anyhow!("主题目录已存在: {}", theme_dir.display()));
+    }
+    
+    for (path, content) in SKELETON_FILES {
+        let dest_path = theme_dir.join(path);
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest_path, content)?;
+    }
+    fs::create_dir_all(theme_dir.join("source").join("images"))?;
+    
+    info!("已创建主题 {}: {}", name, theme_dir.display());
+    Ok(theme_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_theme_source() {
+        assert_eq!(ThemeSource::default_name("https://github.com/user/hexo-theme-next.git").as_deref(), Some("next"));
+        assert_eq!(ThemeSource::default_name("git@github.com:user/landscape").as_deref(), Some("landscape"));
+        assert_eq!(ThemeSource::default_name("/tmp/downloads/cactus-1.0.tar.gz").as_deref(), Some("cactus-1.0"));
+        assert_eq!(ThemeSource::parse("https://example.com/theme.git").unwrap(), ThemeSource::Git("https://example.com/theme.git".to_string()));
+        assert!(ThemeSource::parse("no-such-theme").is_err());
+    }
+    
+    #[test]
+    fn test_install_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes_dir = dir.path().join("themes");
+        let source = new_theme(dir.path(), "hexo-theme-plain").unwrap();
+        
+        let theme_dir = install_theme(&themes_dir, source.to_str().unwrap(), None, false).unwrap();
+        assert_eq!(theme_dir, themes_dir.join("plain"));
+        assert!(install_theme(&themes_dir, source.to_str().unwrap(), None, false).is_err());
+        
+        // 主题名不能跳出 themes 目录
+        for name in ["../plain", "a/b", ".hidden"] {
+            assert!(install_theme(&themes_dir, source.to_str().unwrap(), Some(name), true).is_err());
+        }
+        assert!(!dir.path().join("plain").exists());
+    }
+    
+    #[test]
+    fn test_list_themes() {
+        let dir = tempfile::tempdir().unwrap();
+        new_theme(dir.path(), "plain").unwrap();
+        
+        let themes = list_themes(dir.path()).unwrap();
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].name, "plain");
+        assert_eq!(themes[0].version.as_deref(), Some("0.1.0"));
+        assert_eq!(themes[0].engine, TemplateEngineKind::Tera);
+    }
+    
+    #[test]
+    fn test_install_force_from_theme_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes_dir = dir.path().join("themes");
+        let theme_dir = new_theme(&themes_dir, "plain").unwrap();
+        
+        // 来源是将被替换的主题目录本身
+        fs::write(theme_dir.join("layout").join("extra.html"), "extra").unwrap();
+        install_theme(&themes_dir, theme_dir.to_str().unwrap(), Some("plain"), true).unwrap();
+        assert!(theme_dir.join("layout").join("extra.html").is_file());
+        assert_eq!(fs::read_dir(&themes_dir).unwrap().count(), 1);
+    }
+    
+    #[test]
+    fn test_install_force_invalid_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes_dir = dir.path().join("themes");
+        let theme_dir = new_theme(&themes_dir, "plain").unwrap();
+        fs::write(theme_dir.join("layout").join("extra.html"), "extra").unwrap();
+        
+        // 来源不是有效的主题时保留旧主题
+        let invalid = dir.path().join("invalid");
+        fs::create_dir_all(&invalid).unwrap();
+        assert!(install_theme(&themes_dir, invalid.to_str().unwrap(), Some("plain"), true).is_err());
+        assert!(theme_dir.join("layout").join("extra.html").is_file());
+        assert_eq!(fs::read_dir(&themes_dir).unwrap().count(), 1);
+    }
+}
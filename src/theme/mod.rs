@@ -1 +1,8 @@
-pub mod renderer;
\ No newline at end of file
+pub mod embedded;
+pub mod engine;
+pub mod helpers;
+pub mod inheritance;
+pub mod manager;
+pub mod partial;
+pub mod renderer;
+pub mod validate;
@@ -0,0 +1,178 @@
+use anyhow::{anyhow, Context as _, Result};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::theme::engine::{TemplateContext as Context, TemplateEngine};
+use crate::utils::url::current_path;
+
+thread_local! {
+    /// 当前正在渲染的页面上下文，局部模板在此基础上叠加自己的变量
+    static RENDER_CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+/// 在渲染页面期间执行 `f`，期间调用的 `partial` 可以访问页面上下文
+pub fn with_render_context<R>(context: &Context, f: impl FnOnce() -> R) -> R {
+    let previous = RENDER_CONTEXT.with(|current| current.replace(Some(context.clone())));
+    let result = f();
+    RENDER_CONTEXT.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// 局部模板渲染器，提供 `partial(name, locals, cache)` 模板函数
+///
+/// 局部模板位于主题的 `layout/_partial/` 目录，渲染时使用页面上下文加上 `locals` 中的变量。
+/// Tera 不支持对象字面量，因此除 `name`、`locals`、`cache` 以外的参数也作为局部变量传入。
+/// `cache=true` 的局部模板以模板名和 `locals` 为键，在一次生成中只渲染一次；开启 `relative_link`
+/// 时局部模板中的链接取决于页面所在的目录，键中还包含该目录。
+#[derive(Clone, Default)]
+pub struct PartialRenderer {
+    /// 已加载全部模板的模板引擎副本，模板函数无法访问注册它的引擎本身
+    engine: Arc<RwLock<Option<Arc<dyn TemplateEngine>>>>,
+    /// 已缓存的渲染结果
+    cache: Arc<Mutex<HashMap<String, String>>>,
+    /// 各局部模板的缓存命中次数
+    hits: Arc<Mutex<BTreeMap<String, usize>>>,
+    /// 站点是否生成相对链接
+    relative_link: bool,
+}
+
+impl PartialRenderer {
+    pub fn new(relative_link: bool) -> Self {
+        Self {
+            relative_link,
+            ..Self::default()
+        }
+    }
+    
+    /// 注册 `partial` 模板函数
+    pub fn register(&self, engine: &mut dyn TemplateEngine) {
+        let partials = self.clone();
+        engine.register_function("partial", Arc::new(move |args: &HashMap<String, Value>| partials.render(args)));
+    }
+    
+    /// 更新用于渲染局部模板的模板引擎，模板加载或重新加载后调用
+    pub fn set_engine(&self, engine: &dyn TemplateEngine) {
+        *self.engine.write().unwrap() = Some(Arc::from(engine.box_clone()));
+        self.clear_cache();
+    }
+    
+    /// 清空缓存和命中统计，每次生成开始时调用
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+        self.hits.lock().unwrap().clear();
+    }
+    
+    /// 本次生成中各局部模板的缓存命中次数
+    pub fn cache_hits(&self) -> BTreeMap<String, usize> {
+        self.hits.lock().unwrap().clone()
+    }
+    
+    /// 局部模板名对应的模板，`header` 和 `_partial/header.html` 都指向 `_partial/header.html`
+    pub fn template_name(name: &str) -> String {
+        let name = name.trim_start_matches('/');
+        let name = if name.starts_with("_partial/") { name.to_string() } else { format!("_partial/{}", name) };
+        if name.ends_with(".html") { name } else { format!("{}.html", name) }
+    }
+    
+    fn render(&self, args: &HashMap<String, Value>) -> Result<Value> {
+        let name = args.get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("缺少必要的参数: name"))?;
+        let template = Self::template_name(name);
+        let mut locals = match args.get("locals") {
+            Some(Value::Object(locals)) => locals.clone(),
+            Some(Value::Null) | None => serde_json::Map::new(),
+            Some(_) => return Err(anyhow!("locals 必须是对象")),
+        };
+        for (key, value) in args.iter().filter(|(key, _)| !matches!(key.as_str(), "name" | "locals" | "cache")) {
+            locals.insert(key.clone(), value.clone());
+        }
+        
+        let cache_key = args.get("cache")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+            .then(|| {
+                let page_dir = match current_path() {
+                    Some(path) if self.relative_link => path[..path.rfind('/').map_or(0, |index| index + 1)].to_string(),
+                    _ => String::new(),
+                };
+                format!("{}\n{}\n{}", template, page_dir, Value::Object(locals.clone()))
+            });
+        if let Some(key) = &cache_key {
+            if let Some(html) = self.cache.lock().unwrap().get(key) {
+                *self.hits.lock().unwrap().entry(template).or_default() += 1;
+                return Ok(Value::String(html.clone()));
+            }
+        }
+        
+        // 先取出引擎再渲染，嵌套的局部模板会再次获取读锁
+        let engine = self.engine.read().unwrap().clone()
+            .ok_or_else(|| anyhow!("模板尚未加载"))?;
+        let resolved = engine.resolve(&template)
+            .ok_or_else(|| anyhow!("局部模板不存在: {}", template))?;
+        
+        let mut context = RENDER_CONTEXT.with(|current| current.borrow().clone()).unwrap_or_default();
+        for (key, value) in &locals {
+            context.insert(key, value);
+        }
+        
+        let html = with_render_context(&context, || engine.render(&resolved, &context))
+            .with_context(|| format!("渲染局部模板 {} 失败", template))?;
+        
+        if let Some(key) = cache_key {
+            self.cache.lock().unwrap().insert(key, html.clone());
+        }
+        Ok(Value::String(html))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::engine::TemplateEngineKind;
+    use crate::utils::url::with_current_path;
+    
+    #[test]
+    fn test_partial_locals_and_cache() {
+        let partials = PartialRenderer::default();
+        let mut engine = TemplateEngineKind::Tera.create();
+        partials.register(engine.as_mut());
+        engine.load_templates(vec![
+            ("_partial/greeting.html".to_string(), "{{ greeting }}, {{ name }}".to_string()),
+            ("page.html".to_string(), "{{ partial(name='greeting', greeting='Hi', cache=true) }}|{{ partial(name='greeting', greeting='Hi', cache=true) }}".to_string()),
+        ]).unwrap();
+        partials.set_engine(engine.as_ref());
+        
+        let mut context = Context::new();
+        context.insert("name", "Ada");
+        let html = with_render_context(&context, || engine.render("page.html", &context)).unwrap();
+        
+        assert_eq!(html, "Hi, Ada|Hi, Ada");
+        assert_eq!(partials.cache_hits().get("_partial/greeting.html"), Some(&1));
+        assert_eq!(PartialRenderer::template_name("_partial/sidebar"), "_partial/sidebar.html");
+    }
+    
+    #[test]
+    fn test_partial_cache_relative_link() {
+        let partials = PartialRenderer::new(true);
+        let mut engine = TemplateEngineKind::Tera.create();
+        partials.register(engine.as_mut());
+        engine.register_function("here", Arc::new(|_: &HashMap<String, Value>| Ok(Value::from(current_path().unwrap_or_default()))));
+        engine.load_templates(vec![
+            ("_partial/nav.html".to_string(), "{{ here() | safe }}".to_string()),
+            ("page.html".to_string(), "{{ partial(name='nav', cache=true) | safe }}".to_string()),
+        ]).unwrap();
+        partials.set_engine(engine.as_ref());
+        
+        let context = Context::new();
+        let render = |page: &str| with_current_path(page, || with_render_context(&context, || engine.render("page.html", &context))).unwrap();
+        
+        // 不同目录的页面各自渲染，同一目录的页面共用缓存
+        assert_eq!(render("posts/a/index.html"), "posts/a/index.html");
+        assert_eq!(render("about/index.html"), "about/index.html");
+        assert_eq!(render("posts/a/print.html"), "posts/a/index.html");
+        assert_eq!(partials.cache_hits().get("_partial/nav.html"), Some(&1));
+    }
+}
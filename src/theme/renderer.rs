@@ -1,47 +1,170 @@
 use anyhow::{Context, Result, anyhow};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
 use std::path::{Path, PathBuf};
-use tera::{Context as TeraContext, Tera};
-use tracing::{debug, warn, info, error};
+use std::sync::Arc;
+use tracing::{debug, info, warn, error};
 use crate::models::config::Config;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use pulldown_cmark::{html, Options, Parser};
+use walkdir::WalkDir;
 use crate::plugins::PluginManager;
+use crate::models::types::Post;
+use crate::theme::embedded;
+use crate::theme::engine::{TemplateContext, TemplateEngine, TemplateEngineKind, TemplateFunction};
+use crate::theme::helpers::SiteHelpers;
+use crate::theme::inheritance::resolve_theme_chain;
+use crate::theme::partial::{with_render_context, PartialRenderer};
+use crate::utils::url::{with_current_path, UrlHelper};
 
 #[derive(Clone)]
 pub struct ThemeRenderer {
     /// 主题目录
     pub theme_dir: PathBuf,
+    /// 主题继承链，子主题在前、父主题在后（第一项为 `theme_dir`），使用内置主题时为空
+    pub theme_dirs: Vec<PathBuf>,
+    /// 主题目录不存在，使用编译进程序的默认主题
+    pub embedded: bool,
+    /// 主题使用的模板引擎类型
+    pub engine_kind: TemplateEngineKind,
     /// 模板引擎
-    pub tera: Tera,
+    engine: Box<dyn TemplateEngine>,
     /// 主题配置
     pub config: Config,
     /// 插件管理器
     pub plugin_manager: Option<PluginManager>,
+    /// 站点级模板覆盖目录，其中的模板优先于主题中的同名模板
+    pub override_dirs: Vec<PathBuf>,
+    /// 列表辅助函数使用的站点数据
+    helpers: SiteHelpers,
+    /// 局部模板渲染器
+    partials: PartialRenderer,
+    /// URL 生成器，与模板中的 URL 辅助函数共享资源清单
+    urls: UrlHelper,
 }
 
 impl ThemeRenderer {
-    /// 创建新的主题渲染器
+    /// 创建新的主题渲染器，使用站点配置中的主题
     pub fn new(base_dir: &Path, config: Config) -> Result<Self> {
         let theme = config.theme.as_ref().unwrap_or(&"default".to_string()).clone();
         let theme_dir = base_dir.join("themes").join(&theme);
-        
-        if !theme_dir.exists() {
-            return Err(anyhow!("主题目录不存在: {}", theme_dir.display()));
-        }
-        
-        let mut tera = Tera::new(&format!("{}/**/*.html", theme_dir.join("layout").display()))?;
+        Self::from_theme_dir(theme_dir, config)
+    }
+    
+    /// 从指定的主题目录创建主题渲染器
+    ///
+    /// `layout/` 下的所有模板只在这里加载一次，之后由 `reload_templates` 重新加载。
+    /// 模板引擎根据主题中模板文件的扩展名选择（`.html` 为 Tera，`.j2` 为 minijinja，`.hbs` 为 Handlebars）。
+    /// 主题声明了父主题时，先加载父主题的模板，再用子主题的同名模板覆盖。
+    /// 主题目录不存在时使用内置的默认主题。
+    pub fn from_theme_dir(theme_dir: PathBuf, config: Config) -> Result<Self> {
+        let embedded = !theme_dir.exists();
+        let theme_dirs = if embedded {
+            info!("主题目录不存在: {}，使用内置默认主题", theme_dir.display());
+            Vec::new()
+        } else {
+            resolve_theme_chain(&theme_dir)?
+        };
+        let layout_dirs: Vec<PathBuf> = theme_dirs.iter().map(|dir| dir.join("layout")).collect();
+        let engine_kind = TemplateEngineKind::detect(&layout_dirs);
+        debug!("主题 {} 使用 {} 模板引擎", theme_dir.display(), engine_kind);
         
         // 注册过滤器和函数
-        Self::register_filters(&mut tera);
-        Self::register_functions(&mut tera);
+        let mut engine = engine_kind.create();
+        let urls = UrlHelper::from_config(&config);
+        Self::register_filters(engine.as_mut());
+        Self::register_functions(engine.as_mut(), &urls);
+        let helpers = SiteHelpers::new(&config);
+        helpers.register(engine.as_mut());
+        let partials = PartialRenderer::new(config.relative_link.unwrap_or(false));
+        partials.register(engine.as_mut());
         
-        Ok(ThemeRenderer {
+        let mut renderer = ThemeRenderer {
             theme_dir,
-            tera,
+            theme_dirs,
+            embedded,
+            engine_kind,
+            engine,
             config,
             plugin_manager: None,
-        })
+            override_dirs: Vec::new(),
+            helpers,
+            partials,
+            urls,
+        };
+        renderer.load_templates()?;
+        
+        Ok(renderer)
+    }
+    
+    /// 设置站点级模板覆盖目录并加载其中的模板
+    ///
+    /// 靠后的目录优先级更高，不存在的目录会被忽略。
+    pub fn set_override_dirs(&mut self, dirs: Vec<PathBuf>) -> Result<()> {
+        self.override_dirs = dirs;
+        self.load_templates()
+    }
+    
+    /// 按优先级从低到高加载父主题（或内置主题）、子主题和站点覆盖目录中的模板，同名模板以后加载的为准
+    ///
+    /// 只加载属于当前模板引擎的模板文件。
+    fn load_templates(&mut self) -> Result<()> {
+        let theme_layouts = self.theme_dirs.iter()
+            .rev()
+            .map(|dir| dir.join("layout"));
+        let layers: Vec<PathBuf> = theme_layouts.chain(self.override_dirs.iter().cloned()).collect();
+        // 内置主题的模板被覆盖时同样记录
+        let root_layout = if self.embedded { None } else { layers.first().cloned() };
+        
+        let mut templates: BTreeMap<String, String> = BTreeMap::new();
+        if self.embedded {
+            templates.extend(embedded::layouts().map(|(name, source)| (name.to_string(), source.to_string())));
+        }
+        
+        for dir in layers.iter().filter(|dir| dir.is_dir()) {
+            for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if !path.is_file() || TemplateEngineKind::from_path(path) != Some(self.engine_kind) {
+                    continue;
+                }
+                if let Ok(rel_path) = path.strip_prefix(dir) {
+                    let name = rel_path.to_string_lossy().replace('\\', "/");
+                    if Some(dir) != root_layout.as_ref() && templates.contains_key(&name) {
+                        debug!("使用 {} 中的模板覆盖同名模板: {}", dir.display(), name);
+                    }
+                    let source = fs::read_to_string(path)
+                        .with_context(|| format!("读取模板失败: {}", path.display()))?;
+                    templates.insert(name, source);
+                }
+            }
+        }
+        
+        self.engine.load_templates(templates.into_iter().collect())
+            .with_context(|| format!("加载主题模板失败: {}", self.theme_dir.display()))?;
+        self.refresh_partials();
+        Ok(())
+    }
+    
+    /// 注册模板函数，插件提供的函数也通过这里注册
+    pub fn register_function(&mut self, name: &str, function: TemplateFunction) {
+        self.engine.register_function(name, function);
+        self.refresh_partials();
+    }
+    
+    /// 使局部模板使用当前加载的模板和已注册的函数，模板或函数变化后调用
+    fn refresh_partials(&self) {
+        self.partials.set_engine(self.engine.as_ref());
+    }
+    
+    /// 清空局部模板缓存，每次生成开始时调用
+    pub fn clear_partial_cache(&self) {
+        self.partials.clear_cache();
+    }
+    
+    /// 本次生成中各局部模板的缓存命中次数
+    pub fn partial_cache_hits(&self) -> BTreeMap<String, usize> {
+        self.partials.cache_hits()
     }
     
     /// 设置插件管理器
@@ -49,110 +172,122 @@ impl ThemeRenderer {
         self.plugin_manager = Some(plugin_manager);
     }
     
+    /// URL 生成器，通过它设置的资源清单对模板中的 `asset_url` 生效
+    pub fn urls(&self) -> &UrlHelper {
+        &self.urls
+    }
+    
+    /// 更新列表辅助函数（`list_posts`、`list_tags` 等）使用的文章
+    pub fn set_posts(&self, posts: &[Post]) {
+        self.helpers.set_posts(posts);
+    }
+    
     /// 注册模板过滤器
-    fn register_filters(tera: &mut Tera) {
+    fn register_filters(engine: &mut dyn TemplateEngine) {
         // 注册日期格式化过滤器
-        tera.register_filter("date_format", Self::date_format_filter);
+        engine.register_filter("date_format", Arc::new(Self::date_format_filter));
         // 注册Markdown过滤器
-        tera.register_filter("markdown", Self::markdown_filter);
+        engine.register_filter("markdown", Arc::new(Self::markdown_filter));
         // 其他过滤器...
     }
     
     /// 注册模板函数
-    fn register_functions(tera: &mut Tera) {
+    fn register_functions(engine: &mut dyn TemplateEngine, urls: &UrlHelper) {
         // 注册URL生成函数
-        tera.register_function("url_for", Self::url_for_function);
+        Self::register_url_functions(engine, urls.clone());
+        
+        // 注册日期格式化函数
+        engine.register_function("date", Arc::new(Self::date_function));
+        
+        // 注册默认值函数
+        engine.register_function("default", Arc::new(Self::default_function));
         
         // 注册字数统计函数
-        // tera.register_function("word_count", Self::word_count_function);
+        // engine.register_function("word_count", Arc::new(Self::word_count_function));
         
         // 注册阅读时间函数
-        // tera.register_function("reading_time", Self::reading_time_function);
+        // engine.register_function("reading_time", Arc::new(Self::reading_time_function));
         
         // 其他函数...
     }
     
     /// 渲染模板
     pub fn render(&self, template: &str, context: &HashMap<String, serde_yaml::Value>) -> Result<String> {
-        let mut tera_context = tera::Context::new();
+        let mut template_context = TemplateContext::new();
         
-        // 将YAML值转换为Tera值
+        // 将YAML值转换为模板值
         for (key, value) in context {
-            tera_context.insert(key, &tera_yaml_to_value(value)?);
+            template_context.insert(key.clone(), &yaml_to_value(value)?);
         }
         
         // 添加插件资源
-        if let Some(ref plugin_manager) = self.plugin_manager {
-            debug!("检查插件管理器...");
-            let plugins = match plugin_manager.get_all_plugins() {
-                Ok(p) => {
-                    info!("获取到 {} 个插件", p.len());
-                    p
-                },
-                Err(e) => {
-                    warn!("获取插件列表失败: {}", e);
-                    Vec::new()
-                }
-            };
-            
-            // 收集所有插件的资源
-            let mut head_resources = Vec::new();
-            let mut footer_resources = Vec::new();
-            for plugin in &plugins {
-                debug!("处理插件 {} 的资源", plugin.name());
-                // 获取所有资源
-                for resource in plugin.get_resources() {
-                    match resource.1 {
-                        crate::plugins::ResourceLocation::Head => head_resources.push(resource),
-                        crate::plugins::ResourceLocation::Footer => footer_resources.push(resource),
-                    }
-                }
-            }
-            tera_context.insert("plugin_head_resources", &head_resources);
-            tera_context.insert("plugin_footer_resources", &footer_resources);
-            
-            // 为特定插件添加标志
-            let mut plugin_enabled = HashMap::new();
-            for plugin in &plugins {
-                let name = plugin.name();
-                info!("启用插件 {} 在模板中", name);
-                // 使用插件名作为键（统一使用中横线格式，与配置名保持一致）
-                let config_name = if name.contains('_') {
-                    name.replace('_', "-")
-                } else {
-                    name.to_string()
-                };
-                plugin_enabled.insert(config_name, true);
-            }
-            
-            if !plugin_enabled.is_empty() {
-                info!("向模板上下文添加 {} 个插件", plugin_enabled.len());
-                tera_context.insert("plugins", &plugin_enabled);
-            } else {
-                warn!("没有插件被添加到模板上下文");
-            }
-        } else {
-            warn!("没有可用的插件管理器");
-        }
+        self.insert_plugin_data(&mut template_context);
         
         // 渲染模板
-        match self.tera.render(template, &tera_context) {
+        match self.render_template(template, &template_context) {
             Ok(result) => Ok(result),
             Err(e) => {
-                error!("模板渲染失败: {}", e);
-                Err(anyhow!(e))
+                error!("模板渲染失败: {:#}", e);
+                Err(e)
             }
         }
     }
     
+    /// 向模板上下文添加插件数据：已启用插件的标志以及插件注入的头部/底部资源
+    pub fn insert_plugin_data(&self, context: &mut TemplateContext) {
+        let plugin_manager = match self.plugin_manager {
+            Some(ref plugin_manager) => plugin_manager,
+            None => {
+                debug!("没有可用的插件管理器");
+                return;
+            }
+        };
+        
+        let plugins = match plugin_manager.get_all_plugins() {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("获取插件列表失败: {}", e);
+                Vec::new()
+            }
+        };
+        
+        // 收集所有插件的资源
+        let mut head_resources = Vec::new();
+        let mut footer_resources = Vec::new();
+        for plugin in &plugins {
+            debug!("处理插件 {} 的资源", plugin.name());
+            for (resource, location) in plugin.get_resources() {
+                match location {
+                    crate::plugins::ResourceLocation::Head => head_resources.push(resource),
+                    crate::plugins::ResourceLocation::Footer => footer_resources.push(resource),
+                }
+            }
+        }
+        context.insert("plugin_head_resources", &head_resources);
+        context.insert("plugin_footer_resources", &footer_resources);
+        
+        // 为特定插件添加标志，使用插件名作为键（统一使用中横线格式，与配置名保持一致）
+        let mut plugin_enabled = HashMap::new();
+        for plugin in &plugins {
+            debug!("启用插件 {} 在模板中", plugin.name());
+            plugin_enabled.insert(plugin.name().replace('_', "-"), true);
+        }
+        context.insert("plugins", &plugin_enabled);
+    }
+    
     /// 获取可用的布局列表
     pub fn available_layouts(&self) -> Vec<String> {
-        self.tera.get_template_names().map(String::from).collect()
+        self.engine.template_names()
+    }
+    
+    /// 模板从上下文中读取的变量，模板引擎不支持分析时返回 `None`
+    pub fn referenced_variables(&self, template: &str) -> Option<BTreeSet<String>> {
+        self.engine.referenced_variables(template)
     }
     
-    /// 检查布局是否存在
+    /// 检查布局是否存在，`post.html` 也匹配其他模板引擎的 `post.j2`、`post.hbs` 等
     pub fn has_layout(&self, layout: &str) -> bool {
-        self.tera.get_template_names().any(|name| name == layout)
+        self.engine.resolve(layout).is_some()
     }
     
     /// 获取主题资源目录
@@ -160,29 +295,41 @@ impl ThemeRenderer {
         self.theme_dir.join("source")
     }
     
+    /// 获取主题继承链中的所有资源目录，子主题在前、父主题在后
+    pub fn source_dirs(&self) -> Vec<PathBuf> {
+        self.theme_dirs.iter().map(|dir| dir.join("source")).collect()
+    }
+    
     /// 从模板引擎中重新加载模板
     pub fn reload_templates(&mut self) -> Result<()> {
         debug!("Reloading theme templates...");
-        self.tera.full_reload()?;
-        Ok(())
+        self.load_templates()
     }
 
-    pub fn render_template(&self, template_name: &str, context: &tera::Context) -> Result<String> {
-        Ok(self.tera.render(template_name, context)?)
+    /// 使用已构建好的上下文渲染模板
+    pub fn render_template(&self, template_name: &str, context: &TemplateContext) -> Result<String> {
+        let name = self.engine.resolve(template_name).unwrap_or_else(|| template_name.to_string());
+        with_render_context(context, || self.engine.render(&name, context))
+            .with_context(|| format!("渲染模板 {} 失败", template_name))
+    }
+    
+    /// 渲染输出到 `path`（相对于站点根目录）的页面，URL 辅助函数以该页面为相对链接的起点
+    pub fn render_page(&self, template_name: &str, context: &TemplateContext, path: &str) -> Result<String> {
+        with_current_path(path, || self.render_template(template_name, context))
     }
 
-    fn date_format_filter(value: &tera::Value, args: &std::collections::HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    fn date_format_filter(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
         if let Some(date) = value.as_str().and_then(|s| DateTime::parse_from_rfc3339(s).ok()) {
             let format = args.get("format")
                 .and_then(|f| f.as_str())
                 .unwrap_or("%Y-%m-%d");
-            Ok(tera::Value::String(date.format(format).to_string()))
+            Ok(Value::String(date.format(format).to_string()))
         } else {
             Ok(value.clone())
         }
     }
 
-    fn markdown_filter(value: &tera::Value, _args: &std::collections::HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    fn markdown_filter(value: &Value, _args: &HashMap<String, Value>) -> Result<Value> {
         if let Some(text) = value.as_str() {
             let mut options = Options::empty();
             options.insert(Options::ENABLE_TABLES);
@@ -206,25 +353,147 @@ impl ThemeRenderer {
                 html_output
             };
 
-            Ok(tera::Value::String(html_output))
+            Ok(Value::String(html_output))
         } else {
             Ok(value.clone())
         }
     }
 
-    fn url_for_function(_args: &std::collections::HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
-        // TODO: 实现URL生成功能
-        Ok(tera::Value::String("/".to_string()))
+    /// 注册 URL 辅助函数：`url_for`、`full_url_for`、`relative_url`、`url_for_tag`、`url_for_category`、`asset_url`、`image`
+    fn register_url_functions(engine: &mut dyn TemplateEngine, urls: UrlHelper) {
+        let helper = urls.clone();
+        engine.register_function("url_for", Arc::new(move |args: &HashMap<String, Value>| {
+            let path = Self::string_arg(args, "path")?;
+            let from = args.get("from").and_then(|v| v.as_str());
+            Ok(Value::String(helper.url_for(&path, from)))
+        }));
+        
+        let helper = urls.clone();
+        engine.register_function("full_url_for", Arc::new(move |args: &HashMap<String, Value>| {
+            let path = Self::string_arg(args, "path")?;
+            Ok(Value::String(helper.full_url_for(&path)))
+        }));
+        
+        let helper = urls.clone();
+        engine.register_function("relative_url", Arc::new(move |args: &HashMap<String, Value>| {
+            let from = Self::string_arg(args, "from")?;
+            let to = Self::string_arg(args, "to")?;
+            Ok(Value::String(helper.relative_url(&from, &to)))
+        }));
+        
+        let helper = urls.clone();
+        engine.register_function("url_for_tag", Arc::new(move |args: &HashMap<String, Value>| {
+            let name = Self::string_arg(args, "name")?;
+            Ok(Value::String(helper.url_for_tag(&name)))
+        }));
+        
+        let helper = urls.clone();
+        engine.register_function("url_for_category", Arc::new(move |args: &HashMap<String, Value>| {
+            let name = Self::string_arg(args, "name")?;
+            Ok(Value::String(helper.url_for_category(&name)))
+        }));
+        
+        let helper = urls.clone();
+        engine.register_function("asset_url", Arc::new(move |args: &HashMap<String, Value>| {
+            let path = Self::string_arg(args, "path")?;
+            Ok(Value::String(helper.asset_url(&path)))
+        }));
+        
+        // 文章图片中宽度不小于 `width` 的最小尺寸，如 `{{ image(src='/images/a.jpg', width=480) }}`
+        engine.register_function("image", Arc::new(move |args: &HashMap<String, Value>| {
+            let src = Self::string_arg(args, "src")?;
+            let width = args.get("width")
+                .and_then(|width| width.as_u64().or_else(|| width.as_str()?.parse().ok()))
+                .map(|width| width as u32);
+            Ok(Value::String(urls.image_url(&src, width)))
+        }));
     }
-
+    
+    /// 读取字符串参数，数字等其他标量也转换为字符串
+    fn string_arg(args: &HashMap<String, Value>, name: &str) -> Result<String> {
+        match args.get(name) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Null) | None => Err(anyhow!(format!("缺少必要的参数: {}", name))),
+            Some(value) => Ok(value.to_string()),
+        }
+    }
+    
+    /// 格式化日期，支持 RFC3339、常见日期字符串和 Unix 时间戳
+    fn date_function(args: &HashMap<String, Value>) -> Result<Value> {
+        let value = match args.get("value") {
+            Some(v) => v,
+            None => return Err(anyhow!("缺少必要的参数: value"))
+        };
+        
+        let format = match args.get("format") {
+            Some(f) => match f.as_str() {
+                Some(s) => s,
+                None => return Err(anyhow!("format 必须是字符串"))
+            },
+            None => "%Y-%m-%d"
+        };
+        
+        // 处理不同类型的日期值
+        if let Some(date_str) = value.as_str() {
+            // 尝试解析为RFC3339格式
+            if let Ok(date) = DateTime::parse_from_rfc3339(date_str) {
+                return Ok(Value::String(date.format(format).to_string()));
+            }
+            
+            // 尝试解析为其他常见格式
+            let formats = [
+                "%Y-%m-%d %H:%M:%S",
+                "%Y-%m-%d",
+                "%Y/%m/%d %H:%M:%S",
+                "%Y/%m/%d",
+            ];
+            
+            for fmt in &formats {
+                if let Ok(date) = NaiveDateTime::parse_from_str(date_str, fmt) {
+                    return Ok(Value::String(date.and_utc().format(format).to_string()));
+                }
+            }
+            
+            // 返回原始字符串如果无法解析
+            return Ok(Value::String(date_str.to_string()));
+        }
+        
+        // 处理数字类型（Unix时间戳）
+        if let Some(date) = value.as_i64().and_then(|timestamp| DateTime::<Utc>::from_timestamp(timestamp, 0)) {
+            return Ok(Value::String(date.format(format).to_string()));
+        }
+        
+        // 如果无法解析，返回错误
+        Err(anyhow!("无法将value解析为日期时间"))
+    }
+    
+    /// 值为空时返回默认值
+    fn default_function(args: &HashMap<String, Value>) -> Result<Value> {
+        let value = match args.get("value") {
+            Some(v) => v,
+            None => return Err(anyhow!("缺少必要的参数: value"))
+        };
+        
+        let default_value = match args.get("default") {
+            Some(d) => d,
+            None => return Err(anyhow!("缺少必要的参数: default"))
+        };
+        
+        if value.is_null() || (value.is_string() && value.as_str().unwrap_or("").is_empty()) {
+            Ok(default_value.clone())
+        } else {
+            Ok(value.clone())
+        }
+    }
+    
     /// 计算内容的字数
-    fn word_count_function(args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    fn word_count_function(args: &HashMap<String, Value>) -> Result<Value> {
         let content = match args.get("content") {
             Some(val) => match val.as_str() {
                 Some(s) => s,
-                None => return Err(tera::Error::msg("无法将content转换为字符串")),
+                None => return Err(anyhow!("无法将content转换为字符串")),
             },
-            None => return Err(tera::Error::msg("缺少必要的参数: content")),
+            None => return Err(anyhow!("缺少必要的参数: content")),
         };
 
         // 计算英文单词和中文字符
@@ -238,17 +507,17 @@ impl ThemeRenderer {
         }).count();
 
         let total_count = english_words + chinese_chars;
-        Ok(tera::Value::Number(serde_json::Number::from(total_count as i64)))
+        Ok(Value::Number(serde_json::Number::from(total_count as i64)))
     }
 
     /// 估计内容的阅读时间（分钟）
-    fn reading_time_function(args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    fn reading_time_function(args: &HashMap<String, Value>) -> Result<Value> {
         let content = match args.get("content") {
             Some(val) => match val.as_str() {
                 Some(s) => s,
-                None => return Err(tera::Error::msg("无法将content转换为字符串")),
+                None => return Err(anyhow!("无法将content转换为字符串")),
             },
-            None => return Err(tera::Error::msg("缺少必要的参数: content")),
+            None => return Err(anyhow!("缺少必要的参数: content")),
         };
 
         // 计算字数
@@ -270,45 +539,45 @@ impl ThemeRenderer {
         
         // 使用from_f64方法或转换为整数
         match serde_json::Number::from_f64(reading_time) {
-            Some(num) => Ok(tera::Value::Number(num)),
-            None => Ok(tera::Value::Number(serde_json::Number::from(reading_time as u64)))
+            Some(num) => Ok(Value::Number(num)),
+            None => Ok(Value::Number(serde_json::Number::from(reading_time as u64)))
         }
     }
 }
 
-/// 将YAML值转换为Tera值
-fn tera_yaml_to_value(yaml: &serde_yaml::Value) -> Result<tera::Value> {
+/// 将YAML值转换为模板值
+fn yaml_to_value(yaml: &serde_yaml::Value) -> Result<Value> {
     Ok(match yaml {
-        serde_yaml::Value::Null => tera::Value::Null,
-        serde_yaml::Value::Bool(b) => tera::Value::Bool(*b),
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(*b),
         serde_yaml::Value::Number(n) => {
             if let Some(i) = n.as_i64() {
-                tera::Value::Number(serde_json::Number::from(i))
+                Value::Number(serde_json::Number::from(i))
             } else if let Some(f) = n.as_f64() {
-                tera::Value::Number(serde_json::Number::from_f64(f).unwrap_or_else(|| serde_json::Number::from(0)))
+                Value::Number(serde_json::Number::from_f64(f).unwrap_or_else(|| serde_json::Number::from(0)))
             } else {
-                tera::Value::Null
+                Value::Null
             }
         },
-        serde_yaml::Value::String(s) => tera::Value::String(s.clone()),
+        serde_yaml::Value::String(s) => Value::String(s.clone()),
         serde_yaml::Value::Sequence(seq) => {
-            let values: Vec<tera::Value> = seq.iter()
-                .map(tera_yaml_to_value)
+            let values: Vec<Value> = seq.iter()
+                .map(yaml_to_value)
                 .collect::<Result<Vec<_>>>()?;
-            tera::Value::Array(values)
+            Value::Array(values)
         },
         serde_yaml::Value::Mapping(map) => {
             let mut object = serde_json::Map::new();
             for (k, v) in map {
                 if let serde_yaml::Value::String(key) = k {
-                    object.insert(key.clone(), tera_yaml_to_value(v)?);
+                    object.insert(key.clone(), yaml_to_value(v)?);
                 }
             }
-            tera::Value::Object(object)
+            Value::Object(object)
         },
         serde_yaml::Value::Tagged(tagged) => {
             // 处理带标签的值，直接使用内部值
-            tera_yaml_to_value(&tagged.value)?
+            yaml_to_value(&tagged.value)?
         },
     })
 } 
\ No newline at end of file
@@ -0,0 +1,213 @@
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::models::config::Config;
+use crate::theme::engine::TemplateEngineKind;
+use crate::theme::inheritance::resolve_theme_chain;
+use crate::theme::renderer::ThemeRenderer;
+
+/// 主题必须提供的模板
+pub const REQUIRED_TEMPLATES: &[&str] = &["index", "post"];
+
+/// 生成器使用的其他模板，缺少时使用内置的简单页面
+pub const OPTIONAL_TEMPLATES: &[&str] = &["category", "tag", "archive", "categories", "tags"];
+
+/// 所有页面共享的顶层变量
+const GLOBAL_VARIABLES: &[&str] = &["site", "page", "now", "plugins", "plugin_head_resources", "plugin_footer_resources"];
+
+/// `site` 的字段
+const SITE_FIELDS: &[&str] = &["config", "title", "url", "author"];
+
+/// 文章页面中 `page` 的字段，即 `Post` 序列化后的字段
+const POST_FIELDS: &[&str] = &[
+    "title", "date", "updated", "comments", "layout", "content", "rendered_content", "source",
+    "path", "permalink", "excerpt", "url", "categories", "tags", "front_matter",
+];
+
+/// 列表页面中 `page` 的字段
+const LIST_FIELDS: &[&str] = &[
+    "type", "title", "posts", "current", "total", "prev", "next", "prev_link", "next_link", "current_url", "base",
+];
+
+/// 各列表模板额外提供的 `page` 字段
+const LIST_EXTRA_FIELDS: &[(&str, &[&str])] = &[
+    ("index", &[]),
+    ("category", &["category"]),
+    ("tag", &["tag"]),
+    ("archive", &["archives", "year", "month", "day"]),
+    ("categories", &["categories"]),
+    ("tags", &["tags"]),
+];
+
+/// 主题验证结果
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    /// 主题使用的模板引擎
+    pub engine: TemplateEngineKind,
+    /// 缺少的必需模板
+    pub missing_required: Vec<&'static str>,
+    /// 缺少的可选模板
+    pub missing_optional: Vec<&'static str>,
+    /// 模板加载或编译错误
+    pub errors: Vec<String>,
+    /// 各模板引用但生成器不提供的变量，`None` 表示模板引擎不支持检查
+    pub unknown_variables: Option<BTreeMap<String, Vec<String>>>,
+}
+
+impl ValidationReport {
+    /// 主题能否用于生成：必需模板齐全且所有模板都能编译
+    pub fn is_valid(&self) -> bool {
+        self.missing_required.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// 检查主题的模板是否齐全、能否编译，以及引用了哪些生成器不提供的变量
+///
+/// 主题继承的父主题中的模板同样计入。局部模板（以 `_` 开头的路径）可以使用调用时传入的变量，不检查变量。
+pub fn validate_theme(theme_dir: &Path) -> Result<ValidationReport> {
+    if !theme_dir.is_dir() {
+        return Err(anyhow!("主题目录不存在: {}", theme_dir.display()));
+    }
+    
+    let theme_dirs = resolve_theme_chain(theme_dir)?;
+    let layout_dirs: Vec<_> = theme_dirs.iter().map(|dir| dir.join("layout")).collect();
+    let engine = TemplateEngineKind::detect(&layout_dirs);
+    
+    // 按文件检查模板是否存在，不依赖编译结果
+    let mut stems = BTreeSet::new();
+    for dir in layout_dirs.iter().filter(|dir| dir.is_dir()) {
+        for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            if TemplateEngineKind::from_path(entry.path()) != Some(engine) {
+                continue;
+            }
+            if let Ok(rel_path) = entry.path().strip_prefix(dir) {
+                stems.insert(template_stem(&rel_path.to_string_lossy().replace('\\', "/")).to_string());
+            }
+        }
+    }
+    let missing = |names: &[&'static str]| names.iter().copied().filter(|name| !stems.contains(*name)).collect();
+    
+    let mut report = ValidationReport {
+        engine,
+        missing_required: missing(REQUIRED_TEMPLATES),
+        missing_optional: missing(OPTIONAL_TEMPLATES),
+        errors: Vec::new(),
+        unknown_variables: None,
+    };
+    
+    let renderer = match ThemeRenderer::from_theme_dir(theme_dir.to_path_buf(), Config::default()) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            report.errors.push(format!("{:#}", e));
+            return Ok(report);
+        }
+    };
+    
+    let mut unknown_variables = BTreeMap::new();
+    for name in renderer.available_layouts() {
+        if name.starts_with('_') || name.contains("/_") {
+            continue;
+        }
+        let Some(variables) = renderer.referenced_variables(&name) else {
+            return Ok(report);
+        };
+        
+        let page_fields = page_fields(template_stem(&name));
+        let unknown: BTreeSet<String> = variables.iter()
+            .filter(|variable| !is_provided(variable, &page_fields))
+            .map(|variable| checked_path(variable))
+            .collect();
+        if !unknown.is_empty() {
+            unknown_variables.insert(name, unknown.into_iter().collect());
+        }
+    }
+    report.unknown_variables = Some(unknown_variables);
+    
+    Ok(report)
+}
+
+/// 模板名去掉扩展名，如 `post.html.j2` 对应 `post`
+fn template_stem(name: &str) -> &str {
+    let file_start = name.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match name[file_start..].find('.') {
+        Some(i) => &name[..file_start + i],
+        None => name,
+    }
+}
+
+/// 模板中 `page` 的字段：列表模板对应列表页面，`layout` 可能被任何页面使用，其他模板按文章布局处理
+fn page_fields(stem: &str) -> BTreeSet<&'static str> {
+    if stem == "layout" {
+        return POST_FIELDS.iter()
+            .chain(LIST_FIELDS)
+            .chain(LIST_EXTRA_FIELDS.iter().flat_map(|(_, fields)| fields.iter()))
+            .copied()
+            .collect();
+    }
+    
+    match LIST_EXTRA_FIELDS.iter().find(|(name, _)| *name == stem) {
+        Some((_, extra)) => LIST_FIELDS.iter().chain(extra.iter()).copied().collect(),
+        None => POST_FIELDS.iter().copied().collect(),
+    }
+}
+
+/// 变量路径中被检查的部分，如 `page.next.title` 对应 `page.next`
+fn checked_path(variable: &str) -> String {
+    let depth = if variable.starts_with("page.") || variable.starts_with("site.") { 2 } else { 1 };
+    variable.split('.').take(depth).collect::<Vec<_>>().join(".")
+}
+
+/// 生成器是否提供该变量，只检查顶层变量以及 `page`、`site` 的直接字段
+fn is_provided(variable: &str, page_fields: &BTreeSet<&str>) -> bool {
+    let mut segments = variable.split('.');
+    let root = segments.next().unwrap_or_default();
+    match (root, segments.next()) {
+        ("page", Some(field)) => page_fields.contains(field),
+        ("site", Some(field)) => SITE_FIELDS.contains(&field),
+        _ => GLOBAL_VARIABLES.contains(&root),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    
+    /// 只有文章和标签模板的主题
+    fn theme() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("layout")).unwrap();
+        fs::write(dir.path().join("layout/post.html"), "{{ page.title }}{{ page.subtitle }}{{ page.subtitle.text }}{% for tag in page.tags %}{{ tag }}{% endfor %}").unwrap();
+        fs::write(dir.path().join("layout/tag.html"), "{{ page.tag }}{{ sidebar }}{% set n = 1 %}{{ n }}").unwrap();
+        dir
+    }
+    
+    #[test]
+    fn test_validate_missing_templates() {
+        let theme_dir = theme();
+        let report = validate_theme(theme_dir.path()).unwrap();
+        assert_eq!(report.missing_required, vec!["index"]);
+        assert!(!report.missing_optional.contains(&"tag"));
+        assert!(!report.is_valid());
+    }
+    
+    #[test]
+    fn test_validate_unknown_variables() {
+        let theme_dir = theme();
+        let unknown = validate_theme(theme_dir.path()).unwrap().unknown_variables.unwrap();
+        assert_eq!(unknown["post.html"], vec!["page.subtitle"]);
+        assert_eq!(unknown["tag.html"], vec!["sidebar"]);
+    }
+    
+    #[test]
+    fn test_validate_syntax_errors() {
+        let theme_dir = theme();
+        fs::write(theme_dir.path().join("layout/index.html"), "{% if %}").unwrap();
+        let report = validate_theme(theme_dir.path()).unwrap();
+        assert!(report.missing_required.is_empty());
+        assert_eq!(report.errors.len(), 1);
+        assert!(!report.is_valid());
+    }
+}
@@ -72,4 +72,5 @@ pub fn relative_time_from_now(date: &DateTime<Utc>) -> String {
     }
 }
 
-pub mod markdown; 
\ No newline at end of file
+pub mod markdown;
+pub mod url;
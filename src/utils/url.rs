@@ -0,0 +1,278 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::models::image::ImageManifest;
+use crate::models::config::Config;
+
+thread_local! {
+    /// 当前正在渲染的页面路径（相对于站点根目录），用于生成相对链接
+    static CURRENT_PATH: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 在渲染指定页面期间执行 `f`，期间的 URL 辅助函数以该页面为相对链接的起点
+pub fn with_current_path<R>(path: &str, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT_PATH.with(|current| current.replace(Some(path.to_string())));
+    let result = f();
+    CURRENT_PATH.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// 当前正在渲染的页面路径
+pub fn current_path() -> Option<String> {
+    CURRENT_PATH.with(|current| current.borrow().clone())
+}
+
+/// 站点 URL 生成器
+///
+/// 所有路径都以站点根目录为基准（如 `tags/rust/`），生成的链接会加上 `root`；
+/// 开启 `relative_link` 时生成相对于当前页面的链接，便于通过 file:// 直接浏览。
+#[derive(Debug, Clone)]
+pub struct UrlHelper {
+    /// 站点根目录，以 `/` 开头和结尾
+    root: String,
+    /// 站点地址，不以 `/` 结尾
+    url: String,
+    /// 是否生成相对链接
+    relative_link: bool,
+    /// 资源清单，原路径到带哈希的路径，所有副本共享
+    assets: Arc<RwLock<HashMap<String, String>>>,
+    /// 处理过的文章图片，所有副本共享
+    images: ImageManifest,
+}
+
+impl UrlHelper {
+    /// 根据站点配置创建
+    pub fn from_config(config: &Config) -> Self {
+        let root = config.root.clone().unwrap_or_else(|| "/".to_string());
+        let root = format!("/{}/", root.trim_matches('/')).replace("//", "/");
+        
+        Self {
+            root,
+            url: config.url.clone().unwrap_or_default().trim_end_matches('/').to_string(),
+            relative_link: config.relative_link.unwrap_or(false),
+            assets: Arc::default(),
+            images: ImageManifest::default(),
+        }
+    }
+    
+    /// 站点内链接，`from` 为当前页面路径，未指定时使用正在渲染的页面
+    pub fn url_for(&self, path: &str, from: Option<&str>) -> String {
+        if Self::is_external(path) {
+            return path.to_string();
+        }
+        
+        let path = path.trim_start_matches('/');
+        if self.relative_link {
+            if let Some(from) = from.map(str::to_string).or_else(current_path) {
+                return self.relative_url(&from, path);
+            }
+        }
+        
+        format!("{}{}", self.root, path)
+    }
+    
+    /// 页面链接，以 `index.html` 结尾的页面使用目录形式的链接（如 `posts/hello/`）
+    pub fn url_for_page(&self, path: &str) -> String {
+        self.url_for(path.strip_suffix("index.html").unwrap_or(path), None)
+    }
+    
+    /// 包含站点地址的完整链接
+    pub fn full_url_for(&self, path: &str) -> String {
+        if Self::is_external(path) {
+            return path.to_string();
+        }
+        
+        // `url` 可能已经包含了根目录
+        let root = self.root.trim_end_matches('/');
+        let base = if self.url.ends_with(root) {
+            self.url.clone()
+        } else {
+            format!("{}{}", self.url, root)
+        };
+        format!("{}/{}", base, path.trim_start_matches('/'))
+    }
+    
+    /// 从页面 `from` 指向 `to` 的相对链接，两者都是相对于站点根目录的路径
+    ///
+    /// 指向目录的链接会补全 `index.html`，使其在 file:// 下也能打开。
+    pub fn relative_url(&self, from: &str, to: &str) -> String {
+        let from = from.trim_start_matches('/');
+        let to = to.trim_start_matches('/');
+        
+        // `from` 所在的目录
+        let from_dir: Vec<&str> = match from.rfind('/') {
+            Some(idx) => from[..idx].split('/').filter(|s| !s.is_empty()).collect(),
+            None => Vec::new(),
+        };
+        let to_parts: Vec<&str> = to.split('/').collect();
+        
+        let common = from_dir.iter()
+            .zip(to_parts.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        
+        let mut parts: Vec<&str> = vec![".."; from_dir.len() - common];
+        parts.extend(&to_parts[common..]);
+        
+        let mut url = parts.join("/");
+        if url.is_empty() || url.ends_with('/') {
+            url.push_str("index.html");
+        }
+        url
+    }
+    
+    /// 标签页面链接
+    pub fn url_for_tag(&self, tag: &str) -> String {
+        self.url_for(&Self::tag_path(tag), None)
+    }
+    
+    /// 分类页面链接
+    pub fn url_for_category(&self, category: &str) -> String {
+        self.url_for(&Self::category_path(category), None)
+    }
+    
+    /// 主题或站点静态资源链接，资源清单中有对应的带哈希文件名时使用该文件名
+    pub fn asset_url(&self, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        match self.assets.read().unwrap().get(path) {
+            Some(fingerprinted) => self.url_for(fingerprinted, None),
+            None => self.url_for(path, None),
+        }
+    }
+    
+    /// 当前的资源清单
+    pub fn asset_manifest(&self) -> HashMap<String, String> {
+        self.assets.read().unwrap().clone()
+    }
+    
+    /// 替换资源清单，由资源处理阶段在渲染页面前调用，返回清单是否变化
+    pub fn set_asset_manifest(&self, manifest: HashMap<String, String>) -> bool {
+        let mut assets = self.assets.write().unwrap();
+        let changed = *assets != manifest;
+        *assets = manifest;
+        changed
+    }
+    
+    /// 处理过的文章图片，由图片处理阶段在渲染页面前更新
+    pub fn images(&self) -> &ImageManifest {
+        &self.images
+    }
+    
+    /// 图片链接，指定 `width` 时使用宽度不小于它的最小尺寸，图片没有处理过时返回原图链接
+    pub fn image_url(&self, path: &str, width: Option<u32>) -> String {
+        let from = current_path().unwrap_or_default();
+        let image = self.site_path(path, &from).and_then(|site_path| self.images.get(&site_path));
+        match (image, width) {
+            (Some(image), Some(width)) => self.url_for(image.closest(width), None),
+            _ => self.url_for(path, None),
+        }
+    }
+    
+    /// 页面中的链接对应的站点路径（相对于站点根目录），`from` 为链接所在页面的路径
+    ///
+    /// 外部链接返回 `None`，查询参数和锚点会被去掉。
+    pub fn site_path(&self, link: &str, from: &str) -> Option<String> {
+        if Self::is_external(link) {
+            return None;
+        }
+        let link = link.split(['?', '#']).next().unwrap_or_default();
+        
+        let joined = if let Some(path) = link.strip_prefix(self.root.as_str()) {
+            path.to_string()
+        } else if let Some(path) = link.strip_prefix('/') {
+            path.to_string()
+        } else {
+            let from_dir = from.trim_start_matches('/').rsplit_once('/').map(|(dir, _)| dir).unwrap_or_default();
+            format!("{}/{}", from_dir, link)
+        };
+        
+        let mut segments: Vec<&str> = Vec::new();
+        for segment in joined.split('/') {
+            match segment {
+                "" | "." => {},
+                ".." => { segments.pop(); },
+                segment => segments.push(segment),
+            }
+        }
+        Some(segments.join("/"))
+    }
+    
+    /// 标签页面相对于站点根目录的路径
+    pub fn tag_path(tag: &str) -> String {
+        format!("tags/{}/", Self::slug(tag))
+    }
+    
+    /// 分类页面相对于站点根目录的路径
+    pub fn category_path(category: &str) -> String {
+        format!("categories/{}/", Self::slug(category))
+    }
+    
+    /// 分类或标签的别名，用作页面的目录名和链接中的路径段
+    ///
+    /// 别名只包含小写字母、数字和 `-`，链接中无需再编码。名称中没有可转写的字符时（如只有符号）使用名称字节的十六进制表示。
+    pub fn slug(name: &str) -> String {
+        let slug = slug::slugify(name);
+        if slug.is_empty() {
+            name.bytes().map(|byte| format!("{:02x}", byte)).collect()
+        } else {
+            slug
+        }
+    }
+    
+    /// 是否为外部链接或锚点，这类链接保持不变
+    fn is_external(path: &str) -> bool {
+        path.contains("://")
+            || path.starts_with("//")
+            || path.starts_with('#')
+            || path.starts_with("mailto:")
+            || path.starts_with("data:")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn helper(root: &str, relative_link: bool) -> UrlHelper {
+        UrlHelper::from_config(&Config {
+            url: Some("https://example.com/blog/".to_string()),
+            root: Some(root.to_string()),
+            relative_link: Some(relative_link),
+            ..Config::default()
+        })
+    }
+    
+    #[test]
+    fn test_url_for_respects_root() {
+        let urls = helper("/blog/", false);
+        assert_eq!(urls.url_for("/css/style.css", None), "/blog/css/style.css");
+        assert_eq!(urls.url_for_tag("rust"), "/blog/tags/rust/");
+        assert_eq!(urls.url_for_category("Dev Notes"), "/blog/categories/dev-notes/");
+        assert_eq!(urls.url_for_tag("中文"), "/blog/tags/zhong-wen/");
+        assert_eq!(urls.url_for_tag("C# / ?"), "/blog/tags/c/");
+        assert_eq!(urls.url_for_tag("#?"), "/blog/tags/233f/");
+        assert_eq!(urls.url_for("https://cdn.example.com/a.js", None), "https://cdn.example.com/a.js");
+        assert_eq!(urls.full_url_for("posts/a.html"), "https://example.com/blog/posts/a.html");
+        
+        urls.clone().set_asset_manifest(HashMap::from([("css/style.css".to_string(), "css/style.3fa9c1.css".to_string())]));
+        assert_eq!(urls.asset_url("/css/style.css"), "/blog/css/style.3fa9c1.css");
+        
+        assert_eq!(urls.site_path("/blog/images/a.jpg?v=1", "index.html").as_deref(), Some("images/a.jpg"));
+        assert_eq!(urls.site_path("../images/b.png", "posts/hello/index.html").as_deref(), Some("posts/images/b.png"));
+        assert_eq!(urls.site_path("c.png", "posts/a.html").as_deref(), Some("posts/c.png"));
+        assert_eq!(urls.site_path("https://example.com/a.jpg", "index.html"), None);
+    }
+    
+    #[test]
+    fn test_relative_links() {
+        let urls = helper("/", true);
+        assert_eq!(urls.relative_url("tags/rust/index.html", "css/style.css"), "../../css/style.css");
+        assert_eq!(urls.relative_url("posts/a.html", "posts/b.html"), "b.html");
+        assert_eq!(urls.relative_url("index.html", ""), "index.html");
+        
+        let url = with_current_path("tags/rust/page/2/index.html", || urls.url_for_category("dev"));
+        assert_eq!(url, "../../../../categories/dev/index.html");
+        assert_eq!(urls.url_for("about/", None), "/about/");
+    }
+}